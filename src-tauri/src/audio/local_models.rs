@@ -1,51 +1,329 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
 use bzip2::read::BzDecoder;
 use futures::StreamExt;
-use serde::Serialize;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
 use specta::Type;
-use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager, Wry};
+use tauri::{path::BaseDirectory, AppHandle, Manager, Wry};
+use tauri_specta::Event;
 use tokio::fs as async_fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::{info, warn};
 
+use crate::disk;
+
 pub const PARAFORMER_MODEL_ID: &str = "sherpa-onnx-paraformer-zh-small-2024-03-09";
 pub const SENSEVOICE_MODEL_ID: &str = "sherpa-onnx-sense-voice-zh-en-ja-ko-yue-int8-2025-09-09";
 
 pub const DEFAULT_MODEL_ID: &str = PARAFORMER_MODEL_ID;
 
-struct LocalModelSpec {
-    id: &'static str,
-    title: &'static str,
-    archive_url: &'static str,
-    required_files: &'static [(&'static str, &'static str)],
-}
-
-const LOCAL_MODEL_SPECS: &[LocalModelSpec] = &[
-    LocalModelSpec {
-        id: PARAFORMER_MODEL_ID,
-        title: "Paraformer 小尺寸离线识别",
-        archive_url: "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-paraformer-zh-small-2024-03-09.tar.bz2",
-        required_files: &[
-            ("model.int8.onnx", "ASR 模型文件"),
-            ("tokens.txt", "词表文件"),
-        ],
-    },
-    LocalModelSpec {
-        id: SENSEVOICE_MODEL_ID,
-        title: "SenseVoice 多语种离线识别",
-        archive_url: "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-sense-voice-zh-en-ja-ko-yue-int8-2025-09-09.tar.bz2",
-        required_files: &[
-            ("model.int8.onnx", "ASR 模型文件"),
-            ("tokens.txt", "词表文件"),
-        ],
-    },
-];
-
-fn get_spec(model_id: &str) -> Option<&'static LocalModelSpec> {
-    LOCAL_MODEL_SPECS.iter().find(|spec| spec.id == model_id)
+const MODEL_MANIFEST_PATH: &str = "models/manifest.json";
+/// 暂存目录名为 `.<UUID>.staging-<模型 ID>`，模型 ID 可能含有 `.` 与 `-`，只能按该分隔符切分
+const STAGING_DIR_SEPARATOR: &str = ".staging-";
+
+/// 离线识别引擎类型，决定使用哪种 sherpa-onnx 识别器加载模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LocalModelEngine {
+    Paraformer,
+    SenseVoice,
+}
+
+/// 识别器参数，未配置的字段沿用内置默认值
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecognizerOptions {
+    #[serde(default = "default_model_file")]
+    pub model_file: String,
+    #[serde(default = "default_tokens_file")]
+    pub tokens_file: String,
+    #[serde(default)]
+    pub num_threads: Option<i32>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub use_itn: Option<bool>,
+}
+
+impl Default for RecognizerOptions {
+    fn default() -> Self {
+        Self {
+            model_file: default_model_file(),
+            tokens_file: default_tokens_file(),
+            num_threads: None,
+            provider: None,
+            language: None,
+            use_itn: None,
+        }
+    }
+}
+
+fn default_model_file() -> String {
+    "model.int8.onnx".to_string()
+}
+
+fn default_tokens_file() -> String {
+    "tokens.txt".to_string()
+}
+
+#[derive(Debug, Clone)]
+enum LocalModelSource {
+    /// 从远程 `.tar.bz2` 归档下载并解压到模型目录
+    Archive(String),
+    /// 直接使用本地已有的模型目录，无需下载
+    Directory(PathBuf),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequiredFile {
+    file: String,
+    #[serde(default = "default_required_file_description")]
+    description: String,
+}
+
+fn default_required_file_description() -> String {
+    "模型文件".to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalModelSpec {
+    pub id: String,
+    pub title: String,
+    pub size: Option<String>,
+    pub engine: LocalModelEngine,
+    pub options: RecognizerOptions,
+    source: LocalModelSource,
+    required_files: Vec<RequiredFile>,
+    builtin: bool,
+}
+
+impl LocalModelSpec {
+    fn builtin(id: &str, title: &str, engine: LocalModelEngine, archive_url: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            size: None,
+            engine,
+            options: RecognizerOptions::default(),
+            source: LocalModelSource::Archive(archive_url.to_string()),
+            required_files: vec![
+                RequiredFile {
+                    file: "model.int8.onnx".to_string(),
+                    description: "ASR 模型文件".to_string(),
+                },
+                RequiredFile {
+                    file: "tokens.txt".to_string(),
+                    description: "词表文件".to_string(),
+                },
+            ],
+            builtin: true,
+        }
+    }
+
+    fn model_dir(&self, root: &Path) -> PathBuf {
+        match &self.source {
+            LocalModelSource::Directory(path) => path.clone(),
+            LocalModelSource::Archive(_) => root.join(&self.id),
+        }
+    }
+}
+
+fn builtin_specs() -> Vec<LocalModelSpec> {
+    vec![
+        LocalModelSpec::builtin(
+            PARAFORMER_MODEL_ID,
+            "Paraformer 小尺寸离线识别",
+            LocalModelEngine::Paraformer,
+            "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-paraformer-zh-small-2024-03-09.tar.bz2",
+        ),
+        LocalModelSpec::builtin(
+            SENSEVOICE_MODEL_ID,
+            "SenseVoice 多语种离线识别",
+            LocalModelEngine::SenseVoice,
+            "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-sense-voice-zh-en-ja-ko-yue-int8-2025-09-09.tar.bz2",
+        ),
+    ]
+}
+
+/// `models/manifest.json` 的文件结构
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelManifest {
+    #[serde(default)]
+    models: Vec<ManifestModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestModelEntry {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    engine: LocalModelEngine,
+    #[serde(default)]
+    archive_url: Option<String>,
+    #[serde(default)]
+    local_path: Option<PathBuf>,
+    #[serde(default)]
+    size: Option<String>,
+    #[serde(default)]
+    required_files: Vec<RequiredFile>,
+    #[serde(default)]
+    options: RecognizerOptions,
+}
+
+impl ManifestModelEntry {
+    fn into_spec(self) -> Result<LocalModelSpec> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() || id.contains(['/', '\\']) || id == "." || id == ".." {
+            return Err(anyhow!("模型 ID 无效: {:?}", self.id));
+        }
+
+        let source = match (self.archive_url, self.local_path) {
+            (Some(url), None) if !url.trim().is_empty() => {
+                LocalModelSource::Archive(url.trim().to_string())
+            }
+            (None, Some(path)) if path.is_absolute() => LocalModelSource::Directory(path),
+            (None, Some(path)) => {
+                return Err(anyhow!(
+                    "模型 {id} 的 localPath 必须为绝对路径: {}",
+                    path.display()
+                ))
+            }
+            _ => {
+                return Err(anyhow!(
+                    "模型 {id} 需要且只能配置 archiveUrl 或 localPath 之一"
+                ))
+            }
+        };
+
+        let required_files = if self.required_files.is_empty() {
+            vec![
+                RequiredFile {
+                    file: self.options.model_file.clone(),
+                    description: "ASR 模型文件".to_string(),
+                },
+                RequiredFile {
+                    file: self.options.tokens_file.clone(),
+                    description: "词表文件".to_string(),
+                },
+            ]
+        } else {
+            self.required_files
+        };
+
+        Ok(LocalModelSpec {
+            title: self
+                .title
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| id.clone()),
+            id,
+            size: self.size,
+            engine: self.engine,
+            options: self.options,
+            source,
+            required_files,
+            builtin: false,
+        })
+    }
+}
+
+static LOCAL_MODEL_SPECS: OnceCell<Vec<LocalModelSpec>> = OnceCell::new();
+
+/// 读取应用数据目录下的模型清单并与内置模型合并，同时清理残留的安装暂存目录；应在启动时调用一次
+pub fn init(app: &AppHandle<Wry>) {
+    let mut specs = builtin_specs();
+    match load_manifest(app) {
+        Ok(entries) => {
+            for entry in entries {
+                let entry_id = entry.id.clone();
+                match entry.into_spec() {
+                    Ok(spec) if specs.iter().any(|existing| existing.id == spec.id) => {
+                        warn!(
+                            target = "miaoyu_audio",
+                            model = %spec.id,
+                            "模型清单中的 ID 与已有模型重复，已忽略"
+                        );
+                    }
+                    Ok(spec) => {
+                        info!(
+                            target = "miaoyu_audio",
+                            model = %spec.id,
+                            engine = ?spec.engine,
+                            "加载自定义离线模型"
+                        );
+                        specs.push(spec);
+                    }
+                    Err(error) => {
+                        warn!(
+                            target = "miaoyu_audio",
+                            model = %entry_id,
+                            error = %error,
+                            "模型清单条目无效，已忽略"
+                        );
+                    }
+                }
+            }
+        }
+        Err(error) => {
+            warn!(
+                target = "miaoyu_audio",
+                error = %error,
+                "读取模型清单失败，仅使用内置模型"
+            );
+        }
+    }
+    LOCAL_MODEL_SPECS.set(specs).ok();
+
+    match models_root(app) {
+        Ok(root) => cleanup_staging_dirs(&root),
+        Err(error) => {
+            warn!(
+                target = "miaoyu_audio",
+                error = %error,
+                "无法定位模型目录，跳过暂存目录清理"
+            );
+        }
+    }
+}
+
+fn load_manifest(app: &AppHandle<Wry>) -> Result<Vec<ManifestModelEntry>> {
+    let path = app
+        .path()
+        .resolve(MODEL_MANIFEST_PATH, BaseDirectory::AppData)
+        .map_err(|err| anyhow!("无法定位模型清单: {err}"))?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("无法读取模型清单: {}", path.display()))?;
+    let manifest: ModelManifest = serde_json::from_str(&content)
+        .with_context(|| format!("模型清单格式错误: {}", path.display()))?;
+    Ok(manifest.models)
+}
+
+fn specs() -> &'static [LocalModelSpec] {
+    LOCAL_MODEL_SPECS.get_or_init(builtin_specs)
+}
+
+pub fn get_spec(model_id: &str) -> Option<&'static LocalModelSpec> {
+    specs().iter().find(|spec| spec.id == model_id)
+}
+
+/// 模型清单中声明的自定义模型（不含内置模型）
+pub fn custom_specs() -> impl Iterator<Item = &'static LocalModelSpec> {
+    specs().iter().filter(|spec| !spec.builtin)
 }
 
 #[derive(Debug, Clone, Serialize, Type)]
@@ -67,12 +345,114 @@ pub struct OfflineAsrModelStatus {
     pub install_dir: String,
 }
 
-#[derive(Debug, Clone, Serialize, Type)]
+#[derive(Serialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OfflineModelDownloadStage {
+    Started,
+    Downloading,
+    Verifying,
+    Extracting,
+    Installed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Type, tauri_specta::Event, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct OfflineModelDownloadProgress {
+pub struct OnOfflineModelDownload {
     pub model_id: String,
-    pub received_bytes: u64,
-    pub total_bytes: Option<u64>,
+    pub stage: OfflineModelDownloadStage,
+    /// 字节数用浮点表示，前端按 number 处理，超过 4 GiB 也不会溢出
+    pub received_bytes: f64,
+    pub total_bytes: Option<f64>,
+    pub bytes_per_second: Option<f64>,
+    pub eta_seconds: Option<u32>,
+    pub error: Option<String>,
+}
+
+impl OnOfflineModelDownload {
+    fn new(model_id: &str, stage: OfflineModelDownloadStage) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            stage,
+            received_bytes: 0.0,
+            total_bytes: None,
+            bytes_per_second: None,
+            eta_seconds: None,
+            error: None,
+        }
+    }
+}
+
+const EXTRACTION_OVERHEAD_FACTOR: u64 = 2;
+const INSTALL_SPACE_MARGIN_BYTES: u64 = 64 * 1024 * 1024;
+/// 服务器未返回文件大小、清单也未声明大小时，按此估算归档体积
+const UNKNOWN_ARCHIVE_SIZE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// 下载进度事件的最小间隔，避免每个网络分块都向前端推送
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+static ACTIVE_DOWNLOADS: Lazy<StdMutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+#[derive(Debug)]
+struct DownloadCancelled;
+
+impl std::fmt::Display for DownloadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "下载已取消")
+    }
+}
+
+impl std::error::Error for DownloadCancelled {}
+
+/// 正在进行的下载任务；同一模型同时只允许一个任务，析构时自动释放
+struct DownloadTask {
+    app: AppHandle,
+    model_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl DownloadTask {
+    fn begin(app: &AppHandle, spec: &LocalModelSpec) -> Result<Self, String> {
+        let mut active = ACTIVE_DOWNLOADS
+            .lock()
+            .map_err(|_| "下载任务状态不可用".to_string())?;
+        if active.contains_key(&spec.id) {
+            return Err(format!("{} 正在下载中，请勿重复操作", spec.title));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        active.insert(spec.id.clone(), Arc::clone(&cancelled));
+        Ok(Self {
+            app: app.clone(),
+            model_id: spec.id.clone(),
+            cancelled,
+        })
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(DownloadCancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn emit(&self, event: OnOfflineModelDownload) {
+        event.emit(&self.app).ok();
+    }
+
+    fn emit_stage(&self, stage: OfflineModelDownloadStage) {
+        self.emit(OnOfflineModelDownload::new(&self.model_id, stage));
+    }
+}
+
+impl Drop for DownloadTask {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_DOWNLOADS.lock() {
+            active.remove(&self.model_id);
+        }
+    }
 }
 
 #[tauri::command]
@@ -107,24 +487,56 @@ pub async fn download_offline_models(
     model_id: String,
 ) -> Result<OfflineModelsStatus, String> {
     let spec = get_spec(&model_id).ok_or_else(|| format!("不支持的离线模型: {model_id}"))?;
+    if matches!(spec.source, LocalModelSource::Directory(_)) {
+        return Err(format!("{} 为本地目录模型，无需下载", spec.title));
+    }
 
-    let models_dir = models_root(&app).map_err(|err| err.to_string())?;
-    if let Err(error) = download_and_extract(&app, spec, &models_dir).await {
-        warn!(
-            target = "miaoyu_audio",
-            error = %error,
-            "下载离线模型失败"
-        );
+    let task = DownloadTask::begin(&app, spec)?;
+    task.emit_stage(OfflineModelDownloadStage::Started);
+
+    let result = match models_root(&app) {
+        Ok(models_dir) => download_and_extract(&task, spec, &models_dir).await,
+        Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+        if error.downcast_ref::<DownloadCancelled>().is_some() {
+            info!(target = "miaoyu_audio", model = %spec.id, "离线模型下载已取消");
+            task.emit_stage(OfflineModelDownloadStage::Cancelled);
+        } else {
+            warn!(
+                target = "miaoyu_audio",
+                error = %error,
+                "下载离线模型失败"
+            );
+            let mut event =
+                OnOfflineModelDownload::new(&spec.id, OfflineModelDownloadStage::Failed);
+            event.error = Some(error.to_string());
+            task.emit(event);
+        }
         return Err(error.to_string());
     }
 
+    task.emit_stage(OfflineModelDownloadStage::Installed);
     status(&app).map_err(|err| err.to_string())
 }
 
+/// 取消正在进行的离线模型下载；没有对应任务时不做任何操作
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_offline_model_download(model_id: String) -> Result<(), String> {
+    let active = ACTIVE_DOWNLOADS
+        .lock()
+        .map_err(|_| "下载任务状态不可用".to_string())?;
+    if let Some(cancelled) = active.get(&model_id) {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
 pub fn resolve_model_file(app: &AppHandle<Wry>, model_id: &str, relative: &str) -> Result<PathBuf> {
     let spec = get_spec(model_id).ok_or_else(|| anyhow!("未知离线模型: {model_id}"))?;
     let root = models_root(app)?;
-    let target = root.join(spec.id).join(relative);
+    let target = spec.model_dir(&root).join(relative);
     if target.exists() {
         Ok(target)
     } else {
@@ -149,7 +561,7 @@ fn status(app: &AppHandle<Wry>) -> Result<OfflineModelsStatus> {
     let root = models_root(app)?;
     let mut models = Vec::new();
     let mut missing_all = Vec::new();
-    for spec in LOCAL_MODEL_SPECS {
+    for spec in specs() {
         let model_status = status_for_spec(&root, spec);
         if !model_status.ready {
             missing_all.extend(model_status.missing_files.iter().cloned());
@@ -166,18 +578,21 @@ fn status(app: &AppHandle<Wry>) -> Result<OfflineModelsStatus> {
 }
 
 fn status_for_spec(root: &Path, spec: &LocalModelSpec) -> OfflineAsrModelStatus {
-    let model_dir = root.join(spec.id);
+    let model_dir = spec.model_dir(root);
     let mut missing_files = Vec::new();
-    for (file, description) in spec.required_files {
-        let path = model_dir.join(file);
+    for required in &spec.required_files {
+        let path = model_dir.join(&required.file);
         if !path.exists() {
-            missing_files.push(format!("{}（{}/{}）", description, spec.id, file));
+            missing_files.push(format!(
+                "{}（{}/{}）",
+                required.description, spec.id, required.file
+            ));
         }
     }
 
     OfflineAsrModelStatus {
-        id: spec.id.to_string(),
-        title: spec.title.to_string(),
+        id: spec.id.clone(),
+        title: spec.title.clone(),
         ready: missing_files.is_empty(),
         missing_files,
         install_dir: model_dir.display().to_string(),
@@ -185,31 +600,172 @@ fn status_for_spec(root: &Path, spec: &LocalModelSpec) -> OfflineAsrModelStatus
 }
 
 async fn download_and_extract(
-    app: &AppHandle,
+    task: &DownloadTask,
     spec: &LocalModelSpec,
     models_dir: &Path,
 ) -> Result<()> {
+    let LocalModelSource::Archive(archive_url) = &spec.source else {
+        return Err(anyhow!("{} 没有可下载的归档地址", spec.title));
+    };
     info!(
         target = "miaoyu_audio",
-        model = spec.id,
+        model = %spec.id,
         "开始下载 {} 离线模型",
         spec.title
     );
-    let temp_dir = std::env::temp_dir().join(format!("miaoyu-model-{}", uuid::Uuid::new_v4()));
-    async_fs::create_dir_all(&temp_dir).await?;
-    let archive_path = temp_dir.join(format!("{}.tar.bz2", spec.id));
-    download_file(app, spec.id, spec.archive_url, &archive_path).await?;
-    extract_tar_bz2(&archive_path, &temp_dir).await?;
-    copy_model_contents(spec, &temp_dir, models_dir).await?;
-    async_fs::remove_dir_all(&temp_dir).await.ok();
+    // 在模型目录内暂存，保证最终 rename 位于同一文件系统，可原子替换
+    let staging_dir = models_dir.join(staging_dir_name(
+        &spec.id,
+        &uuid::Uuid::new_v4().to_string(),
+    ));
+    async_fs::create_dir_all(&staging_dir).await?;
+    let result = stage_and_install(task, spec, archive_url, &staging_dir, models_dir).await;
+    if let Err(error) = async_fs::remove_dir_all(&staging_dir).await {
+        if error.kind() != std::io::ErrorKind::NotFound {
+            warn!(
+                target = "miaoyu_audio",
+                error = %error,
+                dir = %staging_dir.display(),
+                "清理模型暂存目录失败"
+            );
+        }
+    }
+    result
+}
+
+async fn stage_and_install(
+    task: &DownloadTask,
+    spec: &LocalModelSpec,
+    archive_url: &str,
+    staging_dir: &Path,
+    models_dir: &Path,
+) -> Result<()> {
+    let archive_path = staging_dir.join(format!("{}.tar.bz2", spec.id));
+    let declared_size = spec.size.as_deref().and_then(parse_size_label);
+    download_file(task, archive_url, &archive_path, declared_size).await?;
+
+    task.check_cancelled()?;
+    task.emit_stage(OfflineModelDownloadStage::Verifying);
+    verify_archive(&archive_path).await?;
+
+    task.check_cancelled()?;
+    task.emit_stage(OfflineModelDownloadStage::Extracting);
+    let extract_dir = staging_dir.join("extracted");
+    extract_tar_bz2(&archive_path, &extract_dir).await?;
+    async_fs::remove_file(&archive_path).await.ok();
+
+    let staged_model =
+        find_model_dir(&extract_dir, spec).ok_or_else(|| anyhow!("归档中缺少 {} 目录", spec.id))?;
+    let missing: Vec<_> = spec
+        .required_files
+        .iter()
+        .filter(|required| !staged_model.join(&required.file).is_file())
+        .map(|required| required.file.clone())
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "{} 归档不完整，缺少文件：{}",
+            spec.title,
+            missing.join("，")
+        ));
+    }
+
+    task.check_cancelled()?;
+    let destination = models_dir.join(&spec.id);
+    let backup = staging_dir.join("previous");
+    tokio::task::spawn_blocking(move || swap_model_dir(&staged_model, &destination, &backup))
+        .await??;
+    // 已加载的识别器仍持有旧模型，重新安装或更新后需要重新加载
+    super::transcribing::evict_recognizer(&spec.id);
+    Ok(())
+}
+
+/// 用暂存目录替换已安装的模型目录；旧版本先移入 `backup`，替换失败时还原
+fn swap_model_dir(staged: &Path, destination: &Path, backup: &Path) -> Result<()> {
+    let had_previous = destination.exists();
+    if had_previous {
+        fs::rename(destination, backup)
+            .with_context(|| format!("无法备份旧模型目录: {}", destination.display()))?;
+    }
+    if let Err(error) = fs::rename(staged, destination) {
+        if had_previous {
+            if let Err(restore_error) = fs::rename(backup, destination) {
+                warn!(
+                    target = "miaoyu_audio",
+                    error = %restore_error,
+                    dir = %destination.display(),
+                    "还原旧模型目录失败"
+                );
+            }
+        }
+        return Err(anyhow!(
+            "无法安装模型目录 {}: {error}",
+            destination.display()
+        ));
+    }
     Ok(())
 }
 
+fn staging_dir_name(model_id: &str, token: &str) -> String {
+    format!(".{token}{STAGING_DIR_SEPARATOR}{model_id}")
+}
+
+/// 从暂存目录名中取出模型 ID，不是暂存目录时返回 `None`
+fn staged_model_id(dir_name: &str) -> Option<&str> {
+    let (token, model_id) = dir_name
+        .strip_prefix('.')?
+        .split_once(STAGING_DIR_SEPARATOR)?;
+    (!token.is_empty() && !model_id.is_empty()).then_some(model_id)
+}
+
+/// 清理上次中断安装留下的暂存目录；若旧模型已被移入暂存目录但未完成替换，则先还原
+fn cleanup_staging_dirs(models_dir: &Path) {
+    let Ok(entries) = fs::read_dir(models_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let Some(model_id) = staged_model_id(name) else {
+            continue;
+        };
+        let path = entry.path();
+        let backup = path.join("previous");
+        let destination = models_dir.join(model_id);
+        if backup.is_dir() && !destination.exists() {
+            match fs::rename(&backup, &destination) {
+                Ok(()) => info!(
+                    target = "miaoyu_audio",
+                    model = model_id,
+                    "已从中断的安装中还原旧模型"
+                ),
+                Err(error) => warn!(
+                    target = "miaoyu_audio",
+                    error = %error,
+                    model = model_id,
+                    "还原旧模型目录失败"
+                ),
+            }
+        }
+        if let Err(error) = fs::remove_dir_all(&path) {
+            warn!(
+                target = "miaoyu_audio",
+                error = %error,
+                dir = %path.display(),
+                "清理残留的模型暂存目录失败"
+            );
+        }
+    }
+}
+
+/// `declared_size` 为清单中声明的大小，服务器未返回 Content-Length 时用于检查磁盘空间
 async fn download_file(
-    app: &AppHandle,
-    model_id: &str,
+    task: &DownloadTask,
     url: &str,
     destination: &Path,
+    declared_size: Option<u64>,
 ) -> Result<()> {
     if let Some(parent) = destination.parent() {
         async_fs::create_dir_all(parent).await?;
@@ -219,19 +775,125 @@ async fn download_file(
         .send()
         .await?
         .error_for_status()?;
-    let mut file = async_fs::File::create(destination).await?;
     let total = response.content_length();
+    let expected = total
+        .or(declared_size)
+        .unwrap_or(UNKNOWN_ARCHIVE_SIZE_BYTES);
+    let target = destination.parent().unwrap_or(destination);
+    disk::ensure_available_space(target, required_install_space(expected), "下载并解压模型")
+        .map_err(|e| anyhow!(e))?;
+    let mut file = async_fs::File::create(destination).await?;
     let mut stream = response.bytes_stream();
-    let mut downloaded = 0u64;
-    emit_download_progress(app, model_id, downloaded, total);
+    let mut meter = DownloadMeter::new(total);
+    task.emit(meter.event(&task.model_id));
     while let Some(chunk) = stream.next().await {
+        task.check_cancelled()?;
         let data = chunk?;
         file.write_all(&data).await?;
-        downloaded = downloaded.saturating_add(data.len() as u64);
-        emit_download_progress(app, model_id, downloaded, total);
+        if meter.advance(data.len() as u64) {
+            task.emit(meter.event(&task.model_id));
+        }
     }
     file.flush().await?;
-    emit_download_progress(app, model_id, downloaded, total);
+    task.emit(meter.event(&task.model_id));
+
+    if let Some(expected) = total {
+        if meter.received != expected {
+            return Err(anyhow!(
+                "下载不完整：已接收 {} 字节，预期 {} 字节",
+                meter.received,
+                expected
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 统计下载速度与剩余时间，并按固定间隔节流进度事件
+struct DownloadMeter {
+    started_at: Instant,
+    last_emit: Instant,
+    received: u64,
+    total: Option<u64>,
+}
+
+impl DownloadMeter {
+    fn new(total: Option<u64>) -> Self {
+        let now = Instant::now();
+        Self {
+            started_at: now,
+            last_emit: now,
+            received: 0,
+            total,
+        }
+    }
+
+    /// 累加已接收字节数，返回是否应当推送一次进度事件
+    fn advance(&mut self, bytes: u64) -> bool {
+        self.received = self.received.saturating_add(bytes);
+        if self.last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+            self.last_emit = Instant::now();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn event(&self, model_id: &str) -> OnOfflineModelDownload {
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        let bytes_per_second =
+            (elapsed > 0.0 && self.received > 0).then(|| self.received as f64 / elapsed);
+        let eta_seconds = match (self.total, bytes_per_second) {
+            (Some(total), Some(speed)) if speed > 0.0 => {
+                let remaining = total.saturating_sub(self.received) as f64;
+                Some((remaining / speed).ceil().min(u32::MAX as f64) as u32)
+            }
+            _ => None,
+        };
+        OnOfflineModelDownload {
+            received_bytes: self.received as f64,
+            total_bytes: self.total.map(|total| total as f64),
+            bytes_per_second,
+            eta_seconds,
+            ..OnOfflineModelDownload::new(model_id, OfflineModelDownloadStage::Downloading)
+        }
+    }
+}
+
+/// 估算安装所需空间：归档本身 + 解压后的模型文件（按归档的两倍估算）+ 固定余量
+fn required_install_space(archive_bytes: u64) -> u64 {
+    archive_bytes
+        .saturating_mul(1 + EXTRACTION_OVERHEAD_FACTOR)
+        .saturating_add(INSTALL_SPACE_MARGIN_BYTES)
+}
+
+/// 解析清单中的大小说明，如 "220 MB"、"1.5GB"；无法识别时返回 `None`
+fn parse_size_label(label: &str) -> Option<u64> {
+    let label = label.trim();
+    let split = label
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(label.len());
+    let value: f64 = label[..split].parse().ok()?;
+    let multiplier = match label[split..].trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1u64,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return None,
+    };
+    (value.is_finite() && value > 0.0).then_some((value * multiplier as f64) as u64)
+}
+
+/// 解压前确认归档为 bzip2 格式，避免把错误页面等内容当作模型解压
+async fn verify_archive(archive_path: &Path) -> Result<()> {
+    let mut header = [0u8; 3];
+    let mut file = async_fs::File::open(archive_path).await?;
+    file.read_exact(&mut header)
+        .await
+        .map_err(|_| anyhow!("下载的模型归档为空或已损坏"))?;
+    if &header != b"BZh" {
+        return Err(anyhow!("下载的模型归档不是有效的 .tar.bz2 文件"));
+    }
     Ok(())
 }
 
@@ -249,38 +911,16 @@ async fn extract_tar_bz2(archive_path: &Path, destination: &Path) -> Result<()>
     Ok(())
 }
 
-async fn copy_model_contents(
-    spec: &LocalModelSpec,
-    temp_root: &Path,
-    models_root: &Path,
-) -> Result<()> {
-    let source =
-        find_model_dir(temp_root, spec).ok_or_else(|| anyhow!("归档中缺少 {} 目录", spec.id))?;
-    let destination = models_root.join(spec.id);
-    tokio::task::spawn_blocking(move || {
-        if destination.exists() {
-            fs::remove_dir_all(&destination)?;
-        }
-        fs::create_dir_all(&destination)?;
-        for entry in fs::read_dir(&source)? {
-            let entry = entry?;
-            let target = destination.join(entry.file_name());
-            if entry.file_type()?.is_dir() {
-                copy_dir_recursive(entry.path(), target)?;
-            } else {
-                fs::copy(entry.path(), target)?;
-            }
-        }
-        Result::<_, anyhow::Error>::Ok(())
-    })
-    .await??;
-    Ok(())
-}
-
 fn find_model_dir(root: &Path, spec: &LocalModelSpec) -> Option<PathBuf> {
     let mut stack = vec![root.to_path_buf()];
     while let Some(path) = stack.pop() {
-        if path.file_name().and_then(|n| n.to_str()) == Some(spec.id) {
+        // 内置模型归档的顶层目录与模型 ID 同名；自定义归档则按所需文件定位
+        if path.file_name().and_then(|n| n.to_str()) == Some(spec.id.as_str())
+            || spec
+                .required_files
+                .iter()
+                .all(|required| path.join(&required.file).exists())
+        {
             return Some(path);
         }
         if let Ok(entries) = fs::read_dir(&path) {
@@ -296,28 +936,247 @@ fn find_model_dir(root: &Path, spec: &LocalModelSpec) -> Option<PathBuf> {
     None
 }
 
-fn copy_dir_recursive(src: PathBuf, dest: PathBuf) -> Result<()> {
-    if dest.exists() {
-        fs::remove_dir_all(&dest)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_entry(value: serde_json::Value) -> serde_json::Result<ManifestModelEntry> {
+        serde_json::from_value(value)
     }
-    fs::create_dir_all(&dest)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let target = dest.join(entry.file_name());
-        if entry.file_type()?.is_dir() {
-            copy_dir_recursive(entry.path(), target)?;
-        } else {
-            fs::copy(entry.path(), target)?;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("miaoyu-models-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
         }
     }
-    Ok(())
-}
 
-fn emit_download_progress(app: &AppHandle, model_id: &str, received: u64, total: Option<u64>) {
-    let payload = OfflineModelDownloadProgress {
-        model_id: model_id.to_string(),
-        received_bytes: received,
-        total_bytes: total,
-    };
-    app.emit("offline-model-download-progress", payload).ok();
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_marker(dir: &Path, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("model.onnx"), content).unwrap();
+    }
+
+    fn read_marker(dir: &Path) -> String {
+        fs::read_to_string(dir.join("model.onnx")).unwrap()
+    }
+
+    #[test]
+    fn staged_model_id_splits_on_separator() {
+        let token = uuid::Uuid::new_v4().to_string();
+        for id in ["paraformer-zh", "sense-voice.v2", "a.staging-b"] {
+            assert_eq!(staged_model_id(&staging_dir_name(id, &token)), Some(id));
+        }
+        assert_eq!(staged_model_id("paraformer-zh"), None);
+        assert_eq!(staged_model_id(".staging-paraformer-zh"), None);
+        assert_eq!(staged_model_id(".abc.staging-"), None);
+    }
+
+    #[test]
+    fn swap_installs_fresh_model() {
+        let root = TempDir::new();
+        let staged = root.0.join("staged");
+        let destination = root.0.join("model");
+        write_marker(&staged, "new");
+
+        swap_model_dir(&staged, &destination, &staged.join("previous")).unwrap();
+
+        assert_eq!(read_marker(&destination), "new");
+        assert!(!staged.exists());
+    }
+
+    #[test]
+    fn swap_keeps_previous_model_as_backup() {
+        let root = TempDir::new();
+        let staging = root.0.join("staging");
+        let staged = staging.join("model");
+        let backup = staging.join("previous");
+        let destination = root.0.join("model");
+        write_marker(&staged, "new");
+        write_marker(&destination, "old");
+
+        swap_model_dir(&staged, &destination, &backup).unwrap();
+
+        assert_eq!(read_marker(&destination), "new");
+        assert_eq!(read_marker(&backup), "old");
+    }
+
+    #[test]
+    fn swap_rolls_back_when_install_fails() {
+        let root = TempDir::new();
+        let staging = root.0.join("staging");
+        let backup = staging.join("previous");
+        let destination = root.0.join("model");
+        fs::create_dir_all(&staging).unwrap();
+        write_marker(&destination, "old");
+
+        let result = swap_model_dir(&staging.join("missing"), &destination, &backup);
+
+        assert!(result.is_err());
+        assert_eq!(read_marker(&destination), "old");
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn cleanup_restores_interrupted_install() {
+        let root = TempDir::new();
+        let staging = root.0.join(staging_dir_name("sense-voice.v2", "token"));
+        write_marker(&staging.join("previous"), "old");
+        write_marker(&staging.join("model"), "partial");
+
+        cleanup_staging_dirs(&root.0);
+
+        assert_eq!(read_marker(&root.0.join("sense-voice.v2")), "old");
+        assert!(!staging.exists());
+    }
+
+    #[test]
+    fn cleanup_keeps_installed_model() {
+        let root = TempDir::new();
+        let staging = root.0.join(staging_dir_name("paraformer-zh", "token"));
+        let destination = root.0.join("paraformer-zh");
+        write_marker(&staging.join("previous"), "old");
+        write_marker(&destination, "new");
+        write_marker(&root.0.join("other-model"), "other");
+
+        cleanup_staging_dirs(&root.0);
+
+        assert_eq!(read_marker(&destination), "new");
+        assert_eq!(read_marker(&root.0.join("other-model")), "other");
+        assert!(!staging.exists());
+    }
+
+    #[test]
+    fn manifest_archive_entry_uses_defaults() {
+        let manifest: ModelManifest = serde_json::from_value(json!({
+            "models": [{
+                "id": " custom-paraformer ",
+                "engine": "paraformer",
+                "archiveUrl": " https://example.com/model.tar.bz2 ",
+            }]
+        }))
+        .unwrap();
+        let spec = manifest
+            .models
+            .into_iter()
+            .next()
+            .unwrap()
+            .into_spec()
+            .unwrap();
+
+        assert_eq!(spec.id, "custom-paraformer");
+        assert_eq!(spec.title, "custom-paraformer");
+        assert_eq!(spec.engine, LocalModelEngine::Paraformer);
+        assert!(!spec.builtin);
+        assert!(matches!(
+            &spec.source,
+            LocalModelSource::Archive(url) if url == "https://example.com/model.tar.bz2"
+        ));
+        let files: Vec<_> = spec
+            .required_files
+            .iter()
+            .map(|f| f.file.as_str())
+            .collect();
+        assert_eq!(files, ["model.int8.onnx", "tokens.txt"]);
+        assert_eq!(
+            spec.model_dir(Path::new("root")),
+            Path::new("root").join(&spec.id)
+        );
+    }
+
+    #[test]
+    fn manifest_directory_entry_keeps_options() {
+        let dir = std::env::temp_dir().join("miaoyu-sensevoice");
+        let spec = parse_entry(json!({
+            "id": "my-sensevoice",
+            "title": "我的 SenseVoice",
+            "engine": "senseVoice",
+            "localPath": dir,
+            "size": "220 MB",
+            "requiredFiles": [{ "file": "model.onnx" }],
+            "options": { "modelFile": "model.onnx", "language": "zh", "useItn": true },
+        }))
+        .unwrap()
+        .into_spec()
+        .unwrap();
+
+        assert_eq!(spec.title, "我的 SenseVoice");
+        assert_eq!(spec.engine, LocalModelEngine::SenseVoice);
+        assert_eq!(spec.size.as_deref(), Some("220 MB"));
+        assert!(matches!(&spec.source, LocalModelSource::Directory(path) if *path == dir));
+        assert_eq!(spec.model_dir(Path::new("root")), dir);
+        assert_eq!(spec.required_files.len(), 1);
+        assert_eq!(
+            spec.required_files[0].description,
+            default_required_file_description()
+        );
+        assert_eq!(spec.options.model_file, "model.onnx");
+        assert_eq!(spec.options.tokens_file, "tokens.txt");
+        assert_eq!(spec.options.language.as_deref(), Some("zh"));
+        assert_eq!(spec.options.use_itn, Some(true));
+    }
+
+    #[test]
+    fn manifest_rejects_invalid_sources() {
+        let dir = std::env::temp_dir();
+        let cases = [
+            // 未配置来源
+            json!({ "id": "a", "engine": "paraformer" }),
+            // 同时配置两种来源
+            json!({ "id": "a", "engine": "paraformer", "archiveUrl": "https://example.com/a.tar.bz2", "localPath": dir }),
+            // 空的下载地址
+            json!({ "id": "a", "engine": "paraformer", "archiveUrl": "  " }),
+            // 相对路径
+            json!({ "id": "a", "engine": "paraformer", "localPath": "models/a" }),
+        ];
+        for case in cases {
+            assert!(
+                parse_entry(case.clone()).unwrap().into_spec().is_err(),
+                "{case}"
+            );
+        }
+    }
+
+    #[test]
+    fn manifest_rejects_invalid_ids() {
+        for id in ["", "  ", ".", "..", "a/b", "a\\b"] {
+            let entry = parse_entry(json!({
+                "id": id,
+                "engine": "paraformer",
+                "archiveUrl": "https://example.com/a.tar.bz2",
+            }))
+            .unwrap();
+            assert!(entry.into_spec().is_err(), "{id:?}");
+        }
+    }
+
+    #[test]
+    fn size_labels_estimate_download_size() {
+        assert_eq!(parse_size_label("220 MB"), Some(220 << 20));
+        assert_eq!(parse_size_label("1.5GB"), Some(3 << 29));
+        assert_eq!(parse_size_label(" 512 kib "), Some(512 << 10));
+        assert_eq!(parse_size_label("1024"), Some(1024));
+        assert_eq!(parse_size_label("约 200 MB"), None);
+        assert_eq!(parse_size_label("200 TB"), None);
+        assert_eq!(parse_size_label("0 MB"), None);
+        assert_eq!(parse_size_label(""), None);
+    }
+
+    #[test]
+    fn manifest_rejects_missing_fields_and_unknown_engine() {
+        assert!(parse_entry(json!({ "engine": "paraformer", "archiveUrl": "x" })).is_err());
+        assert!(parse_entry(json!({ "id": "a", "archiveUrl": "x" })).is_err());
+        assert!(parse_entry(json!({ "id": "a", "engine": "whisper", "archiveUrl": "x" })).is_err());
+        let manifest: ModelManifest = serde_json::from_value(json!({})).unwrap();
+        assert!(manifest.models.is_empty());
+    }
 }
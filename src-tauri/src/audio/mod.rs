@@ -7,31 +7,66 @@ pub use transcribing::TranscriptionResult;
 use dictating::{AudioDictating, DictatingStream};
 use serde::Serialize;
 use specta::Type;
+use std::time::Duration;
 use tauri::{AppHandle, Manager, Wry};
 use tauri_specta::Event;
 use tracing::warn;
 
+use crate::chinese_conversion;
 use crate::clipboard;
+use crate::context::{self, ConversationContext};
 use crate::history::{self, HistoryKind, LlmPolishStatus, NewHistoryEntry};
 use crate::hotkeys;
 use crate::llm::LLMService;
 use crate::models;
 use crate::notification::{self, NotificationType};
+use crate::polish_guard;
+use crate::prompts::{self, PromptVariables};
+use crate::replacements::{self, ReplacementStage};
+use crate::source_app;
+use crate::typography;
+use crate::voice_commands;
 use crate::windows::{self, AppWindowId, ShowAppWindow};
 use crate::{AppState, AudioState};
 
-pub use local_models::{download_offline_models, get_offline_models_status};
+/// 等待用户松开快捷键再读取选区，否则仍按住的修饰键会叠加到模拟的复制快捷键上
+const SELECTION_CAPTURE_DELAY: Duration = Duration::from_millis(150);
+/// 粘贴后等待目标应用插入内容再模拟回车，过早发送会漏掉刚粘贴的文字
+const SEND_AFTER_PASTE_DELAY: Duration = Duration::from_millis(150);
 
+pub use local_models::{
+    cancel_offline_model_download, download_offline_models, get_offline_models_status,
+    OnOfflineModelDownload,
+};
+
+/// 开始听写；`templateId` 指定本次使用的润色模式，为空时使用默认模式
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn start_dictating(app: AppHandle, template_id: Option<String>) -> Result<(), String> {
+    start_recording(app, HistoryKind::Dictation, template_id).await
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn start_voice_diary(app: AppHandle, template_id: Option<String>) -> Result<(), String> {
+    start_recording(app, HistoryKind::Diary, template_id).await
+}
+
+/// 开始翻译输入；`templateId` 为空时使用内置的翻译模式
 #[tauri::command(async)]
 #[specta::specta]
-pub async fn start_dictating(app: AppHandle) -> Result<(), String> {
-    start_recording(app, HistoryKind::Dictation).await
+pub async fn start_translating(app: AppHandle, template_id: Option<String>) -> Result<(), String> {
+    start_recording(app, HistoryKind::Translation, template_id).await
 }
 
+/// 开始语音编辑：读取前台应用中选中的文本，录下修改指令后用结果替换选区
 #[tauri::command(async)]
 #[specta::specta]
-pub async fn start_voice_diary(app: AppHandle) -> Result<(), String> {
-    start_recording(app, HistoryKind::Diary).await
+pub async fn start_editing_selection(
+    app: AppHandle,
+    template_id: Option<String>,
+) -> Result<(), String> {
+    start_recording(app, HistoryKind::Edit, template_id).await
 }
 
 #[derive(Serialize, Type, Debug, Clone, Copy)]
@@ -47,8 +82,32 @@ pub struct OnTranscribingStage {
     pub stage: TranscribingStage,
 }
 
-async fn start_recording(app: AppHandle, history_kind: HistoryKind) -> Result<(), String> {
+async fn start_recording(
+    app: AppHandle,
+    history_kind: HistoryKind,
+    template_id: Option<String>,
+) -> Result<(), String> {
     ensure_model_downloaded(&app).await?;
+    // 必须在显示录音窗口之前读取，否则可能拿到妙语自身
+    let source_app = source_app::frontmost_app_name();
+    // 同理，选区也要在录音窗口出现前读取
+    let selected_text = if history_kind == HistoryKind::Edit {
+        match capture_selection(&app).await {
+            Ok(text) => Some(text),
+            Err(error) => {
+                let _ = notification::show_notification(
+                    app.clone(),
+                    error.clone(),
+                    NotificationType::Info,
+                    None,
+                )
+                .await;
+                return Err(error);
+            }
+        }
+    } else {
+        None
+    };
 
     let state = app.state::<AppState>();
     {
@@ -59,6 +118,9 @@ async fn start_recording(app: AppHandle, history_kind: HistoryKind) -> Result<()
         guard.state = AudioState::Recording;
         guard.dictating_stream = None;
         guard.history_kind = history_kind;
+        guard.prompt_template = template_id;
+        guard.source_app = source_app;
+        guard.selected_text = selected_text;
     }
 
     let stream = match DictatingStream::new() {
@@ -130,7 +192,7 @@ async fn ensure_model_downloaded(app: &AppHandle<Wry>) -> Result<(), String> {
 #[specta::specta]
 pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::TranscriptionResult, String> {
     let state = app.state::<AppState>();
-    let (stream, history_kind) = {
+    let (stream, history_kind, template_id, source_app, selected_text) = {
         let mut guard = state.audio.lock().await;
         if guard.state != AudioState::Recording {
             return Err("当前没有正在进行的录音".to_string());
@@ -140,7 +202,13 @@ pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::Transcriptio
             .dictating_stream
             .take()
             .ok_or_else(|| "录音准备中，请稍候再试".to_string())?;
-        (stream, guard.history_kind)
+        (
+            stream,
+            guard.history_kind,
+            guard.prompt_template.take(),
+            guard.source_app.take(),
+            guard.selected_text.take(),
+        )
     };
 
     if let Err(error) = ShowAppWindow::AudioTranscribing.show(&app).await {
@@ -199,20 +267,105 @@ pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::Transcriptio
         }
     };
 
+    // 口述格式指令在润色前处理；语音编辑的口述内容是给模型的指令，原样保留
+    let mut press_enter = false;
+    if history_kind != HistoryKind::Edit {
+        let outcome =
+            voice_commands::apply(&transcription.text, &voice_commands::command_settings(&app));
+        transcription.text = outcome.text;
+        press_enter = outcome.send;
+    }
+    transcription.text = replacements::apply(
+        &app,
+        history_kind,
+        ReplacementStage::BeforePolish,
+        &transcription.text,
+    );
+
     OnTranscribingStage {
         stage: TranscribingStage::Polishing,
     }
     .emit(&app)
     .ok();
-    let llm_outcome = polish_transcription(&app, &transcription.text).await;
-    transcription.text = llm_outcome.text.clone();
+    let default_template = match history_kind {
+        HistoryKind::Translation => Some(prompts::TRANSLATE_TEMPLATE_ID),
+        HistoryKind::Edit => Some(prompts::EDIT_TEMPLATE_ID),
+        _ => None,
+    };
+    let template_id = template_id.or_else(|| default_template.map(str::to_string));
+    let template = prompts::prepare_template(
+        &app,
+        template_id.as_deref(),
+        &PromptVariables {
+            kind: history_kind,
+            source_app: source_app.as_deref(),
+        },
+    );
+    let request_text = match (history_kind, selected_text.as_deref()) {
+        (HistoryKind::Edit, Some(selection)) => {
+            if transcription.text.trim().is_empty() {
+                set_idle_state(&app).await;
+                let _ = notification::show_notification(
+                    app.clone(),
+                    "未识别到编辑指令，已保留选中的文本".to_string(),
+                    NotificationType::Info,
+                    None,
+                )
+                .await;
+                return Ok(transcription);
+            }
+            edit_request(selection, &transcription.text)
+        }
+        _ => transcription.text.clone(),
+    };
+    let conversation_context = context::load(&app, history_kind, source_app.as_deref()).await;
+    let llm_outcome = polish_transcription(
+        &app,
+        &request_text,
+        &template,
+        conversation_context.as_ref(),
+    )
+    .await;
+    // 语音编辑未成功时不能粘贴，否则会用指令或请求内容覆盖选区
+    let should_paste =
+        history_kind != HistoryKind::Edit || llm_outcome.status == LlmPolishStatus::Success;
+    match history_kind {
+        HistoryKind::Translation => {
+            transcription.original_text = Some(transcription.text.clone());
+            transcription.text = llm_outcome.text.clone();
+        }
+        HistoryKind::Edit => {
+            transcription.instruction = Some(transcription.text.clone());
+            transcription.text = if should_paste {
+                llm_outcome.text.clone()
+            } else {
+                selected_text.clone().unwrap_or_default()
+            };
+            transcription.original_text = selected_text;
+        }
+        _ => transcription.text = llm_outcome.text.clone(),
+    }
+    // 语音编辑失败时保留的是原选区，不做替换
+    let mut paste_text = transcription.text.clone();
+    if should_paste {
+        transcription.text = replacements::apply(
+            &app,
+            history_kind,
+            ReplacementStage::AfterPolish,
+            &transcription.text,
+        );
+        // 简繁转换默认只作用于粘贴的文本
+        let conversion = chinese_conversion::conversion_settings(&app);
+        let converted = chinese_conversion::apply_to_output(&transcription.text, &conversion);
+        // 排版规范化放在最后
+        let typography = typography::typography_settings(&app);
+        transcription.text = typography::apply(&converted.history, &typography);
+        paste_text = typography::apply(&converted.paste, &typography);
+    }
     transcription.llm_polish_status = llm_outcome.status;
     transcription.llm_polish_error = llm_outcome.error.clone();
 
-    if matches!(
-        llm_outcome.status,
-        LlmPolishStatus::Failed | LlmPolishStatus::QuotaExceeded
-    ) {
+    if llm_outcome.status.is_error() {
         let message = llm_outcome
             .error
             .clone()
@@ -220,6 +373,21 @@ pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::Transcriptio
         let _ =
             notification::show_notification(app.clone(), message, NotificationType::Error, None)
                 .await;
+    } else if llm_outcome.status == LlmPolishStatus::Skipped {
+        let message = match history_kind {
+            HistoryKind::Translation => Some("翻译需要先配置文本模型，已粘贴识别原文"),
+            HistoryKind::Edit => Some("语音编辑需要先配置文本模型，已保留选中的文本"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            let _ = notification::show_notification(
+                app.clone(),
+                message.to_string(),
+                NotificationType::Info,
+                None,
+            )
+            .await;
+        }
     }
 
     let audio_file_path = match history::save_history_audio_clip(&app, &samples, sample_rate).await
@@ -242,6 +410,7 @@ pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::Transcriptio
         active_asr_entry.as_ref(),
         &llm_outcome,
         audio_file_path.clone(),
+        source_app,
     )
     .await
     {
@@ -252,37 +421,19 @@ pub async fn stop_dictating(app: AppHandle) -> Result<transcribing::Transcriptio
         );
     }
 
-    if let Err(error) = clipboard::paste(transcription.text.clone(), &app) {
-        warn!(
-            target = "miaoyu_audio",
-            error = %error,
-            "自动粘贴失败"
-        );
-        let _ = notification::show_notification(
-            app.clone(),
-            "自动粘贴失败，内容已复制到剪贴板".to_string(),
-            NotificationType::Error,
-            None,
-        )
-        .await;
+    // 语音编辑只在窗口隐藏、焦点回到前台应用后粘贴一次，避免重复替换选区
+    if should_paste && history_kind != HistoryKind::Edit {
+        paste_result(&app, &paste_text).await;
     }
 
     set_idle_state(&app).await;
     tokio::time::sleep(tokio::time::Duration::from_millis(80)).await;
 
-    if let Err(error) = clipboard::paste(transcription.text.clone(), &app) {
-        warn!(
-            target = "miaoyu_audio",
-            error = %error,
-            "自动粘贴失败"
-        );
-        let _ = notification::show_notification(
-            app.clone(),
-            "自动粘贴失败，内容已复制到剪贴板".to_string(),
-            NotificationType::Error,
-            None,
-        )
-        .await;
+    if should_paste {
+        paste_result(&app, &paste_text).await;
+    }
+    if press_enter {
+        send_enter(&app).await;
     }
 
     Ok(transcription)
@@ -308,6 +459,57 @@ pub async fn cancel_dictating(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+async fn paste_result(app: &AppHandle<Wry>, text: &str) {
+    if let Err(error) = clipboard::paste(text.to_string(), app) {
+        warn!(
+            target = "miaoyu_audio",
+            error = %error,
+            "自动粘贴失败"
+        );
+        let _ = notification::show_notification(
+            app.clone(),
+            "自动粘贴失败，内容已复制到剪贴板".to_string(),
+            NotificationType::Error,
+            None,
+        )
+        .await;
+    }
+}
+
+/// 口述“发送”后模拟回车，把刚粘贴的内容发出去
+async fn send_enter(app: &AppHandle<Wry>) {
+    tokio::time::sleep(SEND_AFTER_PASTE_DELAY).await;
+    if let Err(error) = clipboard::press_enter() {
+        warn!(
+            target = "miaoyu_audio",
+            error = %error,
+            "模拟回车失败"
+        );
+        let _ = notification::show_notification(
+            app.clone(),
+            format!("自动发送失败: {error}"),
+            NotificationType::Error,
+            None,
+        )
+        .await;
+    }
+}
+
+/// 读取前台应用中选中的文本
+async fn capture_selection(app: &AppHandle<Wry>) -> Result<String, String> {
+    tokio::time::sleep(SELECTION_CAPTURE_DELAY).await;
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || clipboard::copy_selection(&app))
+        .await
+        .map_err(|e| format!("读取选中文本失败: {e}"))??
+        .ok_or_else(|| "未检测到选中的文本，请先选中要编辑的内容".to_string())
+}
+
+/// 语音编辑发送给文本模型的内容，格式与内置“语音编辑”模板的说明一致
+fn edit_request(selection: &str, instruction: &str) -> String {
+    format!("<selection>\n{selection}\n</selection>\n<instruction>\n{instruction}\n</instruction>")
+}
+
 async fn set_idle_state(app: &AppHandle<Wry>) {
     let state = app.state::<AppState>();
     {
@@ -331,6 +533,7 @@ async fn log_history_entry(
     active_asr_entry: Option<&models::AsrModelStore>,
     llm_outcome: &LlmPolishOutcome,
     audio_file_path: Option<String>,
+    source_app: Option<String>,
 ) -> Result<(), String> {
     let asr_model_id = active_asr_entry
         .as_ref()
@@ -362,9 +565,15 @@ async fn log_history_entry(
         total_words: Some(words),
         total_tokens: Some(words),
         llm_total_tokens: llm_outcome.llm_total_tokens,
-        source_app: None,
+        source_app,
         llm_polish_status: llm_outcome.status,
         llm_polish_error: llm_outcome.error.clone(),
+        original_text: transcription.original_text.clone(),
+        instruction: transcription.instruction.clone(),
+        tags: Vec::new(),
+        source_ids: Vec::new(),
+        digest_period: None,
+        digest_date: None,
     };
 
     history::add_history_entry(app.clone(), entry).await?;
@@ -408,9 +617,14 @@ impl LlmPolishOutcome {
     }
 }
 
-async fn polish_transcription(app: &AppHandle<Wry>, text: &str) -> LlmPolishOutcome {
+async fn polish_transcription(
+    app: &AppHandle<Wry>,
+    text: &str,
+    template: &prompts::PromptTemplate,
+    conversation_context: Option<&ConversationContext>,
+) -> LlmPolishOutcome {
     let original_text = text.to_string();
-    let llm_entry = match models::active_llm_entry(app, None, None) {
+    let llm_entry = match models::active_llm_entry(app, template.model_override.as_deref(), None) {
         Ok(Some(entry)) => entry,
         Ok(None) => {
             return LlmPolishOutcome::from_error(
@@ -437,10 +651,12 @@ async fn polish_transcription(app: &AppHandle<Wry>, text: &str) -> LlmPolishOutc
         }
     };
 
-    match LLMService::polish_text(app, text).await {
+    match LLMService::polish_text(app, text, template, conversation_context).await {
         Ok(result) => {
+            // 首选模型失败时由备用模型完成，统计与历史记录归属实际使用的模型
+            let variant_id = result.variant_id.unwrap_or_else(|| llm_entry.id.clone());
             if let Some(tokens) = result.total_tokens {
-                if let Err(error) = models::record_llm_usage(app, &llm_entry.id, tokens) {
+                if let Err(error) = models::record_llm_usage(app, &variant_id, tokens) {
                     warn!(
                         target = "miaoyu_llm",
                         error = %error,
@@ -448,32 +664,42 @@ async fn polish_transcription(app: &AppHandle<Wry>, text: &str) -> LlmPolishOutc
                     );
                 }
             }
+            let llm_model = Some(
+                result
+                    .text_model_id
+                    .unwrap_or_else(|| llm_entry.text_model_id.clone()),
+            );
+            if let Some(reason) = polish_guard::check(text, &result.text, template.allow_rewrite) {
+                warn!(
+                    target = "miaoyu_llm",
+                    template = template.id,
+                    reason,
+                    "润色结果偏离原文，回退为识别原文"
+                );
+                return LlmPolishOutcome {
+                    text: original_text,
+                    llm_model,
+                    llm_variant_id: Some(variant_id),
+                    llm_total_tokens: result.total_tokens,
+                    status: LlmPolishStatus::Diverged,
+                    error: Some(format!("{reason}，已使用识别原文")),
+                };
+            }
             LlmPolishOutcome {
                 text: result.text,
-                llm_model: Some(llm_entry.text_model_id.clone()),
-                llm_variant_id: Some(llm_entry.id.clone()),
+                llm_model,
+                llm_variant_id: Some(variant_id),
                 llm_total_tokens: result.total_tokens,
                 status: LlmPolishStatus::Success,
                 error: None,
             }
         }
         Err(err) => {
-            let message = err.to_string();
-            let status = if message.contains("额度已用完") {
-                LlmPolishStatus::QuotaExceeded
-            } else if message.contains("未配置文本模型") {
-                LlmPolishStatus::Skipped
-            } else {
-                LlmPolishStatus::Failed
-            };
+            let status = err.polish_status();
             LlmPolishOutcome::from_error(
                 original_text,
                 status,
-                if status == LlmPolishStatus::Skipped {
-                    None
-                } else {
-                    Some(message)
-                },
+                status.is_error().then(|| err.to_string()),
                 Some(llm_entry.text_model_id.clone()),
                 Some(llm_entry.id.clone()),
             )
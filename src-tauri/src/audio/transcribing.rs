@@ -1,7 +1,8 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
 
 use anyhow::{anyhow, Result};
-use once_cell::sync::OnceCell;
+use once_cell::sync::Lazy;
 use sherpa_rs::paraformer::{ParaformerConfig, ParaformerRecognizer};
 use sherpa_rs::sense_voice::{SenseVoiceConfig, SenseVoiceRecognizer};
 use specta::Type;
@@ -9,7 +10,7 @@ use tauri::{AppHandle, Wry};
 use tokio::sync::Mutex;
 use tracing::debug;
 
-use super::local_models;
+use super::local_models::{self, LocalModelEngine, LocalModelSpec};
 use crate::history::LlmPolishStatus;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Type)]
@@ -22,6 +23,12 @@ pub struct TranscriptionResult {
     pub llm_polish_status: LlmPolishStatus,
     #[serde(default)]
     pub llm_polish_error: Option<String>,
+    /// 翻译输入时为译文之前的识别原文，语音编辑时为选中的文本
+    #[serde(default)]
+    pub original_text: Option<String>,
+    /// 语音编辑时识别出的口述指令
+    #[serde(default)]
+    pub instruction: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Type)]
@@ -33,21 +40,7 @@ pub struct TranscriptionUtterance {
 }
 
 const TARGET_SAMPLE_RATE: u32 = 16_000;
-
-enum RecognizerKind {
-    Paraformer,
-    SenseVoice,
-}
-
-impl RecognizerKind {
-    fn from_model_id(model_id: &str) -> Self {
-        if model_id == local_models::SENSEVOICE_MODEL_ID {
-            Self::SenseVoice
-        } else {
-            Self::Paraformer
-        }
-    }
-}
+const DEFAULT_NUM_THREADS: i32 = 2;
 
 struct ParaformerService {
     recognizer: Mutex<ParaformerRecognizer>,
@@ -57,40 +50,58 @@ struct SenseVoiceService {
     recognizer: Mutex<SenseVoiceRecognizer>,
 }
 
-static PARAFORMER_SERVICE: OnceCell<Arc<ParaformerService>> = OnceCell::new();
-static SENSE_VOICE_SERVICE: OnceCell<Arc<SenseVoiceService>> = OnceCell::new();
+// 识别器按模型 ID 缓存，内置模型与清单中的自定义模型可以同时存在
+static PARAFORMER_SERVICES: Lazy<StdMutex<HashMap<String, Arc<ParaformerService>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+static SENSE_VOICE_SERVICES: Lazy<StdMutex<HashMap<String, Arc<SenseVoiceService>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// 模型目录被替换后丢弃缓存的识别器，下次识别时从新文件重新加载
+pub(super) fn evict_recognizer(model_id: &str) {
+    if let Ok(mut services) = PARAFORMER_SERVICES.lock() {
+        services.remove(model_id);
+    }
+    if let Ok(mut services) = SENSE_VOICE_SERVICES.lock() {
+        services.remove(model_id);
+    }
+}
 
 impl ParaformerService {
-    fn instance(app: &AppHandle<Wry>) -> Result<Arc<Self>> {
-        PARAFORMER_SERVICE
-            .get_or_try_init(|| {
-                let svc = Self::create(app)?;
-                Ok(Arc::new(svc))
-            })
-            .map(Arc::clone)
+    fn instance(app: &AppHandle<Wry>, spec: &LocalModelSpec) -> Result<Arc<Self>> {
+        let mut services = PARAFORMER_SERVICES
+            .lock()
+            .map_err(|_| anyhow!("Paraformer 识别器缓存不可用"))?;
+        if let Some(service) = services.get(&spec.id) {
+            return Ok(Arc::clone(service));
+        }
+        let service = Arc::new(Self::create(app, spec)?);
+        services.insert(spec.id.clone(), Arc::clone(&service));
+        Ok(service)
     }
 
-    fn create(app: &AppHandle<Wry>) -> Result<Self> {
-        let model_path = local_models::resolve_model_file(
-            app,
-            local_models::PARAFORMER_MODEL_ID,
-            "model.int8.onnx",
-        )?;
+    fn create(app: &AppHandle<Wry>, spec: &LocalModelSpec) -> Result<Self> {
+        let model_path = local_models::resolve_model_file(app, &spec.id, &spec.options.model_file)?;
         let tokens_path =
-            local_models::resolve_model_file(app, local_models::PARAFORMER_MODEL_ID, "tokens.txt")?;
+            local_models::resolve_model_file(app, &spec.id, &spec.options.tokens_file)?;
 
         debug!(
             target = "miaoyu_audio",
+            model_id = %spec.id,
             model = %model_path.display(),
             tokens = %tokens_path.display(),
-            "加载 Paraformer Small 离线模型"
+            "加载 Paraformer 离线模型"
         );
 
         let config = ParaformerConfig {
             model: model_path.to_string_lossy().to_string(),
             tokens: tokens_path.to_string_lossy().to_string(),
-            provider: Some(sherpa_rs::get_default_provider()),
-            num_threads: Some(2),
+            provider: Some(
+                spec.options
+                    .provider
+                    .clone()
+                    .unwrap_or_else(sherpa_rs::get_default_provider),
+            ),
+            num_threads: Some(spec.options.num_threads.unwrap_or(DEFAULT_NUM_THREADS)),
             ..Default::default()
         };
 
@@ -113,38 +124,49 @@ impl ParaformerService {
 }
 
 impl SenseVoiceService {
-    fn instance(app: &AppHandle<Wry>) -> Result<Arc<Self>> {
-        SENSE_VOICE_SERVICE
-            .get_or_try_init(|| {
-                let svc = Self::create(app)?;
-                Ok(Arc::new(svc))
-            })
-            .map(Arc::clone)
+    fn instance(app: &AppHandle<Wry>, spec: &LocalModelSpec) -> Result<Arc<Self>> {
+        let mut services = SENSE_VOICE_SERVICES
+            .lock()
+            .map_err(|_| anyhow!("SenseVoice 识别器缓存不可用"))?;
+        if let Some(service) = services.get(&spec.id) {
+            return Ok(Arc::clone(service));
+        }
+        let service = Arc::new(Self::create(app, spec)?);
+        services.insert(spec.id.clone(), Arc::clone(&service));
+        Ok(service)
     }
 
-    fn create(app: &AppHandle<Wry>) -> Result<Self> {
-        let model_path = local_models::resolve_model_file(
-            app,
-            local_models::SENSEVOICE_MODEL_ID,
-            "model.int8.onnx",
-        )?;
+    fn create(app: &AppHandle<Wry>, spec: &LocalModelSpec) -> Result<Self> {
+        let model_path = local_models::resolve_model_file(app, &spec.id, &spec.options.model_file)?;
         let tokens_path =
-            local_models::resolve_model_file(app, local_models::SENSEVOICE_MODEL_ID, "tokens.txt")?;
+            local_models::resolve_model_file(app, &spec.id, &spec.options.tokens_file)?;
 
         debug!(
             target = "miaoyu_audio",
+            model_id = %spec.id,
             model = %model_path.display(),
             tokens = %tokens_path.display(),
             "加载 SenseVoice 离线模型"
         );
 
-        let config = SenseVoiceConfig {
+        let mut config = SenseVoiceConfig {
             model: model_path.to_string_lossy().to_string(),
             tokens: tokens_path.to_string_lossy().to_string(),
-            provider: Some(sherpa_rs::get_default_provider()),
-            num_threads: Some(2),
+            provider: Some(
+                spec.options
+                    .provider
+                    .clone()
+                    .unwrap_or_else(sherpa_rs::get_default_provider),
+            ),
+            num_threads: Some(spec.options.num_threads.unwrap_or(DEFAULT_NUM_THREADS)),
             ..Default::default()
         };
+        if let Some(language) = spec.options.language.clone() {
+            config.language = language;
+        }
+        if let Some(use_itn) = spec.options.use_itn {
+            config.use_itn = use_itn;
+        }
 
         let recognizer = SenseVoiceRecognizer::new(config)
             .map_err(|err| anyhow!("初始化 SenseVoice 失败: {err}"))?;
@@ -185,13 +207,15 @@ impl AudioTranscribing {
             return Err(anyhow!("录音数据为空"));
         }
 
-        let text = match RecognizerKind::from_model_id(model_id) {
-            RecognizerKind::SenseVoice => {
-                let service = SenseVoiceService::instance(app)?;
+        let spec =
+            local_models::get_spec(model_id).ok_or_else(|| anyhow!("未知离线模型: {model_id}"))?;
+        let text = match spec.engine {
+            LocalModelEngine::SenseVoice => {
+                let service = SenseVoiceService::instance(app, spec)?;
                 service.transcribe(samples.clone()).await?
             }
-            RecognizerKind::Paraformer => {
-                let service = ParaformerService::instance(app)?;
+            LocalModelEngine::Paraformer => {
+                let service = ParaformerService::instance(app, spec)?;
                 service.transcribe(samples.clone()).await?
             }
         }
@@ -213,6 +237,8 @@ impl AudioTranscribing {
             }],
             llm_polish_status: LlmPolishStatus::Skipped,
             llm_polish_error: None,
+            original_text: None,
+            instruction: None,
         })
     }
 }
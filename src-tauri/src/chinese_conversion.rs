@@ -0,0 +1,193 @@
+//! 简繁转换：在粘贴前把输出转换为简体、繁体或台湾、香港繁体。使用内置的 OpenCC 词典离线转换，
+//! 不依赖文本模型；默认只转换粘贴的文本，可选同时写入历史记录
+
+use ferrous_opencc::{config::BuiltinConfig, OpenCC};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+use tracing::warn;
+
+use crate::settings::SettingsStore;
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ChineseVariant {
+    /// 不转换
+    #[default]
+    Unchanged,
+    /// 简体中文
+    Simplified,
+    /// 繁体中文（OpenCC 标准字形）
+    Traditional,
+    /// 繁体中文（台湾）
+    Taiwan,
+    /// 繁体中文（香港）
+    HongKong,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChineseConversionSettings {
+    #[serde(default)]
+    pub variant: ChineseVariant,
+    /// 转为台湾繁体时同时替换常用词汇，如“软件”转为“軟體”
+    #[serde(default = "default_taiwan_phrases")]
+    pub taiwan_phrases: bool,
+    /// 历史记录也保存转换后的文本
+    #[serde(default)]
+    pub apply_to_history: bool,
+}
+
+fn default_taiwan_phrases() -> bool {
+    true
+}
+
+impl Default for ChineseConversionSettings {
+    fn default() -> Self {
+        Self {
+            variant: ChineseVariant::default(),
+            taiwan_phrases: default_taiwan_phrases(),
+            apply_to_history: false,
+        }
+    }
+}
+
+/// 词典加载较慢，每种转换只加载一次
+static CONVERTERS: [OnceCell<OpenCC>; 5] = [const { OnceCell::new() }; 5];
+
+fn converter(settings: &ChineseConversionSettings) -> Result<Option<&'static OpenCC>, String> {
+    let (index, config) = match settings.variant {
+        ChineseVariant::Unchanged => return Ok(None),
+        ChineseVariant::Simplified => (0, BuiltinConfig::T2s),
+        ChineseVariant::Traditional => (1, BuiltinConfig::S2t),
+        ChineseVariant::Taiwan if settings.taiwan_phrases => (2, BuiltinConfig::S2twp),
+        ChineseVariant::Taiwan => (3, BuiltinConfig::S2tw),
+        ChineseVariant::HongKong => (4, BuiltinConfig::S2hk),
+    };
+    CONVERTERS[index]
+        .get_or_try_init(|| OpenCC::from_config(config))
+        .map(Some)
+        .map_err(|e| format!("加载简繁转换词典失败: {e}"))
+}
+
+fn convert(text: &str, settings: &ChineseConversionSettings) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+    Ok(match converter(settings)? {
+        Some(converter) => converter.convert(text),
+        None => text.to_string(),
+    })
+}
+
+/// 按设置转换文本；词典加载失败时原样返回
+pub fn apply(text: &str, settings: &ChineseConversionSettings) -> String {
+    convert(text, settings).unwrap_or_else(|error| {
+        warn!(target = "miaoyu_chinese_conversion", error = %error, "简繁转换失败");
+        text.to_string()
+    })
+}
+
+/// 听写结果转换后的两份文本
+#[derive(Debug, PartialEq)]
+pub struct ConvertedOutput {
+    /// 粘贴的文本，总是转换
+    pub paste: String,
+    /// 写入历史记录的文本，未开启 `apply_to_history` 时保留原文
+    pub history: String,
+}
+
+/// 按设置转换听写结果，分别得到粘贴与写入历史记录的文本
+pub fn apply_to_output(text: &str, settings: &ChineseConversionSettings) -> ConvertedOutput {
+    let paste = apply(text, settings);
+    let history = if settings.apply_to_history {
+        paste.clone()
+    } else {
+        text.to_string()
+    };
+    ConvertedOutput { paste, history }
+}
+
+pub fn conversion_settings(app: &AppHandle<Wry>) -> ChineseConversionSettings {
+    SettingsStore::load(app).chinese_conversion
+}
+
+/// 更新简繁转换设置
+#[tauri::command]
+#[specta::specta]
+pub fn set_chinese_conversion(
+    app: AppHandle,
+    chinese_conversion: ChineseConversionSettings,
+) -> Result<ChineseConversionSettings, String> {
+    SettingsStore::update(&app, |settings| {
+        settings.chinese_conversion = chinese_conversion.clone()
+    })?;
+    Ok(chinese_conversion)
+}
+
+/// 预览简繁转换效果，用于在设置页测试
+#[tauri::command]
+#[specta::specta]
+pub fn preview_chinese_conversion(
+    chinese_conversion: ChineseConversionSettings,
+    text: String,
+) -> Result<String, String> {
+    convert(&text, &chinese_conversion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(variant: ChineseVariant) -> ChineseConversionSettings {
+        ChineseConversionSettings {
+            variant,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_to_each_variant() {
+        let text = "这个软件的里面";
+        for (settings, expected) in [
+            (settings(ChineseVariant::Taiwan), "這個軟體的裡面"),
+            (
+                ChineseConversionSettings {
+                    taiwan_phrases: false,
+                    ..settings(ChineseVariant::Taiwan)
+                },
+                "這個軟件的裡面",
+            ),
+            (settings(ChineseVariant::HongKong), "這個軟件的裏面"),
+            (settings(ChineseVariant::Traditional), "這個軟件的裏面"),
+            (settings(ChineseVariant::Unchanged), text),
+        ] {
+            assert_eq!(apply(text, &settings), expected, "{:?}", settings.variant);
+        }
+        assert_eq!(
+            apply("這個軟體的裡面", &settings(ChineseVariant::Simplified)),
+            "这个软体的里面"
+        );
+    }
+
+    #[test]
+    fn history_keeps_original_text_unless_enabled() {
+        let output = apply_to_output("软件", &settings(ChineseVariant::Taiwan));
+        assert_eq!(
+            output,
+            ConvertedOutput {
+                paste: "軟體".to_string(),
+                history: "软件".to_string(),
+            }
+        );
+        let output = apply_to_output(
+            "软件",
+            &ChineseConversionSettings {
+                apply_to_history: true,
+                ..settings(ChineseVariant::Taiwan)
+            },
+        );
+        assert_eq!(output.history, "軟體");
+    }
+}
@@ -1,6 +1,11 @@
 use std::{thread, time::Duration};
 use tauri::{AppHandle, Runtime};
 use tauri_plugin_clipboard_manager::ClipboardExt;
+use uuid::Uuid;
+
+/// 模拟复制后轮询剪贴板的次数与间隔，部分应用写入剪贴板较慢
+const SELECTION_POLL_ATTEMPTS: usize = 10;
+const SELECTION_POLL_INTERVAL: Duration = Duration::from_millis(30);
 
 #[cfg(target_os = "macos")]
 #[link(name = "ApplicationServices", kind = "framework")]
@@ -8,8 +13,25 @@ extern "C" {
     fn AXIsProcessTrusted() -> bool;
 }
 
+/// 模拟发送的系统快捷键（macOS 为 Command，其他平台为 Ctrl）
+#[derive(Clone, Copy)]
+enum ShortcutKey {
+    Copy,
+    Paste,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+impl ShortcutKey {
+    fn label(self) -> &'static str {
+        match self {
+            ShortcutKey::Copy => "C",
+            ShortcutKey::Paste => "V",
+        }
+    }
+}
+
 #[cfg(target_os = "macos")]
-fn send_paste() -> Result<(), String> {
+fn send_shortcut(key: ShortcutKey) -> Result<(), String> {
     use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
     use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
@@ -19,25 +41,40 @@ fn send_paste() -> Result<(), String> {
     })?;
 
     let cmd_key: CGKeyCode = 55;
-    let v_key: CGKeyCode = 9;
+    let letter_key: CGKeyCode = match key {
+        ShortcutKey::Copy => 8,
+        ShortcutKey::Paste => 9,
+    };
+    let label = key.label();
 
+    // 显式设置 Command 标记，忽略用户仍按住的快捷键修饰键
     let cmd_down = CGEvent::new_keyboard_event(source.clone(), cmd_key, true).map_err(|_| {
         tracing::error!(target = "miaoyu_clipboard", "创建 Command 按下事件失败");
         "创建 Command 按下事件失败".to_string()
     })?;
     cmd_down.set_flags(CGEventFlags::CGEventFlagCommand);
 
-    let v_down = CGEvent::new_keyboard_event(source.clone(), v_key, true).map_err(|_| {
-        tracing::error!(target = "miaoyu_clipboard", "创建 V 按下事件失败");
-        "创建 V 按下事件失败".to_string()
-    })?;
-    v_down.set_flags(CGEventFlags::CGEventFlagCommand);
+    let letter_down =
+        CGEvent::new_keyboard_event(source.clone(), letter_key, true).map_err(|_| {
+            tracing::error!(
+                target = "miaoyu_clipboard",
+                key = label,
+                "创建按键按下事件失败"
+            );
+            format!("创建 {label} 按下事件失败")
+        })?;
+    letter_down.set_flags(CGEventFlags::CGEventFlagCommand);
 
-    let v_up = CGEvent::new_keyboard_event(source.clone(), v_key, false).map_err(|_| {
-        tracing::error!(target = "miaoyu_clipboard", "创建 V 弹起事件失败");
-        "创建 V 弹起事件失败".to_string()
-    })?;
-    v_up.set_flags(CGEventFlags::CGEventFlagCommand);
+    let letter_up =
+        CGEvent::new_keyboard_event(source.clone(), letter_key, false).map_err(|_| {
+            tracing::error!(
+                target = "miaoyu_clipboard",
+                key = label,
+                "创建按键弹起事件失败"
+            );
+            format!("创建 {label} 弹起事件失败")
+        })?;
+    letter_up.set_flags(CGEventFlags::CGEventFlagCommand);
 
     let cmd_up = CGEvent::new_keyboard_event(source, cmd_key, false).map_err(|_| {
         tracing::error!(target = "miaoyu_clipboard", "创建 Command 弹起事件失败");
@@ -46,42 +83,60 @@ fn send_paste() -> Result<(), String> {
     cmd_up.set_flags(CGEventFlags::CGEventFlagCommand);
 
     cmd_down.post(CGEventTapLocation::HID);
-    v_down.post(CGEventTapLocation::HID);
-    v_up.post(CGEventTapLocation::HID);
+    letter_down.post(CGEventTapLocation::HID);
+    letter_up.post(CGEventTapLocation::HID);
     cmd_up.post(CGEventTapLocation::HID);
 
     Ok(())
 }
 
 #[cfg(target_os = "windows")]
-fn send_paste() -> Result<(), String> {
-    use std::mem::size_of;
-    use windows::Win32::UI::Input::KeyboardAndMouse::{
-        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
-        VIRTUAL_KEY, VK_CONTROL, VK_V,
-    };
+fn key_input(
+    vk: windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY,
+    flags: windows::Win32::UI::Input::KeyboardAndMouse::KEYBD_EVENT_FLAGS,
+) -> windows::Win32::UI::Input::KeyboardAndMouse::INPUT {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT};
 
-    fn key_input(vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS) -> INPUT {
-        INPUT {
-            r#type: INPUT_KEYBOARD,
-            Anonymous: INPUT_0 {
-                ki: KEYBDINPUT {
-                    wVk: vk,
-                    wScan: 0,
-                    dwFlags: flags,
-                    time: 0,
-                    dwExtraInfo: 0,
-                },
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
             },
-        }
+        },
     }
+}
 
-    let inputs = [
-        key_input(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
-        key_input(VK_V, KEYBD_EVENT_FLAGS(0)),
-        key_input(VK_V, KEYEVENTF_KEYUP),
+#[cfg(target_os = "windows")]
+fn send_shortcut(key: ShortcutKey) -> Result<(), String> {
+    use std::mem::size_of;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, SendInput, INPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, VK_C, VK_CONTROL,
+        VK_LMENU, VK_LSHIFT, VK_LWIN, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_V,
+    };
+
+    let letter = match key {
+        ShortcutKey::Copy => VK_C,
+        ShortcutKey::Paste => VK_V,
+    };
+    // 触发快捷键（如 Ctrl+Alt+E）时用户可能仍按着 Alt、Shift 或 Win，
+    // 目标应用会收到 Ctrl+Alt+C 之类的组合；先按下 Ctrl 再松开这些键，
+    // 单独松开 Alt 或 Win 不会激活菜单栏或开始菜单
+    let held: Vec<_> = [VK_LMENU, VK_RMENU, VK_LSHIFT, VK_RSHIFT, VK_LWIN, VK_RWIN]
+        .into_iter()
+        .filter(|vk| unsafe { GetAsyncKeyState(i32::from(vk.0)) } < 0)
+        .collect();
+    let mut inputs = vec![key_input(VK_CONTROL, KEYBD_EVENT_FLAGS(0))];
+    inputs.extend(held.into_iter().map(|vk| key_input(vk, KEYEVENTF_KEYUP)));
+    inputs.extend([
+        key_input(letter, KEYBD_EVENT_FLAGS(0)),
+        key_input(letter, KEYEVENTF_KEYUP),
         key_input(VK_CONTROL, KEYEVENTF_KEYUP),
-    ];
+    ]);
 
     unsafe {
         let sent = SendInput(&inputs, size_of::<INPUT>() as i32);
@@ -92,16 +147,95 @@ fn send_paste() -> Result<(), String> {
                 target = "miaoyu_clipboard",
                 sent,
                 expected = inputs.len(),
-                "发送粘贴按键失败"
+                key = key.label(),
+                "发送快捷键失败"
             );
-            Err("发送粘贴按键失败".to_string())
+            Err(format!("发送 Ctrl+{} 按键失败", key.label()))
         }
     }
 }
 
+#[cfg(target_os = "macos")]
+fn send_enter() -> Result<(), String> {
+    use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|_| {
+        tracing::error!(target = "miaoyu_clipboard", "创建事件源失败");
+        "创建事件源失败".to_string()
+    })?;
+    let return_key: CGKeyCode = 36;
+    let mut events = Vec::with_capacity(2);
+    for key_down in [true, false] {
+        let event =
+            CGEvent::new_keyboard_event(source.clone(), return_key, key_down).map_err(|_| {
+                tracing::error!(target = "miaoyu_clipboard", "创建回车按键事件失败");
+                "创建回车按键事件失败".to_string()
+            })?;
+        // 清除修饰键标记，避免与用户仍按住的快捷键组合成 Shift+Enter 等
+        event.set_flags(CGEventFlags::CGEventFlagNull);
+        events.push(event);
+    }
+    for event in events {
+        event.post(CGEventTapLocation::HID);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn send_enter() -> Result<(), String> {
+    use std::mem::size_of;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, VK_RETURN,
+    };
+
+    let inputs = [
+        key_input(VK_RETURN, KEYBD_EVENT_FLAGS(0)),
+        key_input(VK_RETURN, KEYEVENTF_KEYUP),
+    ];
+    let sent = unsafe { SendInput(&inputs, size_of::<INPUT>() as i32) };
+    if sent == inputs.len() as u32 {
+        Ok(())
+    } else {
+        tracing::error!(
+            target = "miaoyu_clipboard",
+            sent,
+            expected = inputs.len(),
+            "发送回车按键失败"
+        );
+        Err("发送回车按键失败".to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn send_enter() -> Result<(), String> {
+    Err("当前平台暂未实现模拟回车".into())
+}
+
 #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-fn send_paste() -> Result<(), String> {
-    Err("当前平台暂未实现自动粘贴".into())
+fn send_shortcut(key: ShortcutKey) -> Result<(), String> {
+    match key {
+        ShortcutKey::Copy => Err("当前平台暂未实现读取选中文本".into()),
+        ShortcutKey::Paste => Err("当前平台暂未实现自动粘贴".into()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn ensure_accessibility(action: &str) -> Result<(), String> {
+    if unsafe { !AXIsProcessTrusted() } {
+        tracing::warn!(
+            target = "miaoyu_clipboard",
+            action,
+            "未获得辅助功能权限，无法模拟按键"
+        );
+        return Err(format!("未获得辅助功能权限，无法{action}"));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn ensure_accessibility(_action: &str) -> Result<(), String> {
+    Ok(())
 }
 
 pub fn paste<R: Runtime>(text: String, app_handle: &AppHandle<R>) -> Result<(), String> {
@@ -114,20 +248,52 @@ pub fn paste<R: Runtime>(text: String, app_handle: &AppHandle<R>) -> Result<(),
     })?;
 
     // 检查辅助功能权限（macOS）
-    #[cfg(target_os = "macos")]
-    if unsafe { !AXIsProcessTrusted() } {
-        tracing::warn!(
-            target = "miaoyu_clipboard",
-            "未获得辅助功能权限，无法自动粘贴，内容已复制到剪贴板"
-        );
-        return Err("未获得辅助功能权限，内容已复制到剪贴板".to_string());
-    }
+    ensure_accessibility("自动粘贴").map_err(|e| format!("{e}，内容已复制到剪贴板"))?;
 
     // 等待剪贴板写入完成
     thread::sleep(Duration::from_millis(60));
 
     // 发送粘贴按键
-    send_paste()?;
+    send_shortcut(ShortcutKey::Paste)?;
 
     Ok(())
 }
+
+/// 模拟按下回车键，用于口述“发送”后在聊天应用中发出消息
+pub fn press_enter() -> Result<(), String> {
+    ensure_accessibility("发送消息")?;
+    send_enter()
+}
+
+/// 读取前台应用中选中的文本：模拟复制快捷键后读取剪贴板，再恢复原有的剪贴板内容。
+/// 没有选中内容时返回 `None`
+pub fn copy_selection<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Option<String>, String> {
+    ensure_accessibility("读取选中的文本")?;
+
+    let clipboard = app_handle.clipboard();
+    let previous = clipboard.read_text().ok();
+    // 写入占位内容，剪贴板发生变化才说明复制成功，避免把旧的剪贴板内容当作选区
+    let marker = format!("miaoyu-selection-{}", Uuid::new_v4());
+    clipboard.write_text(&marker).map_err(|e| {
+        tracing::error!(target = "miaoyu_clipboard", error = %e, "写入剪贴板失败");
+        "写入剪贴板失败".to_string()
+    })?;
+    thread::sleep(Duration::from_millis(30));
+
+    let copied = send_shortcut(ShortcutKey::Copy).map(|()| {
+        (0..SELECTION_POLL_ATTEMPTS).find_map(|_| {
+            thread::sleep(SELECTION_POLL_INTERVAL);
+            clipboard.read_text().ok().filter(|text| *text != marker)
+        })
+    });
+
+    let restored = match previous {
+        Some(text) => clipboard.write_text(text),
+        None => clipboard.clear(),
+    };
+    if let Err(error) = restored {
+        tracing::warn!(target = "miaoyu_clipboard", error = %error, "恢复剪贴板内容失败");
+    }
+
+    Ok(copied?.filter(|text| !text.trim().is_empty()))
+}
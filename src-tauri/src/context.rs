@@ -0,0 +1,263 @@
+//! 对话上下文：润色时附带同一应用中最近几条同类记录，让连续口述的人称、术语与格式保持一致
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+use tracing::warn;
+
+use crate::{
+    history::{self, HistoryKind},
+    llm_protocol::{ChatRole, ChatTurn},
+    settings::SettingsStore,
+};
+
+/// 模型对上下文消息的固定答复，组成完整的一轮对话后再发送本次输入
+const CONTEXT_ACK: &str = "好的，以上内容仅作参考，我只处理接下来的文本。";
+const MAX_ENTRIES_LIMIT: u32 = 10;
+const WINDOW_MINUTES_LIMIT: u32 = 24 * 60;
+const TOKEN_BUDGET_RANGE: std::ops::RangeInclusive<u32> = 100..=8000;
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationContextSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 最多附带的记录条数
+    #[serde(default = "default_max_entries")]
+    pub max_entries: u32,
+    /// 只附带该时间窗口内的记录，单位为分钟
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+    /// 上下文的 token 预算，按字符数估算，超出时从最早的记录开始舍弃
+    #[serde(default = "default_token_budget")]
+    pub token_budget: u32,
+}
+
+fn default_max_entries() -> u32 {
+    3
+}
+
+fn default_window_minutes() -> u32 {
+    10
+}
+
+fn default_token_budget() -> u32 {
+    800
+}
+
+impl Default for ConversationContextSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: default_max_entries(),
+            window_minutes: default_window_minutes(),
+            token_budget: default_token_budget(),
+        }
+    }
+}
+
+/// 本次润色附带的上下文，按时间从早到晚排列
+#[derive(Debug, Clone)]
+pub struct ConversationContext {
+    entries: Vec<String>,
+}
+
+impl ConversationContext {
+    /// 作为一条用户消息发送的上下文内容
+    pub fn prompt(&self) -> String {
+        format!(
+            "以下是我此前在同一位置输入的内容，仅用于保持人称、术语与格式一致。\
+             不要润色、复述或输出这些内容：\n<context>\n{}\n</context>",
+            self.entries.join("\n---\n")
+        )
+    }
+
+    /// 上下文消息与模型的固定答复
+    pub fn turns(prompt: &str) -> [ChatTurn<'_>; 2] {
+        [
+            ChatTurn {
+                role: ChatRole::User,
+                content: prompt,
+            },
+            ChatTurn {
+                role: ChatRole::Assistant,
+                content: CONTEXT_ACK,
+            },
+        ]
+    }
+
+    /// 模型偶尔会把上下文一并输出，去掉结果开头的 `<context>` 块或重复的上下文记录；
+    /// 记录后面必须紧跟换行或分隔线，避免误删恰好以相同文字开头的正文。去掉后为空时保留原样
+    pub fn strip_echo(&self, output: &str) -> String {
+        let mut rest = output.trim_start();
+        if let Some(end) = rest
+            .strip_prefix("<context>")
+            .and_then(|inner| inner.find("</context>"))
+        {
+            rest = rest["<context>".len() + end + "</context>".len()..].trim_start();
+        }
+        while let Some(stripped) = self
+            .entries
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .find_map(|entry| strip_echoed_entry(rest, entry))
+        {
+            rest = stripped;
+        }
+        if rest.trim().is_empty() {
+            output.to_string()
+        } else {
+            rest.to_string()
+        }
+    }
+}
+
+/// 去掉开头整条重复的记录及其后的分隔线；记录后既不是换行、分隔线也不是结尾时返回 `None`
+fn strip_echoed_entry<'a>(text: &'a str, entry: &str) -> Option<&'a str> {
+    let after = text.strip_prefix(entry)?;
+    if let Some(after) = after.trim_start().strip_prefix("---") {
+        return Some(after.trim_start());
+    }
+    let line_end = after.trim_start_matches([' ', '\t', '\r']);
+    (line_end.is_empty() || line_end.starts_with('\n')).then(|| after.trim_start())
+}
+
+pub fn context_settings(app: &AppHandle<Wry>) -> ConversationContextSettings {
+    SettingsStore::load(app).conversation_context
+}
+
+/// 读取同一应用、同类记录在时间窗口内的最近几条作为上下文；未开启或无可用记录时为 `None`。
+/// Windows 与 Linux 取不到来源应用，此时只按类型与时间窗口匹配同样没有来源应用的记录
+pub async fn load(
+    app: &AppHandle<Wry>,
+    kind: HistoryKind,
+    source_app: Option<&str>,
+) -> Option<ConversationContext> {
+    let settings = context_settings(app);
+    // 语音编辑自带选中文本，不需要上下文
+    if !settings.enabled || kind == HistoryKind::Edit {
+        return None;
+    }
+
+    let recent = match history::recent_entries(
+        app,
+        kind,
+        source_app.map(str::to_string),
+        settings.max_entries,
+    )
+    .await
+    {
+        Ok(entries) => entries,
+        Err(error) => {
+            warn!(
+                target = "miaoyu_context",
+                error = %error,
+                "读取上下文记录失败，本次不附带上下文"
+            );
+            return None;
+        }
+    };
+
+    let since = Utc::now() - Duration::minutes(settings.window_minutes as i64);
+    let mut budget = settings.token_budget as usize;
+    let mut entries = Vec::new();
+    // 记录按时间倒序返回，预算不足时舍弃的是更早的记录
+    for entry in recent {
+        let in_window = DateTime::parse_from_rfc3339(&entry.created_at)
+            .is_ok_and(|created_at| created_at >= since);
+        if !in_window {
+            break;
+        }
+        if entry.llm_polish_status.is_error() || entry.text.trim().is_empty() {
+            continue;
+        }
+        let tokens = entry.text.chars().count();
+        if tokens > budget {
+            break;
+        }
+        budget -= tokens;
+        entries.push(entry.text);
+    }
+    if entries.is_empty() {
+        return None;
+    }
+    entries.reverse();
+    Some(ConversationContext { entries })
+}
+
+/// 更新润色时附带的对话上下文设置
+#[tauri::command]
+#[specta::specta]
+pub fn set_conversation_context(
+    app: AppHandle,
+    context: ConversationContextSettings,
+) -> Result<ConversationContextSettings, String> {
+    if !(1..=MAX_ENTRIES_LIMIT).contains(&context.max_entries) {
+        return Err(format!("附带的记录条数需在 1 到 {MAX_ENTRIES_LIMIT} 之间"));
+    }
+    if !(1..=WINDOW_MINUTES_LIMIT).contains(&context.window_minutes) {
+        return Err("时间窗口需在 1 分钟到 24 小时之间".to_string());
+    }
+    if !TOKEN_BUDGET_RANGE.contains(&context.token_budget) {
+        return Err(format!(
+            "上下文预算需在 {} 到 {} 之间",
+            TOKEN_BUDGET_RANGE.start(),
+            TOKEN_BUDGET_RANGE.end()
+        ));
+    }
+    SettingsStore::update(&app, |settings| {
+        settings.conversation_context = context.clone()
+    })?;
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(entries: &[&str]) -> ConversationContext {
+        ConversationContext {
+            entries: entries.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn strips_echoed_context_block() {
+        let context = context(&["第一条", "第二条"]);
+        let echoed = format!("{}\n\n明天下午开会。", context.prompt());
+        // prompt 以说明文字开头，只有模型从 <context> 开始复述时才去掉
+        assert_eq!(context.strip_echo(&echoed), echoed);
+        assert_eq!(
+            context.strip_echo("<context>\n第一条\n---\n第二条\n</context>\n明天下午开会。"),
+            "明天下午开会。"
+        );
+    }
+
+    #[test]
+    fn strips_echoed_entries_followed_by_separators() {
+        let context = context(&["第一条记录", "第二条记录"]);
+        assert_eq!(
+            context.strip_echo("第一条记录\n---\n第二条记录\n明天下午开会。"),
+            "明天下午开会。"
+        );
+        assert_eq!(
+            context.strip_echo("第二条记录 ---明天下午开会。"),
+            "明天下午开会。"
+        );
+        // 只有上下文时保留原样
+        assert_eq!(
+            context.strip_echo("第一条记录\n---\n第二条记录"),
+            "第一条记录\n---\n第二条记录"
+        );
+    }
+
+    #[test]
+    fn keeps_output_that_only_starts_like_an_entry() {
+        let context = context(&["好的"]);
+        assert_eq!(context.strip_echo("好的，我明天到"), "好的，我明天到");
+        assert_eq!(context.strip_echo("好的我明天到"), "好的我明天到");
+        assert_eq!(context.strip_echo("好的。\n我明天到"), "好的。\n我明天到");
+    }
+}
@@ -0,0 +1,201 @@
+//! 语音日记摘要：汇总一天或一周的日记，由文本模型写成包含亮点、心情与待办的回顾，
+//! 作为 `HistoryKind::Digest` 记录保存并关联来源日记
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use tauri::AppHandle;
+use tracing::warn;
+
+use crate::{
+    history::{self, DigestPeriod, HistoryEntry, HistoryKind, LlmPolishStatus, NewHistoryEntry},
+    llm::{self, LLMService},
+    models,
+    prompts::PromptTemplate,
+};
+
+const DIGEST_TEMPLATE_ID: &str = "diary-digest";
+const DIGEST_SYSTEM_PROMPT: &str = "你是一位温和的日记整理助手。用户会提供一段时间内的语音日记，每条以记录时间开头。请写一份回顾，按以下结构输出纯文本：
+亮点：列出 2 到 4 件值得记住的事
+心情：概括这段时间的情绪与变化
+待办：列出日记中提到但尚未完成的事项，没有则写“暂无”
+回顾：用两三句话给出温和的反思与建议
+只依据日记内容，不要编造，不要输出其他说明。";
+/// 发送给模型的日记总长度上限，超出时只保留较早的部分
+const MAX_INPUT_CHARS: usize = 12_000;
+const WEEKDAYS: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+
+/// 周期的起始日期与本地时间范围 `[start, end)`
+fn period_range(
+    period: DigestPeriod,
+    date: NaiveDate,
+) -> Result<(NaiveDate, DateTime<Local>, DateTime<Local>), String> {
+    let (start_date, days) = match period {
+        DigestPeriod::Day => (date, 1),
+        DigestPeriod::Week => (
+            date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            7,
+        ),
+    };
+    let local_midnight = |day: NaiveDate| {
+        day.and_hms_opt(0, 0, 0)
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+            .ok_or_else(|| format!("无法解析日期: {day}"))
+    };
+    Ok((
+        start_date,
+        local_midnight(start_date)?,
+        local_midnight(start_date + Duration::days(days))?,
+    ))
+}
+
+fn digest_title(period: DigestPeriod, start_date: NaiveDate) -> String {
+    match period {
+        DigestPeriod::Day => format!("{} 日记摘要", start_date.format("%Y-%m-%d")),
+        DigestPeriod::Week => format!(
+            "{} 至 {} 周摘要",
+            start_date.format("%Y-%m-%d"),
+            (start_date + Duration::days(6)).format("%m-%d")
+        ),
+    }
+}
+
+/// 每条日记前标注记录时间，周摘要额外标注日期与星期
+fn format_entry(period: DigestPeriod, created_at: DateTime<Local>, text: &str) -> String {
+    let time = match period {
+        DigestPeriod::Day => created_at.format("%H:%M").to_string(),
+        DigestPeriod::Week => format!(
+            "{} {} {}",
+            created_at.format("%m-%d"),
+            WEEKDAYS[created_at.weekday().num_days_from_monday() as usize],
+            created_at.format("%H:%M")
+        ),
+    };
+    format!("[{time}] {}", text.trim())
+}
+
+/// 生成指定日期所在天或周的日记摘要；该周期已有摘要时重新生成并覆盖
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_diary_digest(
+    app: AppHandle,
+    period: DigestPeriod,
+    date: String,
+) -> Result<HistoryEntry, String> {
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("日期格式无效: {date}"))?;
+    let (start_date, start, end) = period_range(period, date)?;
+    if !llm::has_configured_api_key(&app) {
+        return Err("生成日记摘要需要先配置文本模型".to_string());
+    }
+
+    let diaries: Vec<(DateTime<Local>, HistoryEntry)> = history::entries_of_kind_between(
+        &app,
+        HistoryKind::Diary,
+        start.with_timezone(&Utc).to_rfc3339(),
+        end.with_timezone(&Utc).to_rfc3339(),
+    )
+    .await?
+    .into_iter()
+    .filter(|entry| !entry.text.trim().is_empty())
+    .filter_map(|entry| {
+        let created_at = DateTime::parse_from_rfc3339(&entry.created_at).ok()?;
+        Some((created_at.with_timezone(&Local), entry))
+    })
+    .collect();
+    if diaries.is_empty() {
+        return Err("该时间段内没有语音日记".to_string());
+    }
+
+    let total = diaries.len();
+    let mut source_ids = Vec::new();
+    let mut lines = Vec::new();
+    let mut remaining = MAX_INPUT_CHARS;
+    let mut truncated = false;
+    for (created_at, entry) in diaries {
+        let line = format_entry(period, created_at, &entry.text);
+        let length = line.chars().count();
+        if length > remaining {
+            // 第一条就超出上限时截取开头，避免整段时间都无法生成摘要
+            if lines.is_empty() {
+                lines.push(line.chars().take(remaining).collect());
+                source_ids.push(entry.id);
+            }
+            truncated = true;
+            break;
+        }
+        remaining -= length;
+        lines.push(line);
+        source_ids.push(entry.id);
+    }
+    if truncated {
+        warn!(
+            target = "miaoyu_digest",
+            included = source_ids.len(),
+            total,
+            "日记内容过长，摘要只包含较早的部分"
+        );
+    }
+
+    let template = PromptTemplate {
+        id: DIGEST_TEMPLATE_ID.to_string(),
+        name: "日记摘要".to_string(),
+        system_prompt: DIGEST_SYSTEM_PROMPT.to_string(),
+        temperature: Some(0.5),
+        model_override: None,
+        builtin: true,
+        customized: false,
+        allow_rewrite: true,
+    };
+    let result = LLMService::complete(&app, &lines.join("\n"), &template)
+        .await
+        .map_err(|error| format!("生成日记摘要失败: {error}"))?;
+    if let (Some(variant_id), Some(tokens)) = (result.variant_id.as_deref(), result.total_tokens) {
+        if let Err(error) = models::record_llm_usage(&app, variant_id, tokens) {
+            warn!(
+                target = "miaoyu_digest",
+                error = %error,
+                "记录文本模型使用统计失败"
+            );
+        }
+    }
+
+    let mut text = result.text.trim().to_string();
+    // 写进摘要正文，用户查看时能知道有日记未被纳入
+    if truncated {
+        let note = if source_ids.len() < total {
+            format!("本摘要只依据较早的 {} 条，共 {total} 条", source_ids.len())
+        } else {
+            "本摘要只依据开头部分".to_string()
+        };
+        text.push_str(&format!("\n\n（日记内容过长，{note}）"));
+    }
+
+    let digest_date = start_date.format("%Y-%m-%d").to_string();
+    let existing = history::find_digest(&app, period, digest_date.clone()).await?;
+    let entry = NewHistoryEntry {
+        id: existing.map(|entry| entry.id),
+        text,
+        kind: HistoryKind::Digest,
+        title: Some(digest_title(period, start_date)),
+        // 摘要不是口述内容，不计入时长与字数统计
+        duration_seconds: 0,
+        created_at: None,
+        audio_file_path: None,
+        llm_model: result.text_model_id,
+        llm_variant_id: result.variant_id,
+        asr_model: None,
+        asr_variant_id: None,
+        total_words: Some(0),
+        total_tokens: Some(0),
+        llm_total_tokens: result.total_tokens,
+        source_app: None,
+        llm_polish_status: LlmPolishStatus::Success,
+        llm_polish_error: None,
+        original_text: None,
+        instruction: None,
+        tags: Vec::new(),
+        source_ids,
+        digest_period: Some(period),
+        digest_date: Some(digest_date),
+    };
+    history::add_history_entry(app, entry).await
+}
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+/// 返回 `path` 所在磁盘卷的可用空间（字节）；路径尚未创建时取最近的已存在上级目录
+pub fn available_space(path: &Path) -> Result<u64, String> {
+    let existing = path
+        .ancestors()
+        .find(|candidate| candidate.exists())
+        .ok_or_else(|| format!("无法定位磁盘路径: {}", path.display()))?;
+    fs4::available_space(existing).map_err(|e| format!("读取磁盘可用空间失败: {e}"))
+}
+
+/// 确认目标卷至少还有 `required` 字节可用，不足时返回可直接展示给用户的错误信息；
+/// 成功时返回当前可用空间
+pub fn ensure_available_space(path: &Path, required: u64, purpose: &str) -> Result<u64, String> {
+    let available = available_space(path)?;
+    if available < required {
+        return Err(format!(
+            "磁盘空间不足，{purpose}需要约 {}，当前仅剩 {}",
+            format_bytes(required),
+            format_bytes(available)
+        ));
+    }
+    Ok(available)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
@@ -1,4 +1,8 @@
-use std::{io::ErrorKind, path::PathBuf};
+use std::{
+    io::ErrorKind,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use base64::engine::general_purpose::STANDARD as Base64;
 use base64::Engine;
@@ -10,15 +14,29 @@ use tauri::{path::BaseDirectory, AppHandle, Manager, Wry};
 use tokio::{fs, task::spawn_blocking};
 use uuid::Uuid;
 
-use crate::models;
+use crate::{
+    disk, models,
+    notification::{self, NotificationType},
+    tasks, titles,
+};
 
 const HISTORY_DB_PATH: &str = "history/history.db";
+const WAV_HEADER_BYTES: u64 = 44;
+const LOW_DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+
+static LOW_DISK_WARNED: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum HistoryKind {
     Dictation,
     Diary,
+    /// 翻译输入，`text` 为译文
+    Translation,
+    /// 语音编辑选中的文本，`text` 为编辑结果
+    Edit,
+    /// 由一天或一周的语音日记生成的摘要
+    Digest,
 }
 
 impl HistoryKind {
@@ -26,6 +44,9 @@ impl HistoryKind {
         match self {
             HistoryKind::Dictation => "dictation",
             HistoryKind::Diary => "diary",
+            HistoryKind::Translation => "translation",
+            HistoryKind::Edit => "edit",
+            HistoryKind::Digest => "digest",
         }
     }
 
@@ -33,6 +54,9 @@ impl HistoryKind {
         match value {
             "dictation" => Some(HistoryKind::Dictation),
             "diary" => Some(HistoryKind::Diary),
+            "translation" => Some(HistoryKind::Translation),
+            "edit" => Some(HistoryKind::Edit),
+            "digest" => Some(HistoryKind::Digest),
             _ => None,
         }
     }
@@ -46,6 +70,14 @@ pub enum LlmPolishStatus {
     Skipped,
     QuotaExceeded,
     Failed,
+    AuthFailed,
+    RateLimited,
+    Timeout,
+    NetworkError,
+    BadResponse,
+    ContentFiltered,
+    /// 润色结果偏离原文，已回退为识别原文
+    Diverged,
 }
 
 impl LlmPolishStatus {
@@ -55,6 +87,13 @@ impl LlmPolishStatus {
             LlmPolishStatus::Skipped => "skipped",
             LlmPolishStatus::QuotaExceeded => "quota_exceeded",
             LlmPolishStatus::Failed => "failed",
+            LlmPolishStatus::AuthFailed => "auth_failed",
+            LlmPolishStatus::RateLimited => "rate_limited",
+            LlmPolishStatus::Timeout => "timeout",
+            LlmPolishStatus::NetworkError => "network_error",
+            LlmPolishStatus::BadResponse => "bad_response",
+            LlmPolishStatus::ContentFiltered => "content_filtered",
+            LlmPolishStatus::Diverged => "diverged",
         }
     }
 
@@ -63,9 +102,21 @@ impl LlmPolishStatus {
             "success" => LlmPolishStatus::Success,
             "quota_exceeded" => LlmPolishStatus::QuotaExceeded,
             "failed" => LlmPolishStatus::Failed,
+            "auth_failed" => LlmPolishStatus::AuthFailed,
+            "rate_limited" => LlmPolishStatus::RateLimited,
+            "timeout" => LlmPolishStatus::Timeout,
+            "network_error" => LlmPolishStatus::NetworkError,
+            "bad_response" => LlmPolishStatus::BadResponse,
+            "content_filtered" => LlmPolishStatus::ContentFiltered,
+            "diverged" => LlmPolishStatus::Diverged,
             _ => LlmPolishStatus::Skipped,
         }
     }
+
+    /// 润色未能完成且需要提示用户（未配置模型时静默跳过）
+    pub fn is_error(self) -> bool {
+        !matches!(self, LlmPolishStatus::Success | LlmPolishStatus::Skipped)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Type)]
@@ -88,6 +139,42 @@ pub struct HistoryEntry {
     pub source_app: Option<String>,
     pub llm_polish_status: LlmPolishStatus,
     pub llm_polish_error: Option<String>,
+    /// 翻译前的识别原文，或语音编辑前选中的文本
+    pub original_text: Option<String>,
+    /// 语音编辑时的口述指令
+    pub instruction: Option<String>,
+    /// 自动生成的标签，与标题一起在保存后由后台任务写入
+    pub tags: Vec<String>,
+    /// 日记摘要引用的日记记录 ID
+    pub source_ids: Vec<String>,
+    pub digest_period: Option<DigestPeriod>,
+    /// 日记摘要覆盖的起始日期（本地时间，`YYYY-MM-DD`），周摘要为当周周一
+    pub digest_date: Option<String>,
+}
+
+/// 日记摘要覆盖的时间范围
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestPeriod {
+    Day,
+    Week,
+}
+
+impl DigestPeriod {
+    fn as_str(self) -> &'static str {
+        match self {
+            DigestPeriod::Day => "day",
+            DigestPeriod::Week => "week",
+        }
+    }
+
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(DigestPeriod::Day),
+            "week" => Some(DigestPeriod::Week),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -146,6 +233,18 @@ pub struct NewHistoryEntry {
     pub llm_polish_status: LlmPolishStatus,
     #[serde(default)]
     pub llm_polish_error: Option<String>,
+    #[serde(default)]
+    pub original_text: Option<String>,
+    #[serde(default)]
+    pub instruction: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub source_ids: Vec<String>,
+    #[serde(default)]
+    pub digest_period: Option<DigestPeriod>,
+    #[serde(default)]
+    pub digest_date: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Type)]
@@ -200,7 +299,13 @@ fn init_db(conn: &Connection) -> Result<(), String> {
             llm_total_tokens INTEGER,
             source_app TEXT,
             llm_polish_status TEXT DEFAULT 'skipped',
-            llm_polish_error TEXT
+            llm_polish_error TEXT,
+            original_text TEXT,
+            instruction TEXT,
+            tags TEXT,
+            source_ids TEXT,
+            digest_period TEXT,
+            digest_date TEXT
         );
 
         CREATE INDEX IF NOT EXISTS idx_history_kind_created_at
@@ -208,6 +313,7 @@ fn init_db(conn: &Connection) -> Result<(), String> {
         "#,
     )
     .map_err(|e| format!("初始化历史记录数据库失败: {e}"))?;
+    tasks::init_table(conn)?;
 
     let _ = conn.execute(
         "ALTER TABLE history_entries ADD COLUMN llm_variant_id TEXT",
@@ -229,6 +335,24 @@ fn init_db(conn: &Connection) -> Result<(), String> {
         "ALTER TABLE history_entries ADD COLUMN llm_polish_error TEXT",
         [],
     );
+    let _ = conn.execute(
+        "ALTER TABLE history_entries ADD COLUMN original_text TEXT",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE history_entries ADD COLUMN instruction TEXT",
+        [],
+    );
+    let _ = conn.execute("ALTER TABLE history_entries ADD COLUMN tags TEXT", []);
+    let _ = conn.execute("ALTER TABLE history_entries ADD COLUMN source_ids TEXT", []);
+    let _ = conn.execute(
+        "ALTER TABLE history_entries ADD COLUMN digest_period TEXT",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE history_entries ADD COLUMN digest_date TEXT",
+        [],
+    );
 
     Ok(())
 }
@@ -259,6 +383,15 @@ fn map_history_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistoryEntry> {
             .map(LlmPolishStatus::from_str)
             .unwrap_or_default(),
         llm_polish_error: row.get("llm_polish_error")?,
+        original_text: row.get("original_text")?,
+        instruction: row.get("instruction")?,
+        tags: decode_list(row.get("tags")?),
+        source_ids: decode_list(row.get("source_ids")?),
+        digest_period: row
+            .get::<_, Option<String>>("digest_period")?
+            .as_deref()
+            .and_then(DigestPeriod::from_str),
+        digest_date: row.get("digest_date")?,
     })
 }
 
@@ -313,8 +446,8 @@ fn insert_history_entry(
     let total_words = entry.total_words.unwrap_or(0) as i64;
     let total_tokens = entry.total_tokens.unwrap_or(0) as i64;
     conn.execute(
-        "INSERT OR REPLACE INTO history_entries (id, title, text, kind, created_at, duration_seconds, audio_file_path, llm_model, llm_variant_id, asr_model, asr_variant_id, total_words, total_tokens, llm_total_tokens, source_app, llm_polish_status, llm_polish_error)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        "INSERT OR REPLACE INTO history_entries (id, title, text, kind, created_at, duration_seconds, audio_file_path, llm_model, llm_variant_id, asr_model, asr_variant_id, total_words, total_tokens, llm_total_tokens, source_app, llm_polish_status, llm_polish_error, original_text, instruction, tags, source_ids, digest_period, digest_date)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
         params![
             id,
             entry.title.clone(),
@@ -336,6 +469,12 @@ fn insert_history_entry(
             entry.source_app.clone(),
             entry.llm_polish_status.as_str(),
             entry.llm_polish_error.clone(),
+            entry.original_text.clone(),
+            entry.instruction.clone(),
+            encode_list(&entry.tags),
+            encode_list(&entry.source_ids),
+            entry.digest_period.map(DigestPeriod::as_str),
+            entry.digest_date.clone(),
         ],
     )
     .map_err(|e| format!("写入历史记录失败: {e}"))?;
@@ -358,9 +497,53 @@ fn insert_history_entry(
         source_app: entry.source_app.clone(),
         llm_polish_status: entry.llm_polish_status,
         llm_polish_error: entry.llm_polish_error.clone(),
+        original_text: entry.original_text.clone(),
+        instruction: entry.instruction.clone(),
+        tags: entry.tags.clone(),
+        source_ids: entry.source_ids.clone(),
+        digest_period: entry.digest_period,
+        digest_date: entry.digest_date.clone(),
     })
 }
 
+/// 标签等字符串列表以 JSON 数组保存，空列表存为 NULL
+fn encode_list(values: &[String]) -> Option<String> {
+    (!values.is_empty()).then(|| serde_json::to_string(values).unwrap_or_default())
+}
+
+fn decode_list(value: Option<String>) -> Vec<String> {
+    value
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+fn update_title_and_tags(
+    conn: &Connection,
+    id: &str,
+    title: &str,
+    tags: &[String],
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE history_entries SET title = ?1, tags = ?2 WHERE id = ?3",
+        params![title, encode_list(tags), id],
+    )
+    .map_err(|e| format!("更新历史记录标题失败: {e}"))?;
+    Ok(())
+}
+
+fn query_untitled_entries(conn: &Connection, limit: u32) -> Result<Vec<HistoryEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM history_entries WHERE (title IS NULL OR trim(title) = '') AND trim(text) <> '' ORDER BY created_at DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![limit as i64], map_history_row)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
 fn remove_history_entry(conn: &Connection, id: &str) -> Result<Option<HistoryRemovalInfo>, String> {
     let info = conn
         .query_row(
@@ -386,6 +569,7 @@ fn remove_history_entry(conn: &Connection, id: &str) -> Result<Option<HistoryRem
         .map_err(|e| format!("查询历史记录失败: {e}"))?;
     conn.execute("DELETE FROM history_entries WHERE id = ?1", params![id])
         .map_err(|e| format!("删除历史记录失败: {e}"))?;
+    tasks::delete_for_entry(conn, id)?;
     Ok(info)
 }
 
@@ -404,6 +588,7 @@ fn clear_history(conn: &Connection) -> Result<Vec<String>, String> {
     }
     conn.execute("DELETE FROM history_entries", [])
         .map_err(|e| format!("清空历史记录失败: {e}"))?;
+    tasks::delete_all(conn)?;
     Ok(files)
 }
 
@@ -441,7 +626,110 @@ fn read_stats(conn: &Connection) -> Result<HistoryStats, String> {
     }
 }
 
-async fn with_connection<T, F>(app: AppHandle, task: F) -> Result<T, String>
+/// 同一应用、同类的最近几条记录，按时间倒序，用于润色时附带上下文；来源应用为空时匹配同样为空的记录
+pub async fn recent_entries(
+    app: &AppHandle<Wry>,
+    kind: HistoryKind,
+    source_app: Option<String>,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, String> {
+    with_connection(app.clone(), move |conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT * FROM history_entries WHERE kind = ?1 AND source_app IS ?2 ORDER BY created_at DESC LIMIT ?3",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![kind.as_str(), source_app, limit as i64],
+                map_history_row,
+            )
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// 写回后台生成的标题与标签
+pub async fn set_title_and_tags(
+    app: &AppHandle<Wry>,
+    id: String,
+    title: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    with_connection(app.clone(), move |conn| {
+        update_title_and_tags(&conn, &id, &title, &tags)
+    })
+    .await
+}
+
+/// 指定类型在 `[start, end)` 内的记录，按时间正序；时间为 UTC 的 RFC 3339 字符串，与保存格式一致
+pub async fn entries_of_kind_between(
+    app: &AppHandle<Wry>,
+    kind: HistoryKind,
+    start: String,
+    end: String,
+) -> Result<Vec<HistoryEntry>, String> {
+    with_connection(app.clone(), move |conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT * FROM history_entries WHERE kind = ?1 AND created_at >= ?2 AND created_at < ?3 ORDER BY created_at ASC",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![kind.as_str(), start, end], map_history_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// 同一周期已生成的日记摘要，重新生成时沿用其 ID 覆盖
+pub async fn find_digest(
+    app: &AppHandle<Wry>,
+    period: DigestPeriod,
+    date: String,
+) -> Result<Option<HistoryEntry>, String> {
+    with_connection(app.clone(), move |conn| {
+        conn.query_row(
+            "SELECT * FROM history_entries WHERE kind = ?1 AND digest_period = ?2 AND digest_date = ?3",
+            params![HistoryKind::Digest.as_str(), period.as_str(), date],
+            map_history_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// 按 ID 读取单条记录
+pub async fn get_entry(app: &AppHandle<Wry>, id: String) -> Result<Option<HistoryEntry>, String> {
+    with_connection(app.clone(), move |conn| {
+        conn.query_row(
+            "SELECT * FROM history_entries WHERE id = ?1",
+            params![id],
+            map_history_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// 尚未生成标题的最近几条记录，按时间倒序
+pub async fn untitled_entries(
+    app: &AppHandle<Wry>,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, String> {
+    with_connection(app.clone(), move |conn| {
+        query_untitled_entries(&conn, limit)
+    })
+    .await
+}
+
+pub(crate) async fn with_connection<T, F>(app: AppHandle, task: F) -> Result<T, String>
 where
     T: Send + 'static,
     F: FnOnce(Connection) -> Result<T, String> + Send + 'static,
@@ -472,7 +760,15 @@ pub async fn add_history_entry(
     app: AppHandle,
     entry: NewHistoryEntry,
 ) -> Result<HistoryEntry, String> {
-    with_connection(app, move |conn| insert_history_entry(&conn, &entry)).await
+    let saved =
+        with_connection(app.clone(), move |conn| insert_history_entry(&conn, &entry)).await?;
+    if saved.title.is_none() && !saved.text.trim().is_empty() {
+        titles::spawn(&app, saved.id.clone(), saved.text.clone());
+    }
+    if saved.kind == HistoryKind::Diary && !saved.text.trim().is_empty() {
+        tasks::spawn(&app, saved.clone());
+    }
+    Ok(saved)
 }
 
 async fn delete_history_audio_file(app: &AppHandle<Wry>, path: &str) {
@@ -586,6 +882,15 @@ pub async fn save_history_audio_clip(
         .await
         .map_err(|e| format!("无法创建音频目录: {e}"))?;
 
+    // 16-bit 单声道 PCM，加上 WAV 文件头
+    let required = (samples.len() as u64)
+        .saturating_mul(2)
+        .saturating_add(WAV_HEADER_BYTES);
+    let available = disk::ensure_available_space(&audio_root, required, "保存录音")?;
+    if available.saturating_sub(required) < LOW_DISK_WARNING_BYTES {
+        warn_low_disk_space(app, available.saturating_sub(required));
+    }
+
     let file_name = format!("{}.wav", Uuid::new_v4());
     let audio_path = audio_root.join(&file_name);
     let samples = samples.to_vec();
@@ -615,6 +920,31 @@ pub async fn save_history_audio_clip(
     Ok(format!("audio/{file_name}"))
 }
 
+/// 历史音频所在磁盘即将写满时提醒用户；每次启动只提醒一次，避免每条录音都弹出通知
+fn warn_low_disk_space(app: &AppHandle<Wry>, remaining: u64) {
+    tracing::warn!(
+        target = "miaoyu_history",
+        remaining_bytes = remaining,
+        "历史音频所在磁盘剩余空间不足"
+    );
+    if LOW_DISK_WARNED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = notification::show_notification(
+            app,
+            format!(
+                "磁盘剩余空间仅 {}，建议清理历史录音",
+                disk::format_bytes(remaining)
+            ),
+            NotificationType::Info,
+            None,
+        )
+        .await;
+    });
+}
+
 fn resolve_history_audio_path(app: &AppHandle<Wry>, raw: &str) -> Result<PathBuf, String> {
     let history_root = app
         .path()
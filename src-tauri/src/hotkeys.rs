@@ -10,8 +10,12 @@ use tauri_plugin_store::StoreExt;
 use tauri_specta::Event;
 
 use crate::{
-    audio::{cancel_dictating, start_dictating, start_voice_diary, stop_dictating},
+    audio::{
+        cancel_dictating, start_dictating, start_editing_selection, start_translating,
+        start_voice_diary, stop_dictating,
+    },
     history::HistoryKind,
+    prompts, translation,
     windows::ShowAppWindow,
     AppState, AudioState,
 };
@@ -53,6 +57,8 @@ impl From<Hotkey> for Shortcut {
 pub enum HotkeyAction {
     StartDictating,
     StartVoiceDiary,
+    StartTranslating,
+    EditSelection,
     #[serde(other)]
     Other,
 }
@@ -90,6 +96,24 @@ impl HotkeysStore {
                 alt: true,
                 shift: true,
             });
+        self.hotkeys
+            .entry(HotkeyAction::StartTranslating)
+            .or_insert(Hotkey {
+                code: Code::Space,
+                meta: false,
+                ctrl: true,
+                alt: true,
+                shift: false,
+            });
+        self.hotkeys
+            .entry(HotkeyAction::EditSelection)
+            .or_insert(Hotkey {
+                code: Code::KeyE,
+                meta: false,
+                ctrl: true,
+                alt: true,
+                shift: false,
+            });
     }
 }
 
@@ -116,6 +140,26 @@ impl Default for HotkeysStore {
                 shift: true,
             },
         );
+        hotkeys.insert(
+            HotkeyAction::StartTranslating,
+            Hotkey {
+                code: Code::Space,
+                meta: false,
+                ctrl: true,
+                alt: true,
+                shift: false,
+            },
+        );
+        hotkeys.insert(
+            HotkeyAction::EditSelection,
+            Hotkey {
+                code: Code::KeyE,
+                meta: false,
+                ctrl: true,
+                alt: true,
+                shift: false,
+            },
+        );
 
         Self { hotkeys }
     }
@@ -192,8 +236,16 @@ pub fn init(app: &AppHandle) {
 
 async fn handle_hotkey(app: AppHandle, action: HotkeyAction) -> Result<(), String> {
     let target_kind = match action {
+        // 托盘中开启翻译模式后，语音识别快捷键也按翻译输入处理
+        HotkeyAction::StartDictating
+            if translation::translation_settings(&app).dictation_translates =>
+        {
+            HistoryKind::Translation
+        }
         HotkeyAction::StartDictating => HistoryKind::Dictation,
         HotkeyAction::StartVoiceDiary => HistoryKind::Diary,
+        HotkeyAction::StartTranslating => HistoryKind::Translation,
+        HotkeyAction::EditSelection => HistoryKind::Edit,
         HotkeyAction::Other => return Ok(()),
     };
 
@@ -204,10 +256,21 @@ async fn handle_hotkey(app: AppHandle, action: HotkeyAction) -> Result<(), Strin
     };
 
     match current_state {
-        AudioState::Idle => match target_kind {
-            HistoryKind::Dictation => start_dictating(app).await,
-            HistoryKind::Diary => start_voice_diary(app).await,
-        },
+        AudioState::Idle => {
+            // 语音识别快捷键转为翻译时不沿用其润色模式
+            let template_id = match (action, target_kind) {
+                (HotkeyAction::StartDictating, HistoryKind::Translation) => None,
+                _ => prompts::hotkey_template(&app, action),
+            };
+            match target_kind {
+                HistoryKind::Dictation => start_dictating(app, template_id).await,
+                HistoryKind::Diary => start_voice_diary(app, template_id).await,
+                HistoryKind::Translation => start_translating(app, template_id).await,
+                HistoryKind::Edit => start_editing_selection(app, template_id).await,
+                // 日记摘要由命令生成，没有对应的录音快捷键
+                HistoryKind::Digest => Ok(()),
+            }
+        }
         AudioState::Recording => {
             if current_kind != target_kind {
                 tracing::debug!(
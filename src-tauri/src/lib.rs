@@ -1,22 +1,41 @@
 mod audio;
+mod chinese_conversion;
 mod clipboard;
+mod context;
+mod digest;
+mod disk;
 mod history;
 mod hotkeys;
 mod llm;
+mod llm_protocol;
+mod local_llm;
 mod models;
 mod notification;
 mod permissions;
+mod pii;
+mod polish_guard;
+mod prompts;
+mod replacements;
+mod secrets;
 mod settings;
+mod source_app;
+mod tasks;
+mod titles;
+mod translation;
 mod tray;
+mod typography;
+mod voice_commands;
 mod windows;
 
 use crate::audio::{
-    cancel_dictating, dictating::DictatingStream, download_offline_models,
-    get_offline_models_status, start_dictating, start_voice_diary, stop_dictating,
+    cancel_dictating, cancel_offline_model_download, dictating::DictatingStream,
+    download_offline_models, get_offline_models_status, start_dictating, start_editing_selection,
+    start_translating, start_voice_diary, stop_dictating,
 };
 use crate::history::HistoryKind;
 use crate::settings::SettingsStore;
 use crate::windows::{AppWindowId, ShowAppWindow};
+pub use secrets::RedactingWriter;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::str::FromStr;
@@ -40,6 +59,12 @@ pub struct AudioRuntimeState {
     pub state: AudioState,
     pub dictating_stream: Option<DictatingStream>,
     pub history_kind: HistoryKind,
+    /// 本次录音指定的润色模式
+    pub prompt_template: Option<String>,
+    /// 开始录音时的前台应用
+    pub source_app: Option<String>,
+    /// 语音编辑开始时选中的文本
+    pub selected_text: Option<String>,
 }
 
 pub struct AppState {
@@ -54,6 +79,9 @@ impl Default for AppState {
                 state: AudioState::Idle,
                 dictating_stream: None,
                 history_kind: HistoryKind::Dictation,
+                prompt_template: None,
+                source_app: None,
+                selected_text: None,
             }),
             pending_navigation: Mutex::new(None),
         }
@@ -95,6 +123,8 @@ pub async fn run(_logging_handle: LoggingHandle) {
             hotkeys::set_hotkey,
             start_dictating,
             start_voice_diary,
+            start_translating,
+            start_editing_selection,
             cancel_dictating,
             stop_dictating,
             notification::show_notification,
@@ -102,17 +132,55 @@ pub async fn run(_logging_handle: LoggingHandle) {
             settings::get_autostart_enabled,
             settings::set_autostart_enabled,
             settings::set_onboarding_completed,
+            pii::set_pii_masking,
+            pii::preview_pii_masking,
+            translation::set_translation_settings,
+            context::set_conversation_context,
+            voice_commands::set_voice_commands,
+            voice_commands::reset_voice_commands,
+            voice_commands::preview_voice_commands,
+            replacements::set_replacement_rules,
+            replacements::preview_replacement_rules,
+            replacements::export_replacement_rules,
+            replacements::import_replacement_rules,
+            typography::set_typography,
+            typography::preview_typography,
+            chinese_conversion::set_chinese_conversion,
+            chinese_conversion::preview_chinese_conversion,
             llm::test_llm_api_key,
+            local_llm::list_local_llm_models,
+            prompts::get_prompts_store,
+            prompts::create_prompt_template,
+            prompts::update_prompt_template,
+            prompts::delete_prompt_template,
+            prompts::set_default_prompt_template,
+            prompts::set_hotkey_prompt_template,
+            prompts::get_prompt,
+            prompts::set_prompt,
+            prompts::reset_prompt,
+            prompts::set_prompt_glossary,
             models::get_supported_models,
             models::get_models_store,
             models::set_active_text_model,
             models::update_text_model_credentials,
+            models::add_custom_llm_provider,
+            models::update_custom_llm_provider,
+            models::delete_custom_llm_provider,
+            models::set_llm_fallback_models,
+            models::set_llm_request_settings,
             models::set_active_asr_model,
             models::update_asr_credentials,
             get_offline_models_status,
             download_offline_models,
+            cancel_offline_model_download,
             history::list_history_entries,
             history::add_history_entry,
+            titles::backfill_history_titles,
+            digest::generate_diary_digest,
+            tasks::list_tasks,
+            tasks::set_task_done,
+            tasks::extract_tasks,
+            tasks::export_tasks_ics,
             history::delete_history_entry,
             history::clear_history_entries,
             history::get_history_stats,
@@ -122,6 +190,10 @@ pub async fn run(_logging_handle: LoggingHandle) {
             hotkeys::OnEscapePress,
             notification::ShowNotification,
             audio::OnTranscribingStage,
+            audio::OnOfflineModelDownload,
+            llm::OnPolishPreview,
+            titles::OnHistoryTitled,
+            tasks::OnTasksExtracted,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
         .typ::<hotkeys::HotkeysStore>()
@@ -160,8 +232,10 @@ pub async fn run(_logging_handle: LoggingHandle) {
             let app_handle = app.handle().clone();
 
             specta_builder.mount_events(&app_handle);
+            models::init(&app_handle);
             hotkeys::init(&app_handle);
             settings::init(&app_handle);
+            prompts::init(&app_handle);
             let onboarding_completed = settings::is_onboarding_completed(&app_handle);
             if onboarding_completed {
                 tray::create_tray(&app_handle).ok();
@@ -1,19 +1,304 @@
-use anyhow::{anyhow, Context, Result};
-use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Wry};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, EventTarget, Wry};
+use tauri_specta::Event;
 
 use crate::{
-    models::{self, LlmProviderConfig},
-    settings::SettingsStore,
+    context::ConversationContext,
+    history::{HistoryKind, LlmPolishStatus},
+    llm_protocol::{self, ChatInput, ChatTurn, TokenUsage},
+    local_llm,
+    models::{self, LlmProtocol, LlmProviderConfig},
+    pii,
+    prompts::{self, PromptTemplate, PromptVariables},
+    secrets,
+    windows::AppWindowId,
 };
 
 const DEEPSEEK_API_URL: &str = "https://api.deepseek.com/v1/chat/completions";
 const DEEPSEEK_MODEL: &str = "deepseek-chat";
+/// 流式预览事件的最小间隔，避免每个 token 都触发一次前端渲染
+const PREVIEW_EMIT_INTERVAL: Duration = Duration::from_millis(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+/// Retry-After 超过该值时不再等待，直接切换到备用模型
+const RETRY_AFTER_LIMIT: Duration = Duration::from_secs(10);
+
+/// 润色过程中逐步生成的文本，推送给转写窗口做实时预览
+#[derive(Serialize, Type, tauri_specta::Event, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnPolishPreview {
+    pub text: String,
+}
+
+type PreviewFn<'a> = &'a (dyn Fn(&str) + Send + Sync);
 
 struct LlmRuntimeConfig {
     api_key: String,
     api_url: String,
     model_name: String,
+    extra_headers: BTreeMap<String, String>,
+    local: bool,
+    protocol: LlmProtocol,
+    timeout: Duration,
+    max_retries: u32,
+}
+
+/// 文本模型调用失败的原因，决定润色状态并给出对应的处理建议
+#[derive(Debug)]
+pub enum LlmError {
+    /// 未选择文本模型或缺少 API 密钥
+    NotConfigured(String),
+    /// 内置体验额度用完（`trial`）或服务账户余额不足
+    QuotaExceeded {
+        trial: bool,
+    },
+    /// 密钥无效、已过期或无权访问该模型
+    AuthFailed {
+        status: u16,
+    },
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    Timeout {
+        seconds: u64,
+    },
+    Network(String),
+    /// 服务端出错或响应无法解析；5xx 时值得重试
+    BadResponse {
+        status: Option<u16>,
+        detail: String,
+    },
+    /// 输入或输出触发了服务商的内容安全审核
+    ContentFiltered,
+    /// 读取本地配置失败
+    Internal(String),
+}
+
+/// 服务商在内容审核拦截时返回的错误标识；只收录明确的错误码与提示，
+/// 像 "sensitive" 这样的宽泛单词会误伤 "case-sensitive" 等普通参数错误
+const CONTENT_FILTER_MARKERS: [&str; 5] = [
+    "content_filter",
+    "content_policy",
+    "content exists risk",
+    "data_inspection_failed",
+    "敏感内容",
+];
+
+impl LlmError {
+    fn from_reqwest(error: reqwest::Error, timeout: Duration) -> Self {
+        if error.is_timeout() {
+            LlmError::Timeout {
+                seconds: timeout.as_secs(),
+            }
+        } else if error.is_decode() {
+            LlmError::BadResponse {
+                status: None,
+                detail: error.to_string(),
+            }
+        } else {
+            LlmError::Network(error.to_string())
+        }
+    }
+
+    fn from_status(status: reqwest::StatusCode, retry_after: Option<Duration>, body: &str) -> Self {
+        let body = body.to_lowercase();
+        match status.as_u16() {
+            // Gemini 对无效密钥返回 400
+            400 if body.contains("api_key_invalid") => LlmError::AuthFailed {
+                status: status.as_u16(),
+            },
+            401 | 403 => LlmError::AuthFailed {
+                status: status.as_u16(),
+            },
+            402 => LlmError::QuotaExceeded { trial: false },
+            // OpenAI 在余额不足时同样返回 429
+            429 if body.contains("insufficient_quota") => LlmError::QuotaExceeded { trial: false },
+            429 => LlmError::RateLimited { retry_after },
+            400 | 422
+                if CONTENT_FILTER_MARKERS
+                    .iter()
+                    .any(|marker| body.contains(marker)) =>
+            {
+                LlmError::ContentFiltered
+            }
+            code => LlmError::BadResponse {
+                status: Some(code),
+                detail: status.canonical_reason().unwrap_or("未知错误").to_string(),
+            },
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            LlmError::RateLimited { .. } => true,
+            LlmError::BadResponse {
+                status: Some(status),
+                ..
+            } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// 模型未配置或额度用完，请求根本没有发出
+    fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            LlmError::NotConfigured(_) | LlmError::QuotaExceeded { .. }
+        )
+    }
+
+    /// 备用模型依次失败时保留最值得报告的错误：第一个真正发出请求后的错误优先，
+    /// 否则保留首选模型的错误
+    fn keep_reportable(reported: &mut Option<LlmError>, error: LlmError) {
+        let replace = match reported {
+            None => true,
+            Some(current) => current.is_unavailable() && !error.is_unavailable(),
+        };
+        if replace {
+            *reported = Some(error);
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            LlmError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn polish_status(&self) -> LlmPolishStatus {
+        match self {
+            LlmError::NotConfigured(_) => LlmPolishStatus::Skipped,
+            LlmError::QuotaExceeded { .. } => LlmPolishStatus::QuotaExceeded,
+            LlmError::AuthFailed { .. } => LlmPolishStatus::AuthFailed,
+            LlmError::RateLimited { .. } => LlmPolishStatus::RateLimited,
+            LlmError::Timeout { .. } => LlmPolishStatus::Timeout,
+            LlmError::Network(_) => LlmPolishStatus::NetworkError,
+            LlmError::BadResponse { .. } => LlmPolishStatus::BadResponse,
+            LlmError::ContentFiltered => LlmPolishStatus::ContentFiltered,
+            LlmError::Internal(_) => LlmPolishStatus::Failed,
+        }
+    }
+
+    /// 提示用户如何处理该错误
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            LlmError::NotConfigured(_) => "请在“模型管理”中选择文本模型并设置 API 密钥",
+            LlmError::QuotaExceeded { trial: true } => "请在“模型管理”配置 API 密钥",
+            LlmError::QuotaExceeded { trial: false } => "请为服务账户充值，或在“模型管理”切换模型",
+            LlmError::AuthFailed { .. } => "请在“模型管理”检查 API 密钥是否正确、是否已过期",
+            LlmError::RateLimited { .. } => "请稍后再试，或在“模型管理”配置备用模型",
+            LlmError::Timeout { .. } => "请检查网络，或在“模型管理”调大请求超时",
+            LlmError::Network(_) => "请检查网络连接、代理设置或本地模型服务是否已启动",
+            LlmError::BadResponse { .. } => "服务暂时不可用，请稍后再试或切换模型",
+            LlmError::ContentFiltered => "本次已保留识别原文，可换用其他模型润色",
+            LlmError::Internal(_) => "请重启应用后再试",
+        }
+    }
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmError::NotConfigured(detail) => write!(f, "{detail}")?,
+            LlmError::QuotaExceeded { trial: true } => write!(f, "体验额度已用完")?,
+            LlmError::QuotaExceeded { trial: false } => write!(f, "文本模型账户余额不足")?,
+            LlmError::AuthFailed { status } => write!(f, "文本模型鉴权失败（HTTP {status}）")?,
+            LlmError::RateLimited { .. } => write!(f, "文本模型请求过于频繁，已被限流")?,
+            LlmError::Timeout { seconds } => write!(f, "调用文本模型超时（{seconds} 秒）")?,
+            LlmError::Network(detail) => write!(f, "无法连接文本模型服务: {detail}")?,
+            LlmError::BadResponse {
+                status: Some(status),
+                detail,
+            } => write!(f, "文本模型服务返回错误（HTTP {status} {detail}）")?,
+            LlmError::BadResponse {
+                status: None,
+                detail,
+            } => write!(f, "解析文本模型响应失败: {detail}")?,
+            LlmError::ContentFiltered => write!(f, "内容触发了文本模型的安全审核")?,
+            LlmError::Internal(detail) => write!(f, "读取文本模型配置失败: {detail}")?,
+        }
+        write!(f, "。{}", self.remediation())
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+/// 地址是否指向本机（localhost 或回环地址），无法解析时按远程处理
+fn is_loopback_url(api_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(api_url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    }
+}
+
+/// 解析 Retry-After，支持秒数与 HTTP 日期两种格式
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// 日志中保留的错误信息长度上限
+const ERROR_MESSAGE_LOG_LIMIT: usize = 200;
+
+/// 从错误响应中取出错误码与错误信息用于日志，不记录响应原文；
+/// 兼容 OpenAI、Anthropic、Gemini（`{"error": {...}}`）与通义（顶层 `code`、`message`）
+fn error_summary(body: &str, user_text: &str) -> (Option<String>, Option<String>) {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return (None, None);
+    };
+    let error = value
+        .get("error")
+        .filter(|error| error.is_object())
+        .unwrap_or(&value);
+    let field = |name: &str| match error.get(name)? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    };
+    let code = field("code")
+        .or_else(|| field("type"))
+        .or_else(|| field("status"));
+    let message = field("message").map(|message| {
+        let message = if user_text.trim().is_empty() {
+            message
+        } else {
+            message.replace(user_text, "<口述内容>")
+        };
+        message.chars().take(ERROR_MESSAGE_LOG_LIMIT).collect()
+    });
+    (code, message)
+}
+
+fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY)
 }
 
 fn resolve_llm_runtime_config(
@@ -21,25 +306,28 @@ fn resolve_llm_runtime_config(
     model_override: Option<&str>,
     provider_override: Option<&str>,
     api_key_override: Option<String>,
-) -> Result<LlmRuntimeConfig> {
+) -> Result<LlmRuntimeConfig, LlmError> {
     let entry = models::active_llm_entry(app, model_override, provider_override)
-        .map_err(|e| anyhow!("读取文本模型配置失败: {e}"))?
-        .ok_or_else(|| anyhow!("未配置文本模型，请先在“模型管理”中设置 API 密钥"))?;
+        .map_err(LlmError::Internal)?
+        .ok_or_else(|| LlmError::NotConfigured("未配置文本模型".to_string()))?;
 
-    let config = models::supported_models();
+    let config = models::resolve_supported_models(app).map_err(LlmError::Internal)?;
     let model = config
         .llm_models
         .iter()
         .find(|model| model.id == entry.text_model_id)
-        .ok_or_else(|| anyhow!("未知的文本模型: {}", entry.text_model_id))?;
+        .ok_or_else(|| {
+            LlmError::NotConfigured(format!("未知的文本模型: {}", entry.text_model_id))
+        })?;
     let provider = model
         .providers
         .iter()
         .find(|provider| provider.id == entry.provider)
         .or_else(|| model.providers.first())
-        .ok_or_else(|| anyhow!("文本模型 {} 缺少提供商配置", model.id))?;
+        .ok_or_else(|| LlmError::NotConfigured(format!("文本模型 {} 缺少提供商配置", model.id)))?;
 
     let api_key = resolve_api_key(api_key_override, entry.api_key.clone(), provider)?;
+    let request_settings = models::llm_request_settings(app);
 
     let api_url = provider
         .api_base_url
@@ -61,6 +349,11 @@ fn resolve_llm_runtime_config(
         api_key,
         api_url,
         model_name,
+        extra_headers: provider.extra_headers.clone(),
+        local: provider.local,
+        protocol: provider.protocol,
+        timeout: Duration::from_secs(u64::from(request_settings.timeout_seconds)),
+        max_retries: request_settings.max_retries,
     })
 }
 
@@ -70,7 +363,7 @@ fn resolve_api_key(
     api_key_override: Option<String>,
     entry_value: Option<String>,
     provider: &LlmProviderConfig,
-) -> Result<String> {
+) -> Result<String, LlmError> {
     if let Some(key) = api_key_override.and_then(|value| {
         let trimmed = value.trim().to_string();
         if trimmed.is_empty() {
@@ -99,11 +392,26 @@ fn resolve_api_key(
         }
     }
 
+    // 本地服务无需密钥，发送请求时会省略 Authorization 头
+    if provider.local {
+        return Ok(String::new());
+    }
+
+    // 自定义服务不能回退到内置密钥，否则会把 DeepSeek 密钥发给第三方地址
+    if provider.id == models::CUSTOM_PROVIDER_ID {
+        return Err(LlmError::NotConfigured(format!(
+            "自定义文本模型 {} 未配置 API Key 或环境变量",
+            provider.name
+        )));
+    }
+
     if let Some(value) = resolve_env_api_key("DEEPSEEK_API_KEY") {
         return Ok(value);
     }
 
-    Err(anyhow!("未配置文本模型 API Key，请在设置中配置"))
+    Err(LlmError::NotConfigured(
+        "未配置文本模型 API Key".to_string(),
+    ))
 }
 
 fn resolve_env_api_key(var: &str) -> Option<String> {
@@ -116,21 +424,23 @@ fn resolve_env_api_key(var: &str) -> Option<String> {
         }
     };
 
-    if let Ok(value) = std::env::var(var) {
-        if let Some(result) = normalize(value) {
-            return Some(result);
-        }
-    }
-
-    match var {
-        "DEEPSEEK_API_KEY" => option_env!("DEEPSEEK_API_KEY")
-            .map(|value| value.to_string())
-            .and_then(normalize),
-        "MODELSCOPE_ACCESS_TOKEN" => option_env!("MODELSCOPE_ACCESS_TOKEN")
-            .map(|value| value.to_string())
-            .and_then(normalize),
-        _ => None,
+    // 编译时内置的密钥同样需要在日志中隐藏
+    let value = std::env::var(var)
+        .ok()
+        .and_then(normalize)
+        .or_else(|| match var {
+            "DEEPSEEK_API_KEY" => option_env!("DEEPSEEK_API_KEY")
+                .map(|value| value.to_string())
+                .and_then(normalize),
+            "MODELSCOPE_ACCESS_TOKEN" => option_env!("MODELSCOPE_ACCESS_TOKEN")
+                .map(|value| value.to_string())
+                .and_then(normalize),
+            _ => None,
+        });
+    if let Some(value) = value.as_deref() {
+        secrets::register(value);
     }
+    value
 }
 
 pub fn has_configured_api_key(app: &AppHandle<Wry>) -> bool {
@@ -144,22 +454,33 @@ pub fn has_configured_api_key(app: &AppHandle<Wry>) -> bool {
             return true;
         }
 
-        if let Some(provider_env) = models::supported_models()
-            .llm_models
-            .iter()
-            .find(|model| model.id == entry.text_model_id)
+        let config = models::resolve_supported_models(app).ok();
+        let provider = config
+            .as_ref()
+            .and_then(|config| {
+                config
+                    .llm_models
+                    .iter()
+                    .find(|model| model.id == entry.text_model_id)
+            })
             .and_then(|model| {
                 model
                     .providers
                     .iter()
                     .find(|provider| provider.id == entry.provider)
-            })
-            .and_then(|provider| provider.api_key_env.as_deref())
-        {
+            });
+        if provider.is_some_and(|provider| provider.local) {
+            return true;
+        }
+        if let Some(provider_env) = provider.and_then(|provider| provider.api_key_env.as_deref()) {
             if resolve_env_api_key(provider_env).is_some() {
                 return true;
             }
         }
+
+        if entry.provider == models::CUSTOM_PROVIDER_ID {
+            return false;
+        }
     }
 
     resolve_env_api_key("DEEPSEEK_API_KEY")
@@ -176,44 +497,6 @@ pub const DEFAULT_SYSTEM_PROMPT: &str =
 4. 保持原意不变，不要添加或删除关键信息
 5. 直接返回优化后的文本，不要添加任何解释或前缀";
 
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Debug, Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-    stream: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    enable_thinking: Option<bool>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChatResponse {
-    choices: Vec<Choice>,
-    #[serde(default)]
-    usage: Option<ChatUsage>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: Message,
-}
-
-#[derive(Debug, Deserialize)]
-struct Message {
-    content: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChatUsage {
-    #[serde(default)]
-    total_tokens: Option<u32>,
-}
-
 #[derive(Debug)]
 struct ChatResult {
     content: String,
@@ -226,82 +509,203 @@ pub struct LLMService;
 pub struct PolishResult {
     pub text: String,
     pub total_tokens: Option<u32>,
+    /// 实际完成润色的文本模型与变体，可能是备用模型
+    pub text_model_id: Option<String>,
+    pub variant_id: Option<String>,
 }
 
 impl LLMService {
-    pub async fn polish_text(app: &AppHandle<Wry>, text: &str) -> Result<PolishResult> {
+    pub async fn polish_text(
+        app: &AppHandle<Wry>,
+        text: &str,
+        template: &PromptTemplate,
+        context: Option<&ConversationContext>,
+    ) -> Result<PolishResult, LlmError> {
         // 如果文本为空，直接返回
         if text.trim().is_empty() {
             return Ok(PolishResult {
                 text: text.to_string(),
                 total_tokens: None,
+                text_model_id: None,
+                variant_id: None,
             });
         }
 
-        let active_entry = models::active_llm_entry(app, None, None)
-            .map_err(|e| anyhow!("读取文本模型配置失败: {e}"))?
-            .ok_or_else(|| anyhow!("未配置文本模型，请先在“模型管理”中设置 API 密钥"))?;
-        models::check_llm_quota(app, &active_entry).map_err(|msg| anyhow!(msg))?;
+        let candidates = models::llm_candidate_models(app, template.model_override.as_deref())
+            .map_err(LlmError::Internal)?;
+        let mut reported_error: Option<LlmError> = None;
+        for (index, model_id) in candidates.iter().enumerate() {
+            match Self::polish_with_model(app, text, template, context, model_id, true).await {
+                Ok(result) => {
+                    if index > 0 {
+                        tracing::info!(
+                            target = "miaoyu_llm",
+                            model = model_id,
+                            "已使用备用文本模型完成润色"
+                        );
+                    }
+                    return Ok(result);
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        target = "miaoyu_llm",
+                        model = model_id,
+                        error = %error,
+                        "文本模型润色失败，尝试下一个模型"
+                    );
+                    LlmError::keep_reportable(&mut reported_error, error);
+                }
+            }
+        }
 
-        // 读取用户配置
-        let settings = SettingsStore::get(app).ok().flatten();
-        let runtime = resolve_llm_runtime_config(app, None, None, None)?;
-        let system_prompt = settings
-            .as_ref()
-            .and_then(|s| s.llm_system_prompt.as_deref())
-            .unwrap_or(DEFAULT_SYSTEM_PROMPT);
+        // 全部失败时调用方会回退为原始识别文本
+        Err(reported_error.unwrap_or_else(|| LlmError::NotConfigured("未配置文本模型".to_string())))
+    }
 
-        let chat_result = Self::send_chat_request(
-            &runtime.api_url,
-            &runtime.api_key,
-            &runtime.model_name,
-            system_prompt,
-            text,
+    async fn polish_with_model(
+        app: &AppHandle<Wry>,
+        text: &str,
+        template: &PromptTemplate,
+        context: Option<&ConversationContext>,
+        model_id: &str,
+        stream_preview: bool,
+    ) -> Result<PolishResult, LlmError> {
+        let entry = models::active_llm_entry(app, Some(model_id), None)
+            .map_err(LlmError::Internal)?
+            .ok_or_else(|| LlmError::NotConfigured("未配置文本模型".to_string()))?;
+        models::check_llm_quota(app, &entry)?;
+
+        let runtime = resolve_llm_runtime_config(app, Some(model_id), None, None)?;
+
+        // 只有发往本机地址的请求不脱敏；自定义服务即使选了本地后端，也可能指向局域网或云端。
+        // 上下文与本次输入共用占位符编号
+        let context_prompt = context.map(ConversationContext::prompt);
+        let (masked, context_prompt) = match (is_loopback_url(&runtime.api_url), context_prompt) {
+            (true, context_prompt) => (pii::MaskedText::default(), context_prompt),
+            (false, Some(context_prompt)) => {
+                let (masked, context_prompt) =
+                    pii::mask_with_context(text, &context_prompt, &pii::masking_settings(app))
+                        .map_err(LlmError::Internal)?;
+                (masked, Some(context_prompt))
+            }
+            (false, None) => (
+                pii::mask(text, &pii::masking_settings(app)).map_err(LlmError::Internal)?,
+                None,
+            ),
+        };
+        let history: Vec<ChatTurn> = context_prompt
+            .as_deref()
+            .map(|prompt| ConversationContext::turns(prompt).to_vec())
+            .unwrap_or_default();
+        let (text, system_prompt) = match masked.prompt_hint() {
+            Some(hint) => {
+                tracing::info!(
+                    target = "miaoyu_llm",
+                    masked = masked.len(),
+                    "已对隐私信息脱敏"
+                );
+                (
+                    masked.text.as_str(),
+                    format!("{}\n\n{hint}", template.system_prompt),
+                )
+            }
+            None => (text, template.system_prompt.clone()),
+        };
+
+        let emit_preview = |partial: &str| {
+            OnPolishPreview {
+                text: masked.restore_partial(partial),
+            }
+            .emit_to(
+                app,
+                EventTarget::webview_window(AppWindowId::AudioTranscribing.label()),
+            )
+            .ok();
+        };
+
+        let chat_result = Self::send_with_retry(
+            &runtime,
+            &system_prompt,
+            template.temperature,
             text,
+            &history,
+            stream_preview.then_some(&emit_preview as PreviewFn),
         )
         .await?;
+        let mut content =
+            masked
+                .restore(&chat_result.content)
+                .map_err(|detail| LlmError::BadResponse {
+                    status: None,
+                    detail,
+                })?;
+        if let Some(context) = context {
+            content = context.strip_echo(&content);
+        }
 
         tracing::info!(
             target = "miaoyu_llm",
+            template = template.id,
             original_length = text.len(),
-            polished_length = chat_result.content.len(),
+            polished_length = content.len(),
             "文本润色完成"
         );
 
         Ok(PolishResult {
-            text: chat_result.content,
+            text: content,
             total_tokens: chat_result.total_tokens,
+            text_model_id: Some(entry.text_model_id),
+            variant_id: Some(entry.id),
         })
     }
 
+    /// 后台任务使用的单次调用：不推送预览、不尝试备用模型，仍按设置对隐私信息脱敏
+    pub async fn complete(
+        app: &AppHandle<Wry>,
+        text: &str,
+        template: &PromptTemplate,
+    ) -> Result<PolishResult, LlmError> {
+        let entry = models::active_llm_entry(app, template.model_override.as_deref(), None)
+            .map_err(LlmError::Internal)?
+            .ok_or_else(|| LlmError::NotConfigured("未配置文本模型".to_string()))?;
+        Self::polish_with_model(app, text, template, None, &entry.text_model_id, false).await
+    }
+
     pub async fn test_api_key(
         app: &AppHandle<Wry>,
         model_override: Option<&str>,
         provider_override: Option<&str>,
         api_key_override: Option<String>,
-    ) -> Result<()> {
+    ) -> Result<(), LlmError> {
         if api_key_override.is_none() {
             if let Some(entry) = models::active_llm_entry(app, model_override, provider_override)
-                .map_err(|e| anyhow!("读取文本模型配置失败: {e}"))?
+                .map_err(LlmError::Internal)?
             {
-                models::check_llm_quota(app, &entry).map_err(|msg| anyhow!(msg))?;
+                models::check_llm_quota(app, &entry)?;
             }
         }
-        let settings = SettingsStore::get(app).ok().flatten();
         let runtime =
             resolve_llm_runtime_config(app, model_override, provider_override, api_key_override)?;
-        let system_prompt = settings
-            .as_ref()
-            .and_then(|s| s.llm_system_prompt.as_deref())
-            .unwrap_or(DEFAULT_SYSTEM_PROMPT);
+        if runtime.local {
+            return Self::check_local_server(app, model_override, &runtime).await;
+        }
+        let template = prompts::prepare_template(
+            app,
+            None,
+            &PromptVariables {
+                kind: HistoryKind::Dictation,
+                source_app: None,
+            },
+        );
 
         let _ = Self::send_chat_request(
-            &runtime.api_url,
-            &runtime.api_key,
-            &runtime.model_name,
-            system_prompt,
+            &runtime,
+            &template.system_prompt,
+            None,
             "ping",
             "ping",
+            &[],
+            None,
         )
         .await?;
 
@@ -314,74 +718,234 @@ impl LLMService {
         Ok(())
     }
 
+    /// 本地服务不需要密钥，改为检查服务是否在线、模型是否已安装
+    async fn check_local_server(
+        app: &AppHandle<Wry>,
+        model_override: Option<&str>,
+        runtime: &LlmRuntimeConfig,
+    ) -> Result<(), LlmError> {
+        let entry = models::active_llm_entry(app, model_override, None)
+            .map_err(LlmError::Internal)?
+            .ok_or_else(|| LlmError::NotConfigured("未配置文本模型".to_string()))?;
+        let provider = models::custom_llm_provider(app, &entry.text_model_id)
+            .map_err(LlmError::Internal)?
+            .ok_or_else(|| {
+                LlmError::NotConfigured(format!("未知的本地模型服务: {}", entry.text_model_id))
+            })?;
+        local_llm::health_check(
+            provider.backend,
+            models::local_server_root(&runtime.api_url),
+            &runtime.model_name,
+        )
+        .await
+        .map_err(|error| LlmError::Network(format!("{error:#}")))
+    }
+
+    /// 对限流与 5xx 按 Retry-After 或指数退避重试，其余错误直接返回
+    async fn send_with_retry(
+        runtime: &LlmRuntimeConfig,
+        system_prompt: &str,
+        temperature: Option<f32>,
+        user_text: &str,
+        history: &[ChatTurn<'_>],
+        on_preview: Option<PreviewFn<'_>>,
+    ) -> Result<ChatResult, LlmError> {
+        let mut attempt = 0;
+        loop {
+            let result = Self::send_chat_request(
+                runtime,
+                system_prompt,
+                temperature,
+                user_text,
+                user_text,
+                history,
+                on_preview,
+            )
+            .await;
+            match result {
+                Ok(chat_result) => return Ok(chat_result),
+                Err(error) if error.is_retryable() && attempt < runtime.max_retries => {
+                    let delay = error
+                        .retry_after()
+                        .unwrap_or_else(|| retry_backoff(attempt));
+                    if delay > RETRY_AFTER_LIMIT {
+                        return Err(error);
+                    }
+                    attempt += 1;
+                    tracing::warn!(
+                        target = "miaoyu_llm",
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %error,
+                        "文本模型请求失败，稍后重试"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
     async fn send_chat_request(
-        api_url: &str,
-        api_key: &str,
-        model_name: &str,
+        runtime: &LlmRuntimeConfig,
         system_prompt: &str,
+        temperature: Option<f32>,
         user_text: &str,
         fallback: &str,
-    ) -> Result<ChatResult> {
-        // ModelScope 的 Qwen 接口要求在非流式调用里显式关闭 enable_thinking
-        let should_disable_thinking = api_url.contains("modelscope.cn");
-        let request = ChatRequest {
-            model: model_name.to_string(),
-            messages: vec![
-                ChatMessage {
-                    role: "system".to_string(),
-                    content: system_prompt.to_string(),
-                },
-                ChatMessage {
-                    role: "user".to_string(),
-                    content: user_text.to_string(),
-                },
-            ],
-            stream: false,
-            enable_thinking: should_disable_thinking.then_some(false),
-        };
+        history: &[ChatTurn<'_>],
+        on_preview: Option<PreviewFn<'_>>,
+    ) -> Result<ChatResult, LlmError> {
+        let api_url = runtime.api_url.as_str();
+        let model_name = runtime.model_name.as_str();
+        let request = llm_protocol::build_request(
+            runtime.protocol,
+            &ChatInput {
+                api_url,
+                api_key: &runtime.api_key,
+                model: model_name,
+                system_prompt,
+                user_text,
+                temperature,
+                streaming: on_preview.is_some(),
+                history,
+            },
+        );
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(api_url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .json(&request)
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT.min(runtime.timeout))
+            .timeout(runtime.timeout)
+            .build()
+            .map_err(|error| LlmError::Internal(format!("创建 HTTP 客户端失败: {error}")))?;
+        let mut builder = client
+            .post(&request.url)
+            .header("Content-Type", "application/json");
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value.as_str());
+        }
+        for (name, value) in &runtime.extra_headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder
+            .json(&request.body)
             .send()
             .await
-            .context("调用 DeepSeek API 失败")?;
+            .map_err(|error| LlmError::from_reqwest(error, runtime.timeout))?;
 
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
             let error_text = response.text().await.unwrap_or_default();
-            let request_body = serde_json::to_string(&request).unwrap_or_default();
+            // 请求体包含用户口述内容，不写入日志；错误信息可能回显输入，同样去掉
+            let (code, message) = error_summary(&error_text, user_text);
             tracing::error!(
                 target = "miaoyu_llm",
                 status = %status,
-                error = %error_text,
+                code,
+                message,
                 api_url,
-                api_key,
                 model_name,
-                request_body,
-                "DeepSeek API 返回错误"
+                "文本模型接口返回错误"
             );
-            anyhow::bail!("DeepSeek API 调用失败: {}", status);
+            return Err(LlmError::from_status(status, retry_after, &error_text));
+        }
+
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if let (Some(on_preview), true) = (on_preview, is_event_stream) {
+            return Self::read_chat_stream(runtime, response, fallback, on_preview).await;
         }
 
-        let chat_response: ChatResponse = response
-            .json()
+        let body = response
+            .text()
             .await
-            .context("解析 DeepSeek API 响应失败")?;
+            .map_err(|error| LlmError::from_reqwest(error, runtime.timeout))?;
+        let chunk = llm_protocol::parse_response(runtime.protocol, &body).map_err(|error| {
+            LlmError::BadResponse {
+                status: None,
+                detail: error.to_string(),
+            }
+        })?;
+        if chunk.filtered {
+            return Err(LlmError::ContentFiltered);
+        }
 
-        let content = chat_response
-            .choices
-            .first()
-            .map(|choice| choice.message.content.clone())
-            .unwrap_or_else(|| fallback.to_string());
-        let total_tokens = chat_response.usage.and_then(|usage| usage.total_tokens);
+        Ok(ChatResult {
+            content: if chunk.text.is_empty() {
+                fallback.to_string()
+            } else {
+                chunk.text
+            },
+            total_tokens: chunk.usage.total(),
+        })
+    }
+
+    /// 解析 SSE 流，边接收边回调预览；各协议的分块格式由适配器解析
+    async fn read_chat_stream(
+        runtime: &LlmRuntimeConfig,
+        response: reqwest::Response,
+        fallback: &str,
+        on_preview: PreviewFn<'_>,
+    ) -> Result<ChatResult, LlmError> {
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut content = String::new();
+        let mut usage = TokenUsage::default();
+        let mut last_preview: Option<Instant> = None;
+
+        'receive: while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|error| LlmError::from_reqwest(error, runtime.timeout))?;
+            buffer.extend_from_slice(&chunk);
+
+            // 按行切分，保证多字节字符不会被网络分块截断
+            while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    break 'receive;
+                }
+
+                let Some(parsed) = llm_protocol::parse_stream_event(runtime.protocol, data) else {
+                    tracing::debug!(target = "miaoyu_llm", "忽略无法解析的流式分块");
+                    continue;
+                };
+                if let Some(error) = parsed.error {
+                    return Err(LlmError::BadResponse {
+                        status: None,
+                        detail: error,
+                    });
+                }
+                if parsed.filtered {
+                    return Err(LlmError::ContentFiltered);
+                }
+                usage.merge(parsed.usage);
+                if parsed.text.is_empty() {
+                    continue;
+                }
+                content.push_str(&parsed.text);
+                let throttled =
+                    last_preview.is_some_and(|instant| instant.elapsed() < PREVIEW_EMIT_INTERVAL);
+                if !throttled {
+                    on_preview(&content);
+                    last_preview = Some(Instant::now());
+                }
+            }
+        }
+
+        if content.trim().is_empty() {
+            content = fallback.to_string();
+        } else {
+            on_preview(&content);
+        }
 
         Ok(ChatResult {
             content,
-            total_tokens,
+            total_tokens: usage.total(),
         })
     }
 }
@@ -398,3 +962,127 @@ pub async fn test_llm_api_key(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn classify(status: u16, body: &str) -> LlmError {
+        LlmError::from_status(StatusCode::from_u16(status).unwrap(), None, body)
+    }
+
+    #[test]
+    fn error_summary_keeps_only_code_and_message() {
+        let summary = |body: &str| error_summary(body, "明天下午三点开会");
+        assert_eq!(
+            summary(
+                r#"{"error":{"code":"invalid_api_key","message":"Incorrect API key","param":null}}"#
+            ),
+            (
+                Some("invalid_api_key".to_string()),
+                Some("Incorrect API key".to_string())
+            )
+        );
+        assert_eq!(
+            summary(
+                r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            ),
+            (
+                Some("overloaded_error".to_string()),
+                Some("Overloaded".to_string())
+            )
+        );
+        assert_eq!(
+            summary(r#"{"code":"DataInspectionFailed","message":"输入：明天下午三点开会"}"#),
+            (
+                Some("DataInspectionFailed".to_string()),
+                Some("输入：<口述内容>".to_string())
+            )
+        );
+        assert_eq!(
+            summary(r#"{"error":{"code":400,"status":"INVALID_ARGUMENT"}}"#),
+            (Some("400".to_string()), None)
+        );
+        assert_eq!(summary("<html>明天下午三点开会</html>"), (None, None));
+    }
+
+    #[test]
+    fn fallback_reports_first_real_error() {
+        let mut reported = None;
+        LlmError::keep_reportable(&mut reported, LlmError::QuotaExceeded { trial: true });
+        LlmError::keep_reportable(&mut reported, LlmError::NotConfigured("未配置".to_string()));
+        assert!(matches!(
+            reported,
+            Some(LlmError::QuotaExceeded { trial: true })
+        ));
+
+        LlmError::keep_reportable(&mut reported, LlmError::AuthFailed { status: 401 });
+        LlmError::keep_reportable(&mut reported, LlmError::Timeout { seconds: 30 });
+        LlmError::keep_reportable(&mut reported, LlmError::NotConfigured("未配置".to_string()));
+        assert!(matches!(
+            reported,
+            Some(LlmError::AuthFailed { status: 401 })
+        ));
+    }
+
+    #[test]
+    fn content_filter_markers_match_provider_errors() {
+        for body in [
+            r#"{"error":{"code":"content_filter","message":"blocked"}}"#,
+            r#"{"error":{"type":"invalid_request_error","code":"content_policy_violation"}}"#,
+            r#"{"error":{"message":"Content Exists Risk"}}"#,
+            r#"{"code":"DataInspectionFailed","message":"data_inspection_failed"}"#,
+            r#"{"error":{"code":"1301","message":"系统检测到输入或生成内容可能包含不安全或敏感内容"}}"#,
+        ] {
+            assert!(
+                matches!(classify(400, body), LlmError::ContentFiltered),
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn ordinary_bad_requests_are_not_content_filtered() {
+        for body in [
+            r#"{"error":{"message":"Header names are case sensitive"}}"#,
+            r#"{"error":{"message":"'model' is a case-sensitive parameter"}}"#,
+            r#"{"error":{"message":"sensitive parameter 'api_key' must not be logged"}}"#,
+        ] {
+            assert!(
+                matches!(
+                    classify(400, body),
+                    LlmError::BadResponse {
+                        status: Some(400),
+                        ..
+                    }
+                ),
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_loopback_hosts_skip_masking() {
+        for url in [
+            "http://localhost:11434/v1/chat/completions",
+            "http://LOCALHOST./v1/chat/completions",
+            "http://127.0.0.1:8080/v1/chat/completions",
+            "http://127.8.0.1/v1/chat/completions",
+            "http://[::1]:1234/v1/chat/completions",
+            "http://ollama.localhost/v1/chat/completions",
+        ] {
+            assert!(is_loopback_url(url), "{url}");
+        }
+        for url in [
+            "http://192.168.1.20:11434/v1/chat/completions",
+            "http://10.0.0.5/v1/chat/completions",
+            "https://api.deepseek.com/v1/chat/completions",
+            "http://localhost.example.com/v1/chat/completions",
+            "http://0.0.0.0:8080/v1/chat/completions",
+            "not a url",
+        ] {
+            assert!(!is_loopback_url(url), "{url}");
+        }
+    }
+}
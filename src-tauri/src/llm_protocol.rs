@@ -0,0 +1,869 @@
+//! 各家文本模型接口的协议适配：鉴权头、请求体、响应与流式分块的解析、token 用量
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::LlmProtocol;
+
+/// Anthropic 要求显式指定输出上限，足够覆盖一次口述的润色结果
+const ANTHROPIC_MAX_TOKENS: u32 = 4096;
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 一次润色请求的输入
+pub struct ChatInput<'a> {
+    pub api_url: &'a str,
+    pub api_key: &'a str,
+    pub model: &'a str,
+    pub system_prompt: &'a str,
+    pub user_text: &'a str,
+    pub temperature: Option<f32>,
+    pub streaming: bool,
+    /// 放在本次输入之前的对话轮次，例如由最近几条记录组成的上下文
+    pub history: &'a [ChatTurn<'a>],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChatTurn<'a> {
+    pub role: ChatRole,
+    pub content: &'a str,
+}
+
+impl<'a> ChatInput<'a> {
+    /// 历史轮次加上本次输入，按发送顺序排列
+    fn turns(&self) -> impl Iterator<Item = ChatTurn<'a>> + '_ {
+        self.history
+            .iter()
+            .copied()
+            .chain(std::iter::once(ChatTurn {
+                role: ChatRole::User,
+                content: self.user_text,
+            }))
+    }
+}
+
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Value,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokenUsage {
+    pub input: Option<u32>,
+    pub output: Option<u32>,
+    pub total: Option<u32>,
+}
+
+impl TokenUsage {
+    /// 流式响应会分多次给出用量，后到的值覆盖先到的值
+    pub fn merge(&mut self, other: TokenUsage) {
+        self.input = other.input.or(self.input);
+        self.output = other.output.or(self.output);
+        self.total = other.total.or(self.total);
+    }
+
+    pub fn total(&self) -> Option<u32> {
+        self.total.or(match (self.input, self.output) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        })
+    }
+}
+
+/// 完整响应或一个流式分块的解析结果
+#[derive(Debug, Default)]
+pub struct ChatChunk {
+    pub text: String,
+    pub usage: TokenUsage,
+    /// 服务商的内容审核拦截了输入或输出
+    pub filtered: bool,
+    /// 流式响应中途返回的错误
+    pub error: Option<String>,
+}
+
+trait ProtocolAdapter: Sync {
+    fn build_request(&self, input: &ChatInput) -> HttpRequest;
+    fn parse_response(&self, body: &str) -> serde_json::Result<ChatChunk>;
+    /// 解析一条 SSE `data:`；无法识别的分块返回 `None` 并被忽略
+    fn parse_stream_event(&self, data: &str) -> Option<ChatChunk>;
+}
+
+pub fn build_request(protocol: LlmProtocol, input: &ChatInput) -> HttpRequest {
+    adapter(protocol).build_request(input)
+}
+
+pub fn parse_response(protocol: LlmProtocol, body: &str) -> serde_json::Result<ChatChunk> {
+    adapter(protocol).parse_response(body)
+}
+
+pub fn parse_stream_event(protocol: LlmProtocol, data: &str) -> Option<ChatChunk> {
+    adapter(protocol).parse_stream_event(data)
+}
+
+fn adapter(protocol: LlmProtocol) -> &'static dyn ProtocolAdapter {
+    match protocol {
+        LlmProtocol::OpenAiCompatible => &OpenAiAdapter,
+        LlmProtocol::AnthropicMessages => &AnthropicAdapter,
+        LlmProtocol::GeminiGenerateContent => &GeminiAdapter,
+    }
+}
+
+fn bearer_headers(api_key: &str) -> Vec<(&'static str, String)> {
+    if api_key.is_empty() {
+        Vec::new()
+    } else {
+        vec![("Authorization", format!("Bearer {api_key}"))]
+    }
+}
+
+// ---- OpenAI chat completions ----
+
+struct OpenAiAdapter;
+
+#[derive(Debug, Serialize)]
+struct OpenAiMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiRequest<'a> {
+    model: &'a str,
+    messages: Vec<OpenAiMessage<'a>>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAiStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_thinking: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiStreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResponse {
+    #[serde(default)]
+    choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    #[serde(default)]
+    message: Option<OpenAiContent>,
+    #[serde(default)]
+    delta: Option<OpenAiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiContent {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u32>,
+    #[serde(default)]
+    completion_tokens: Option<u32>,
+    #[serde(default)]
+    total_tokens: Option<u32>,
+}
+
+impl OpenAiResponse {
+    fn into_chunk(self) -> ChatChunk {
+        let mut chunk = ChatChunk::default();
+        for choice in self.choices {
+            chunk.filtered |= choice.finish_reason.as_deref() == Some("content_filter");
+            if let Some(text) = choice
+                .message
+                .or(choice.delta)
+                .and_then(|content| content.content)
+            {
+                chunk.text.push_str(&text);
+            }
+        }
+        if let Some(usage) = self.usage {
+            chunk.usage = TokenUsage {
+                input: usage.prompt_tokens,
+                output: usage.completion_tokens,
+                total: usage.total_tokens,
+            };
+        }
+        chunk
+    }
+}
+
+fn openai_role(role: ChatRole) -> &'static str {
+    match role {
+        ChatRole::User => "user",
+        ChatRole::Assistant => "assistant",
+    }
+}
+
+impl ProtocolAdapter for OpenAiAdapter {
+    fn build_request(&self, input: &ChatInput) -> HttpRequest {
+        // ModelScope 的 Qwen 接口要求在非流式调用里显式关闭 enable_thinking；
+        // 流式调用同样关闭，避免思考过程拖慢润色
+        let disable_thinking = input.api_url.contains("modelscope.cn");
+        let request = OpenAiRequest {
+            model: input.model,
+            messages: std::iter::once(OpenAiMessage {
+                role: "system",
+                content: input.system_prompt,
+            })
+            .chain(input.turns().map(|turn| OpenAiMessage {
+                role: openai_role(turn.role),
+                content: turn.content,
+            }))
+            .collect(),
+            stream: input.streaming,
+            stream_options: input.streaming.then_some(OpenAiStreamOptions {
+                include_usage: true,
+            }),
+            temperature: input.temperature,
+            enable_thinking: disable_thinking.then_some(false),
+        };
+        HttpRequest {
+            url: input.api_url.to_string(),
+            headers: bearer_headers(input.api_key),
+            body: serde_json::to_value(request).unwrap_or_default(),
+        }
+    }
+
+    fn parse_response(&self, body: &str) -> serde_json::Result<ChatChunk> {
+        serde_json::from_str::<OpenAiResponse>(body).map(OpenAiResponse::into_chunk)
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Option<ChatChunk> {
+        serde_json::from_str::<OpenAiResponse>(data)
+            .ok()
+            .map(OpenAiResponse::into_chunk)
+    }
+}
+
+// ---- Anthropic Messages ----
+
+struct AnthropicAdapter;
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    system: &'a str,
+    messages: Vec<OpenAiMessage<'a>>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    #[serde(default)]
+    content: Vec<AnthropicContentBlock>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: Option<u32>,
+    #[serde(default)]
+    output_tokens: Option<u32>,
+}
+
+/// 流式事件按 `type` 区分：message_start 带输入用量，content_block_delta 带文本，
+/// message_delta 带停止原因与输出用量
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicStreamEvent {
+    MessageStart {
+        message: AnthropicResponse,
+    },
+    ContentBlockDelta {
+        delta: AnthropicDelta,
+    },
+    MessageDelta {
+        #[serde(default)]
+        delta: AnthropicDelta,
+        #[serde(default)]
+        usage: AnthropicUsage,
+    },
+    Error {
+        error: Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicDelta {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+impl From<AnthropicUsage> for TokenUsage {
+    fn from(usage: AnthropicUsage) -> Self {
+        TokenUsage {
+            input: usage.input_tokens,
+            output: usage.output_tokens,
+            total: None,
+        }
+    }
+}
+
+fn anthropic_refused(stop_reason: Option<&str>) -> bool {
+    stop_reason == Some("refusal")
+}
+
+impl ProtocolAdapter for AnthropicAdapter {
+    fn build_request(&self, input: &ChatInput) -> HttpRequest {
+        let request = AnthropicRequest {
+            model: input.model,
+            max_tokens: ANTHROPIC_MAX_TOKENS,
+            system: input.system_prompt,
+            messages: input
+                .turns()
+                .map(|turn| OpenAiMessage {
+                    role: openai_role(turn.role),
+                    content: turn.content,
+                })
+                .collect(),
+            stream: input.streaming,
+            // 模板允许 0–2，Anthropic 只接受 0–1，超出会被拒绝
+            temperature: input
+                .temperature
+                .map(|temperature| temperature.clamp(0.0, 1.0)),
+        };
+        let mut headers = vec![("anthropic-version", ANTHROPIC_VERSION.to_string())];
+        if !input.api_key.is_empty() {
+            headers.push(("x-api-key", input.api_key.to_string()));
+        }
+        HttpRequest {
+            url: input.api_url.to_string(),
+            headers,
+            body: serde_json::to_value(request).unwrap_or_default(),
+        }
+    }
+
+    fn parse_response(&self, body: &str) -> serde_json::Result<ChatChunk> {
+        let response: AnthropicResponse = serde_json::from_str(body)?;
+        Ok(ChatChunk {
+            text: response
+                .content
+                .into_iter()
+                .filter_map(|block| block.text)
+                .collect(),
+            usage: response.usage.map(TokenUsage::from).unwrap_or_default(),
+            filtered: anthropic_refused(response.stop_reason.as_deref()),
+            error: None,
+        })
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Option<ChatChunk> {
+        let chunk = match serde_json::from_str::<AnthropicStreamEvent>(data).ok()? {
+            AnthropicStreamEvent::MessageStart { message } => ChatChunk {
+                usage: message.usage.map(TokenUsage::from).unwrap_or_default(),
+                ..Default::default()
+            },
+            AnthropicStreamEvent::ContentBlockDelta { delta } => ChatChunk {
+                text: delta.text.unwrap_or_default(),
+                ..Default::default()
+            },
+            AnthropicStreamEvent::MessageDelta { delta, usage } => ChatChunk {
+                usage: TokenUsage {
+                    output: usage.output_tokens,
+                    ..Default::default()
+                },
+                filtered: anthropic_refused(delta.stop_reason.as_deref()),
+                ..Default::default()
+            },
+            AnthropicStreamEvent::Error { error } => ChatChunk {
+                error: Some(error.to_string()),
+                ..Default::default()
+            },
+            AnthropicStreamEvent::Other => return None,
+        };
+        Some(chunk)
+    }
+}
+
+// ---- Google Gemini generateContent ----
+
+struct GeminiAdapter;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest<'a> {
+    system_instruction: GeminiContent<'a>,
+    contents: Vec<GeminiContent<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiContent<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    parts: Vec<GeminiPart<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiPart<'a> {
+    text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiGenerationConfig {
+    temperature: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+    #[serde(default)]
+    prompt_feedback: Option<GeminiPromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiResponseContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponseContent {
+    #[serde(default)]
+    parts: Vec<GeminiResponsePart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponsePart {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: Option<u32>,
+    #[serde(default)]
+    candidates_token_count: Option<u32>,
+    #[serde(default)]
+    total_token_count: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPromptFeedback {
+    #[serde(default)]
+    block_reason: Option<String>,
+}
+
+/// 这些结束原因表示输出被安全策略拦截
+const GEMINI_BLOCKED_REASONS: [&str; 4] = ["SAFETY", "PROHIBITED_CONTENT", "BLOCKLIST", "SPII"];
+
+impl GeminiResponse {
+    fn into_chunk(self) -> ChatChunk {
+        let mut chunk = ChatChunk {
+            filtered: self
+                .prompt_feedback
+                .is_some_and(|feedback| feedback.block_reason.is_some()),
+            ..Default::default()
+        };
+        for candidate in self.candidates {
+            chunk.filtered |= candidate
+                .finish_reason
+                .as_deref()
+                .is_some_and(|reason| GEMINI_BLOCKED_REASONS.contains(&reason));
+            for part in candidate
+                .content
+                .into_iter()
+                .flat_map(|content| content.parts)
+            {
+                if let Some(text) = part.text {
+                    chunk.text.push_str(&text);
+                }
+            }
+        }
+        if let Some(usage) = self.usage_metadata {
+            chunk.usage = TokenUsage {
+                input: usage.prompt_token_count,
+                output: usage.candidates_token_count,
+                total: usage.total_token_count,
+            };
+        }
+        chunk
+    }
+}
+
+impl ProtocolAdapter for GeminiAdapter {
+    fn build_request(&self, input: &ChatInput) -> HttpRequest {
+        // 模型名写在路径里，流式调用使用 SSE 格式的 streamGenerateContent
+        let base = input.api_url.trim_end_matches('/');
+        let url = if input.streaming {
+            format!(
+                "{base}/models/{}:streamGenerateContent?alt=sse",
+                input.model
+            )
+        } else {
+            format!("{base}/models/{}:generateContent", input.model)
+        };
+        let request = GeminiRequest {
+            system_instruction: GeminiContent {
+                role: None,
+                parts: vec![GeminiPart {
+                    text: input.system_prompt,
+                }],
+            },
+            contents: input
+                .turns()
+                .map(|turn| GeminiContent {
+                    // Gemini 中模型一方的角色名为 model
+                    role: Some(match turn.role {
+                        ChatRole::User => "user",
+                        ChatRole::Assistant => "model",
+                    }),
+                    parts: vec![GeminiPart { text: turn.content }],
+                })
+                .collect(),
+            generation_config: input
+                .temperature
+                .map(|temperature| GeminiGenerationConfig { temperature }),
+        };
+        let headers = if input.api_key.is_empty() {
+            Vec::new()
+        } else {
+            vec![("x-goog-api-key", input.api_key.to_string())]
+        };
+        HttpRequest {
+            url,
+            headers,
+            body: serde_json::to_value(request).unwrap_or_default(),
+        }
+    }
+
+    fn parse_response(&self, body: &str) -> serde_json::Result<ChatChunk> {
+        serde_json::from_str::<GeminiResponse>(body).map(GeminiResponse::into_chunk)
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Option<ChatChunk> {
+        serde_json::from_str::<GeminiResponse>(data)
+            .ok()
+            .map(GeminiResponse::into_chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const HISTORY: [ChatTurn; 2] = [
+        ChatTurn {
+            role: ChatRole::User,
+            content: "上一句",
+        },
+        ChatTurn {
+            role: ChatRole::Assistant,
+            content: "上一句。",
+        },
+    ];
+
+    fn input<'a>(api_url: &'a str, api_key: &'a str, streaming: bool) -> ChatInput<'a> {
+        ChatInput {
+            api_url,
+            api_key,
+            model: "test-model",
+            system_prompt: "润色",
+            user_text: "今天天气不错",
+            temperature: Some(0.5),
+            streaming,
+            history: &HISTORY,
+        }
+    }
+
+    fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn openai_request_carries_history_and_bearer_key() {
+        let request = build_request(
+            LlmProtocol::OpenAiCompatible,
+            &input("https://api.example.com/v1/chat/completions", "key-1", true),
+        );
+        assert_eq!(request.url, "https://api.example.com/v1/chat/completions");
+        assert_eq!(header(&request, "Authorization"), Some("Bearer key-1"));
+        assert_eq!(
+            request.body,
+            json!({
+                "model": "test-model",
+                "messages": [
+                    { "role": "system", "content": "润色" },
+                    { "role": "user", "content": "上一句" },
+                    { "role": "assistant", "content": "上一句。" },
+                    { "role": "user", "content": "今天天气不错" },
+                ],
+                "stream": true,
+                "stream_options": { "include_usage": true },
+                "temperature": 0.5,
+            })
+        );
+
+        let request = build_request(
+            LlmProtocol::OpenAiCompatible,
+            &input(
+                "https://api-inference.modelscope.cn/v1/chat/completions",
+                "",
+                false,
+            ),
+        );
+        assert!(request.headers.is_empty());
+        assert_eq!(request.body["enable_thinking"], json!(false));
+        assert!(request.body.get("stream_options").is_none());
+    }
+
+    #[test]
+    fn openai_parses_response_and_stream_deltas() {
+        let chunk = parse_response(
+            LlmProtocol::OpenAiCompatible,
+            r#"{"choices":[{"message":{"role":"assistant","content":"你好。"},"finish_reason":"stop"}],
+                "usage":{"prompt_tokens":12,"completion_tokens":3,"total_tokens":15}}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.text, "你好。");
+        assert!(!chunk.filtered);
+        assert_eq!(
+            (chunk.usage.input, chunk.usage.output, chunk.usage.total()),
+            (Some(12), Some(3), Some(15))
+        );
+
+        let delta = parse_stream_event(
+            LlmProtocol::OpenAiCompatible,
+            r#"{"choices":[{"delta":{"content":"你"},"finish_reason":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(delta.text, "你");
+        let filtered = parse_stream_event(
+            LlmProtocol::OpenAiCompatible,
+            r#"{"choices":[{"delta":{},"finish_reason":"content_filter"}]}"#,
+        )
+        .unwrap();
+        assert!(filtered.filtered);
+        assert!(parse_stream_event(LlmProtocol::OpenAiCompatible, "[DONE]").is_none());
+    }
+
+    #[test]
+    fn anthropic_request_uses_system_field_and_api_key_header() {
+        let request = build_request(
+            LlmProtocol::AnthropicMessages,
+            &input("https://api.anthropic.com/v1/messages", "key-2", false),
+        );
+        assert_eq!(header(&request, "x-api-key"), Some("key-2"));
+        assert_eq!(
+            header(&request, "anthropic-version"),
+            Some(ANTHROPIC_VERSION)
+        );
+        assert_eq!(header(&request, "Authorization"), None);
+        assert_eq!(
+            request.body,
+            json!({
+                "model": "test-model",
+                "max_tokens": ANTHROPIC_MAX_TOKENS,
+                "system": "润色",
+                "messages": [
+                    { "role": "user", "content": "上一句" },
+                    { "role": "assistant", "content": "上一句。" },
+                    { "role": "user", "content": "今天天气不错" },
+                ],
+                "stream": false,
+                "temperature": 0.5,
+            })
+        );
+    }
+
+    #[test]
+    fn anthropic_clamps_temperature() {
+        for (temperature, expected) in [(1.6, json!(1.0)), (-0.5, json!(0.0))] {
+            let request = build_request(
+                LlmProtocol::AnthropicMessages,
+                &ChatInput {
+                    temperature: Some(temperature),
+                    ..input("https://api.anthropic.com/v1/messages", "key-1", false)
+                },
+            );
+            assert_eq!(request.body["temperature"], expected);
+        }
+    }
+
+    #[test]
+    fn anthropic_parses_response_and_merges_stream_usage() {
+        let chunk = parse_response(
+            LlmProtocol::AnthropicMessages,
+            r#"{"id":"msg_1","type":"message","role":"assistant",
+                "content":[{"type":"text","text":"你好"},{"type":"text","text":"。"}],
+                "stop_reason":"end_turn","usage":{"input_tokens":20,"output_tokens":4}}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.text, "你好。");
+        assert_eq!(chunk.usage.total(), Some(24));
+
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","content":[],"usage":{"input_tokens":20,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"你好"}}"#,
+            r#"{"type":"ping"}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"。"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":4}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        for event in events {
+            if let Some(chunk) = parse_stream_event(LlmProtocol::AnthropicMessages, event) {
+                text.push_str(&chunk.text);
+                usage.merge(chunk.usage);
+                assert!(!chunk.filtered);
+            }
+        }
+        assert_eq!(text, "你好。");
+        assert_eq!(
+            (usage.input, usage.output, usage.total()),
+            (Some(20), Some(4), Some(24))
+        );
+
+        let refused = parse_stream_event(
+            LlmProtocol::AnthropicMessages,
+            r#"{"type":"message_delta","delta":{"stop_reason":"refusal"},"usage":{"output_tokens":0}}"#,
+        )
+        .unwrap();
+        assert!(refused.filtered);
+        let error = parse_stream_event(
+            LlmProtocol::AnthropicMessages,
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap();
+        assert!(error
+            .error
+            .is_some_and(|error| error.contains("overloaded_error")));
+    }
+
+    #[test]
+    fn gemini_request_puts_model_in_path() {
+        let request = build_request(
+            LlmProtocol::GeminiGenerateContent,
+            &input(
+                "https://generativelanguage.googleapis.com/v1beta/",
+                "key-3",
+                true,
+            ),
+        );
+        assert_eq!(
+            request.url,
+            "https://generativelanguage.googleapis.com/v1beta/models/test-model:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(header(&request, "x-goog-api-key"), Some("key-3"));
+        assert_eq!(
+            request.body,
+            json!({
+                "systemInstruction": { "parts": [{ "text": "润色" }] },
+                "contents": [
+                    { "role": "user", "parts": [{ "text": "上一句" }] },
+                    { "role": "model", "parts": [{ "text": "上一句。" }] },
+                    { "role": "user", "parts": [{ "text": "今天天气不错" }] },
+                ],
+                "generationConfig": { "temperature": 0.5 },
+            })
+        );
+
+        let request = build_request(
+            LlmProtocol::GeminiGenerateContent,
+            &input(
+                "https://generativelanguage.googleapis.com/v1beta",
+                "",
+                false,
+            ),
+        );
+        assert!(request
+            .url
+            .ends_with("/v1beta/models/test-model:generateContent"));
+        assert!(request.headers.is_empty());
+    }
+
+    #[test]
+    fn gemini_parses_candidate_parts_and_usage_metadata() {
+        let chunk = parse_response(
+            LlmProtocol::GeminiGenerateContent,
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"你好"},{"text":"。"}]},
+                "finishReason":"STOP"}],
+                "usageMetadata":{"promptTokenCount":9,"candidatesTokenCount":2,"totalTokenCount":11}}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.text, "你好。");
+        assert!(!chunk.filtered);
+        assert_eq!(
+            (chunk.usage.input, chunk.usage.output, chunk.usage.total()),
+            (Some(9), Some(2), Some(11))
+        );
+
+        let delta = parse_stream_event(
+            LlmProtocol::GeminiGenerateContent,
+            r#"{"candidates":[{"content":{"parts":[{"text":"你"}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(delta.text, "你");
+        assert_eq!(delta.usage.total(), None);
+
+        let blocked = parse_stream_event(
+            LlmProtocol::GeminiGenerateContent,
+            r#"{"candidates":[{"finishReason":"SAFETY"}]}"#,
+        )
+        .unwrap();
+        assert!(blocked.filtered);
+        let prompt_blocked = parse_response(
+            LlmProtocol::GeminiGenerateContent,
+            r#"{"promptFeedback":{"blockReason":"OTHER"}}"#,
+        )
+        .unwrap();
+        assert!(prompt_blocked.filtered);
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::models::CustomLlmBackend;
+
+/// 本地服务应当立即响应，超时通常意味着服务未启动
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Deserialize)]
+struct OllamaTags {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModels {
+    #[serde(default)]
+    data: Vec<OpenAiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModel {
+    id: String,
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .build()
+        .context("创建 HTTP 客户端失败")
+}
+
+async fn get_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T> {
+    let response = client()?
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("无法连接本地模型服务 {url}，请确认服务已启动"))?;
+    if !response.status().is_success() {
+        anyhow::bail!("本地模型服务返回错误: {}", response.status());
+    }
+    response.json().await.context("解析本地模型服务响应失败")
+}
+
+/// 列出本地服务中已安装的模型；请求成功本身也说明服务可用
+pub async fn list_models(backend: CustomLlmBackend, server_root: &str) -> Result<Vec<String>> {
+    let root = match server_root.trim().trim_end_matches('/') {
+        "" => backend
+            .default_server_url()
+            .ok_or_else(|| anyhow!("请填写本地模型服务地址"))?,
+        value => value,
+    };
+    let mut models = match backend {
+        CustomLlmBackend::Ollama => get_json::<OllamaTags>(&format!("{root}/api/tags"))
+            .await?
+            .models
+            .into_iter()
+            .map(|model| model.name)
+            .collect::<Vec<_>>(),
+        CustomLlmBackend::LlamaCpp => get_json::<OpenAiModels>(&format!("{root}/v1/models"))
+            .await?
+            .data
+            .into_iter()
+            .map(|model| model.id)
+            .collect(),
+        CustomLlmBackend::OpenAiCompatible
+        | CustomLlmBackend::Anthropic
+        | CustomLlmBackend::Gemini => {
+            return Err(anyhow!("仅本地模型服务支持读取模型列表"));
+        }
+    };
+    models.sort();
+    models.dedup();
+    Ok(models)
+}
+
+/// 代替 API 密钥测试：确认服务在线且已安装所选模型
+pub async fn health_check(
+    backend: CustomLlmBackend,
+    server_root: &str,
+    model_name: &str,
+) -> Result<()> {
+    let models = list_models(backend, server_root).await?;
+    // llama.cpp 一次只加载一个模型，列表中的 id 往往是文件路径，不做严格匹配
+    let installed = backend == CustomLlmBackend::LlamaCpp
+        || models.iter().any(|name| {
+            name == model_name
+                || name
+                    .strip_suffix(":latest")
+                    .is_some_and(|base| base == model_name)
+        });
+    if !installed {
+        anyhow::bail!("本地模型服务中未找到模型 {model_name}");
+    }
+
+    tracing::info!(
+        target = "miaoyu_llm",
+        backend = ?backend,
+        server_root,
+        model_name,
+        "本地模型服务健康检查通过"
+    );
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_local_llm_models(
+    backend: CustomLlmBackend,
+    server_url: String,
+) -> Result<Vec<String>, String> {
+    list_models(backend, &server_url)
+        .await
+        .map_err(|error| error.to_string())
+}
@@ -1,7 +1,7 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use miaoyu_desktop_lib::DynLoggingLayer;
+use miaoyu_desktop_lib::{DynLoggingLayer, RedactingWriter};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 fn main() {
@@ -44,13 +44,14 @@ fn main() {
         .with(
             tracing_subscriber::fmt::layer()
                 .with_ansi(true)
-                .with_target(true),
+                .with_target(true)
+                .with_writer(RedactingWriter::new(std::io::stdout)),
         )
         .with(
             tracing_subscriber::fmt::layer()
                 .with_ansi(false)
                 .with_target(true)
-                .with_writer(non_blocking),
+                .with_writer(RedactingWriter::new(non_blocking)),
         )
         .init();
 
@@ -1,13 +1,17 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use chrono::Local;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use tauri::{AppHandle, Wry};
 use tauri_plugin_store::{Store, StoreExt};
+use uuid::Uuid;
 
 use crate::audio::local_models;
+use crate::llm::LlmError;
+use crate::secrets;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +26,26 @@ pub struct LlmProviderConfig {
     pub api_base_url: Option<String>,
     #[serde(rename = "apiKeyEnv", alias = "api_key_env", default)]
     pub api_key_env: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_headers: BTreeMap<String, String>,
+    /// 运行在本机/局域网的推理服务，无需 API 密钥，也不计入体验额度
+    #[serde(default)]
+    pub local: bool,
+    #[serde(default)]
+    pub protocol: LlmProtocol,
+}
+
+/// 文本模型接口协议，决定鉴权方式、请求格式与响应解析
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LlmProtocol {
+    /// OpenAI chat completions 及其兼容接口
+    #[default]
+    OpenAiCompatible,
+    /// Anthropic Messages API
+    AnthropicMessages,
+    /// Google Gemini generateContent
+    GeminiGenerateContent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -55,6 +79,9 @@ pub static SUPPORTED_MODELS: Lazy<SupportedModels> = Lazy::new(|| SupportedModel
                     api_key_url: Some("https://platform.deepseek.com/api_keys".to_string()),
                     api_base_url: Some("https://api.deepseek.com/v1/chat/completions".to_string()),
                     api_key_env: Some("DEEPSEEK_API_KEY".to_string()),
+                    extra_headers: BTreeMap::new(),
+                    local: false,
+                    protocol: LlmProtocol::OpenAiCompatible,
                 },
                 LlmProviderConfig {
                     id: "modelscope".to_string(),
@@ -65,6 +92,9 @@ pub static SUPPORTED_MODELS: Lazy<SupportedModels> = Lazy::new(|| SupportedModel
                         "https://api-inference.modelscope.cn/v1/chat/completions".to_string(),
                     ),
                     api_key_env: Some("MODELSCOPE_ACCESS_TOKEN".to_string()),
+                    extra_headers: BTreeMap::new(),
+                    local: false,
+                    protocol: LlmProtocol::OpenAiCompatible,
                 },
             ],
         },
@@ -80,6 +110,9 @@ pub static SUPPORTED_MODELS: Lazy<SupportedModels> = Lazy::new(|| SupportedModel
                     "https://api-inference.modelscope.cn/v1/chat/completions".to_string(),
                 ),
                 api_key_env: Some("MODELSCOPE_ACCESS_TOKEN".to_string()),
+                extra_headers: BTreeMap::new(),
+                local: false,
+                protocol: LlmProtocol::OpenAiCompatible,
             }],
         },
     ],
@@ -153,6 +186,103 @@ pub struct LlmModelStore {
     pub usage_date: Option<String>,
 }
 
+/// 自定义文本模型服务的后端类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CustomLlmBackend {
+    #[default]
+    OpenAiCompatible,
+    Ollama,
+    LlamaCpp,
+    Anthropic,
+    Gemini,
+}
+
+impl CustomLlmBackend {
+    pub fn is_local(self) -> bool {
+        matches!(self, CustomLlmBackend::Ollama | CustomLlmBackend::LlamaCpp)
+    }
+
+    pub fn default_server_url(self) -> Option<&'static str> {
+        match self {
+            CustomLlmBackend::OpenAiCompatible => None,
+            CustomLlmBackend::Ollama => Some("http://localhost:11434"),
+            CustomLlmBackend::LlamaCpp => Some("http://localhost:8080"),
+            CustomLlmBackend::Anthropic => Some("https://api.anthropic.com"),
+            CustomLlmBackend::Gemini => Some("https://generativelanguage.googleapis.com/v1beta"),
+        }
+    }
+
+    pub fn protocol(self) -> LlmProtocol {
+        match self {
+            CustomLlmBackend::Anthropic => LlmProtocol::AnthropicMessages,
+            CustomLlmBackend::Gemini => LlmProtocol::GeminiGenerateContent,
+            _ => LlmProtocol::OpenAiCompatible,
+        }
+    }
+}
+
+/// 用户自定义的文本模型服务，例如公司内部网关或本地 Ollama / llama.cpp
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomLlmProvider {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub backend: CustomLlmBackend,
+    pub api_base_url: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    #[serde(default)]
+    pub extra_headers: BTreeMap<String, String>,
+}
+
+impl CustomLlmProvider {
+    fn to_model_config(&self) -> LlmModelConfig {
+        LlmModelConfig {
+            id: self.id.clone(),
+            title: self.name.clone(),
+            providers: vec![LlmProviderConfig {
+                id: CUSTOM_PROVIDER_ID.to_string(),
+                name: self.name.clone(),
+                model: Some(self.model.clone()),
+                api_key_url: None,
+                api_base_url: Some(self.api_base_url.clone()),
+                api_key_env: self.api_key_env.clone(),
+                extra_headers: self.extra_headers.clone(),
+                local: self.backend.is_local(),
+                protocol: self.backend.protocol(),
+            }],
+        }
+    }
+}
+
+/// 由 chat completions 地址反推本地服务根地址，用于健康检查与模型列表
+pub fn local_server_root(api_base_url: &str) -> &str {
+    let root = api_base_url
+        .strip_suffix(CHAT_COMPLETIONS_PATH)
+        .unwrap_or(api_base_url);
+    root.strip_suffix(OPENAI_VERSION_PATH).unwrap_or(root)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomLlmProviderInput {
+    pub name: String,
+    #[serde(default)]
+    pub backend: CustomLlmBackend,
+    #[serde(default)]
+    pub api_base_url: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    #[serde(default)]
+    pub extra_headers: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelsStore {
@@ -164,6 +294,41 @@ pub struct ModelsStore {
     pub asr_models: Vec<AsrModelStore>,
     #[serde(rename = "activeAsrModel", alias = "active-asr-model", default)]
     pub active_asr_model: Option<String>,
+    #[serde(default)]
+    pub custom_llm_providers: Vec<CustomLlmProvider>,
+    /// 当前文本模型失败后依次尝试的备用文本模型 ID
+    #[serde(default)]
+    pub llm_fallback_models: Vec<String>,
+    #[serde(default)]
+    pub llm_request: LlmRequestSettings,
+}
+
+/// 文本模型请求的超时与重试策略
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmRequestSettings {
+    #[serde(default = "default_llm_timeout_seconds")]
+    pub timeout_seconds: u32,
+    /// 遇到 429/5xx 时对同一模型的最大重试次数
+    #[serde(default = "default_llm_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_llm_timeout_seconds() -> u32 {
+    30
+}
+
+fn default_llm_max_retries() -> u32 {
+    2
+}
+
+impl Default for LlmRequestSettings {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: default_llm_timeout_seconds(),
+            max_retries: default_llm_max_retries(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type, Default)]
@@ -190,6 +355,11 @@ pub struct AsrModelStore {
 const MODELS_STORE_NAME: &str = "store";
 const MODELS_STORE_KEY: &str = "models";
 const LLM_DAILY_TOKEN_LIMIT: u32 = 5_000;
+/// 自定义文本模型固定使用的提供商 ID
+pub const CUSTOM_PROVIDER_ID: &str = "custom";
+const CHAT_COMPLETIONS_PATH: &str = "/chat/completions";
+const OPENAI_VERSION_PATH: &str = "/v1";
+const ANTHROPIC_MESSAGES_PATH: &str = "/messages";
 
 fn read_store(app: &AppHandle<Wry>) -> Result<(Arc<Store<Wry>>, ModelsStore), String> {
     let handle = app
@@ -205,18 +375,134 @@ fn read_store(app: &AppHandle<Wry>) -> Result<(Arc<Store<Wry>>, ModelsStore), St
         data = ModelsStore::default();
     }
 
+    if load_secrets(&mut data) {
+        persist_store(&handle, &data)?;
+        tracing::info!(target = "miaoyu_models", "已将明文密钥迁移到系统钥匙串");
+    }
+
     Ok((handle, data))
 }
 
 fn persist_store(handle: &Arc<Store<Wry>>, data: &ModelsStore) -> Result<(), String> {
     handle.set(
         MODELS_STORE_KEY,
-        serde_json::to_value(data).map_err(|e| e.to_string())?,
+        serde_json::to_value(prepare_for_disk(data)).map_err(|e| e.to_string())?,
     );
     handle.save().map_err(|e| e.to_string())
 }
 
+/// 持久化前把密钥移入钥匙串；钥匙串不可用时保留明文，避免用户配置丢失。
+/// 内存中为空的密钥不会删除钥匙串条目，删除只通过 [`clear_secret`] 显式进行
+fn prepare_for_disk(data: &ModelsStore) -> ModelsStore {
+    let mut stored = data.clone();
+    for entry in &mut stored.llm_models {
+        store_secret(&llm_secret_account(&entry.id), &mut entry.api_key);
+    }
+    for entry in &mut stored.asr_models {
+        store_secret(&asr_secret_account(&entry.id), &mut entry.access_token);
+    }
+    stored
+}
+
+fn llm_secret_account(entry_id: &str) -> String {
+    format!("llm:{entry_id}")
+}
+
+fn asr_secret_account(entry_id: &str) -> String {
+    format!("asr:{entry_id}")
+}
+
+/// 本次运行中读取失败的钥匙串条目。其中的密钥未知，界面上显示为空，不能当作用户清空而删除
+static UNREADABLE_SECRETS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+fn unreadable_secrets() -> MutexGuard<'static, HashSet<String>> {
+    UNREADABLE_SECRETS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+fn store_secret(account: &str, value: &mut Option<String>) {
+    let Some(secret) = value.as_deref() else {
+        return;
+    };
+    match secrets::set(account, Some(secret)) {
+        Ok(()) => {
+            unreadable_secrets().remove(account);
+            *value = None;
+        }
+        Err(error) => {
+            tracing::warn!(
+                target = "miaoyu_models",
+                account,
+                error = %error,
+                "系统钥匙串不可用，密钥仍保存在本地配置"
+            );
+        }
+    }
+}
+
+/// 用户清空或删除密钥时移除钥匙串条目；读取失败的条目内容未知，跳过删除以免误删
+fn clear_secret(account: &str) {
+    if unreadable_secrets().contains(account) {
+        tracing::warn!(
+            target = "miaoyu_models",
+            account,
+            "系统钥匙串读取失败，未删除已保存的密钥"
+        );
+        return;
+    }
+    if let Err(error) = secrets::set(account, None) {
+        tracing::warn!(
+            target = "miaoyu_models",
+            account,
+            error = %error,
+            "删除系统钥匙串中的密钥失败"
+        );
+    }
+}
+
+/// 从钥匙串补全密钥；明文密钥写入钥匙串后返回 true，由调用方重新保存以移除明文
+fn load_secrets(data: &mut ModelsStore) -> bool {
+    let mut migrate = false;
+    let slots = data
+        .llm_models
+        .iter_mut()
+        .map(|entry| (llm_secret_account(&entry.id), &mut entry.api_key))
+        .chain(
+            data.asr_models
+                .iter_mut()
+                .map(|entry| (asr_secret_account(&entry.id), &mut entry.access_token)),
+        );
+    for (account, value) in slots {
+        if let Some(plaintext) = value.as_deref() {
+            secrets::register(plaintext);
+            migrate |= secrets::set(&account, Some(plaintext)).is_ok();
+            continue;
+        }
+        match secrets::get(&account) {
+            Ok(secret) => {
+                unreadable_secrets().remove(&account);
+                *value = secret;
+            }
+            Err(error) => {
+                unreadable_secrets().insert(account.clone());
+                tracing::warn!(
+                    target = "miaoyu_models",
+                    account,
+                    error = %error,
+                    "读取系统钥匙串失败"
+                );
+            }
+        }
+    }
+    migrate
+}
+
 fn resolve_variant_id(model: &LlmModelConfig, provider: &LlmProviderConfig) -> String {
+    // 不同的自定义服务可能使用同名模型，因此以服务 ID 区分
+    if provider.id == CUSTOM_PROVIDER_ID {
+        return format!("{}::{}", model.id, provider.id);
+    }
     provider
         .model
         .clone()
@@ -373,15 +659,27 @@ fn with_models_store<F>(app: &AppHandle<Wry>, mutator: F) -> Result<ModelsStore,
 where
     F: FnOnce(&SupportedModels, &mut ModelsStore) -> Result<(), String>,
 {
-    let config = supported_models();
     let (handle, mut data) = read_store(app)?;
-    hydrate_models(&mut data, config);
-    mutator(config, &mut data)?;
-    hydrate_models(&mut data, config);
+    let config = merged_models(&data);
+    hydrate_models(&mut data, &config);
+    mutator(&config, &mut data)?;
+    let config = merged_models(&data);
+    hydrate_models(&mut data, &config);
     persist_store(&handle, &data)?;
     Ok(data)
 }
 
+/// 内置模型、模型清单与用户自定义文本模型合并后的完整列表
+fn merged_models(data: &ModelsStore) -> SupportedModels {
+    let mut merged = supported_models().clone();
+    merged.llm_models.extend(
+        data.custom_llm_providers
+            .iter()
+            .map(CustomLlmProvider::to_model_config),
+    );
+    merged
+}
+
 fn today_string() -> String {
     Local::now().format("%Y-%m-%d").to_string()
 }
@@ -405,14 +703,45 @@ fn sanitize_api_key(value: Option<String>) -> Option<String> {
     })
 }
 
+static MERGED_MODELS: OnceCell<SupportedModels> = OnceCell::new();
+
+/// 加载模型清单，并把其中的自定义语音模型合并到内置模型列表
+pub fn init(app: &AppHandle<Wry>) {
+    local_models::init(app);
+    let mut merged = SUPPORTED_MODELS.clone();
+    for spec in local_models::custom_specs() {
+        merged.asr_models.push(AsrModelConfig {
+            id: spec.id.clone(),
+            title: spec.title.clone(),
+            offline: true,
+            size: spec.size.clone().unwrap_or_default(),
+            providers: vec![AsrProviderConfig {
+                id: "local".to_string(),
+                name: "本地".to_string(),
+                model: None,
+            }],
+        });
+    }
+    MERGED_MODELS.set(merged).ok();
+}
+
 pub fn supported_models() -> &'static SupportedModels {
-    &SUPPORTED_MODELS
+    MERGED_MODELS.get().unwrap_or(&SUPPORTED_MODELS)
+}
+
+/// 包含用户自定义文本模型的完整模型列表
+pub fn resolve_supported_models(app: &AppHandle<Wry>) -> Result<SupportedModels, String> {
+    let (_, data) = read_store(app)?;
+    Ok(merged_models(&data))
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_supported_models() -> SupportedModels {
-    SUPPORTED_MODELS.clone()
+pub fn get_supported_models(app: AppHandle) -> SupportedModels {
+    resolve_supported_models(&app).unwrap_or_else(|error| {
+        tracing::warn!(target = "miaoyu_models", error = %error, "读取自定义文本模型失败");
+        supported_models().clone()
+    })
 }
 
 fn load(app: &AppHandle<Wry>) -> Result<ModelsStore, String> {
@@ -425,6 +754,67 @@ pub fn get_models_store(app: AppHandle) -> Result<ModelsStore, String> {
     load(&app)
 }
 
+pub fn llm_request_settings(app: &AppHandle<Wry>) -> LlmRequestSettings {
+    load(app).map(|data| data.llm_request).unwrap_or_default()
+}
+
+/// 本次润色依次尝试的文本模型：指定模型或当前模型在前，其后是备用模型
+pub fn llm_candidate_models(
+    app: &AppHandle<Wry>,
+    override_model: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let data = load(app)?;
+    let mut candidates: Vec<String> = Vec::new();
+    let primary = override_model
+        .map(str::to_string)
+        .or(data.active_llm_model.clone());
+    for model_id in primary.into_iter().chain(data.llm_fallback_models) {
+        if !candidates.contains(&model_id) {
+            candidates.push(model_id);
+        }
+    }
+    Ok(candidates)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_llm_fallback_models(
+    app: AppHandle,
+    model_ids: Vec<String>,
+) -> Result<ModelsStore, String> {
+    with_models_store(&app, |config, data| {
+        let mut fallbacks: Vec<String> = Vec::new();
+        for model_id in model_ids {
+            if !config.llm_models.iter().any(|model| model.id == model_id) {
+                return Err(format!("未知的文本模型: {model_id}"));
+            }
+            if !fallbacks.contains(&model_id) {
+                fallbacks.push(model_id);
+            }
+        }
+        data.llm_fallback_models = fallbacks;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_llm_request_settings(
+    app: AppHandle,
+    settings: LlmRequestSettings,
+) -> Result<ModelsStore, String> {
+    if !(5..=300).contains(&settings.timeout_seconds) {
+        return Err("请求超时需在 5 到 300 秒之间".to_string());
+    }
+    if settings.max_retries > 5 {
+        return Err("重试次数不能超过 5 次".to_string());
+    }
+    with_models_store(&app, |_, data| {
+        data.llm_request = settings;
+        Ok(())
+    })
+}
+
 pub fn active_llm_entry(
     app: &AppHandle<Wry>,
     override_model: Option<&str>,
@@ -550,6 +940,9 @@ pub fn update_text_model_credentials(
         };
 
         let variant_id = resolve_variant_id(model_config, provider_config);
+        if sanitized.is_none() {
+            clear_secret(&llm_secret_account(&variant_id));
+        }
         if let Some(entry) = data
             .llm_models
             .iter_mut()
@@ -609,6 +1002,9 @@ pub fn update_asr_credentials(
         };
 
         let variant_id = resolve_asr_variant_id(model_config, provider_config);
+        if sanitize_api_key(access_token.clone()).is_none() {
+            clear_secret(&asr_secret_account(&variant_id));
+        }
         if let Some(entry) = data
             .asr_models
             .iter_mut()
@@ -648,6 +1044,189 @@ pub fn update_asr_credentials(
     })
 }
 
+fn normalize_custom_provider(
+    id: String,
+    input: &CustomLlmProviderInput,
+) -> Result<CustomLlmProvider, String> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("请填写服务名称".to_string());
+    }
+    let model = input.model.trim();
+    if model.is_empty() {
+        return Err("请填写模型名称".to_string());
+    }
+
+    let base_url = match input.api_base_url.trim().trim_end_matches('/') {
+        "" => input
+            .backend
+            .default_server_url()
+            .ok_or_else(|| "请填写接口地址".to_string())?,
+        value => value,
+    };
+    let parsed = reqwest::Url::parse(base_url).map_err(|_| "接口地址格式不正确".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("接口地址需以 http:// 或 https:// 开头".to_string());
+    }
+    // 允许只填写 `/v1` 这样的基础地址，自动补全 chat completions 路径；
+    // 本地服务通常只填写 `http://localhost:11434`，统一走其 OpenAI 兼容接口
+    let api_base_url = match input.backend.protocol() {
+        LlmProtocol::AnthropicMessages if base_url.ends_with(ANTHROPIC_MESSAGES_PATH) => {
+            base_url.to_string()
+        }
+        LlmProtocol::AnthropicMessages if base_url.ends_with(OPENAI_VERSION_PATH) => {
+            format!("{base_url}{ANTHROPIC_MESSAGES_PATH}")
+        }
+        LlmProtocol::AnthropicMessages => {
+            format!("{base_url}{OPENAI_VERSION_PATH}{ANTHROPIC_MESSAGES_PATH}")
+        }
+        // Gemini 的模型名写在路径里，只保存到版本号为止的基础地址
+        LlmProtocol::GeminiGenerateContent => base_url
+            .split_once("/models/")
+            .map_or(base_url, |(root, _)| root)
+            .to_string(),
+        LlmProtocol::OpenAiCompatible if base_url.ends_with(CHAT_COMPLETIONS_PATH) => {
+            base_url.to_string()
+        }
+        LlmProtocol::OpenAiCompatible
+            if input.backend.is_local() && !base_url.ends_with(OPENAI_VERSION_PATH) =>
+        {
+            format!("{base_url}{OPENAI_VERSION_PATH}{CHAT_COMPLETIONS_PATH}")
+        }
+        LlmProtocol::OpenAiCompatible => format!("{base_url}{CHAT_COMPLETIONS_PATH}"),
+    };
+
+    let mut extra_headers = BTreeMap::new();
+    for (key, value) in &input.extra_headers {
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        reqwest::header::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|_| format!("请求头名称无效: {key}"))?;
+        reqwest::header::HeaderValue::from_str(value.trim())
+            .map_err(|_| format!("请求头 {key} 的值无效"))?;
+        extra_headers.insert(key.to_string(), value.trim().to_string());
+    }
+
+    Ok(CustomLlmProvider {
+        id,
+        name: name.to_string(),
+        backend: input.backend,
+        api_base_url,
+        model: model.to_string(),
+        api_key_env: input
+            .api_key_env
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+        extra_headers,
+    })
+}
+
+fn set_custom_provider_key(data: &mut ModelsStore, provider_id: &str, api_key: Option<String>) {
+    let variant_id = format!("{provider_id}::{CUSTOM_PROVIDER_ID}");
+    if let Some(entry) = data
+        .llm_models
+        .iter_mut()
+        .find(|entry| entry.id == variant_id)
+    {
+        entry.api_key = api_key;
+        entry.active = true;
+    } else {
+        data.llm_models.push(LlmModelStore {
+            id: variant_id,
+            text_model_id: provider_id.to_string(),
+            provider: CUSTOM_PROVIDER_ID.to_string(),
+            api_key,
+            active: true,
+            ..Default::default()
+        });
+    }
+}
+
+pub fn custom_llm_provider(
+    app: &AppHandle<Wry>,
+    id: &str,
+) -> Result<Option<CustomLlmProvider>, String> {
+    let (_, data) = read_store(app)?;
+    Ok(data
+        .custom_llm_providers
+        .into_iter()
+        .find(|provider| provider.id == id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_custom_llm_provider(
+    app: AppHandle,
+    provider: CustomLlmProviderInput,
+) -> Result<ModelsStore, String> {
+    let id = format!("custom-{}", Uuid::new_v4());
+    let normalized = normalize_custom_provider(id.clone(), &provider)?;
+    let api_key = sanitize_api_key(provider.api_key);
+
+    with_models_store(&app, |_, data| {
+        data.custom_llm_providers.push(normalized);
+        set_custom_provider_key(data, &id, api_key);
+        Ok(())
+    })
+}
+
+/// 更新自定义文本模型；`apiKey` 为空时保留原有密钥
+#[tauri::command]
+#[specta::specta]
+pub fn update_custom_llm_provider(
+    app: AppHandle,
+    id: String,
+    provider: CustomLlmProviderInput,
+) -> Result<ModelsStore, String> {
+    let normalized = normalize_custom_provider(id.clone(), &provider)?;
+    let api_key = sanitize_api_key(provider.api_key);
+
+    with_models_store(&app, |_, data| {
+        let Some(existing) = data
+            .custom_llm_providers
+            .iter_mut()
+            .find(|existing| existing.id == id)
+        else {
+            return Err("未知的自定义文本模型".to_string());
+        };
+        *existing = normalized;
+        if api_key.is_some() {
+            set_custom_provider_key(data, &id, api_key);
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_custom_llm_provider(app: AppHandle, id: String) -> Result<ModelsStore, String> {
+    with_models_store(&app, |_, data| {
+        let before = data.custom_llm_providers.len();
+        data.custom_llm_providers
+            .retain(|provider| provider.id != id);
+        if data.custom_llm_providers.len() == before {
+            return Err("未知的自定义文本模型".to_string());
+        }
+        for entry in data
+            .llm_models
+            .iter()
+            .filter(|entry| entry.text_model_id == id)
+        {
+            clear_secret(&llm_secret_account(&entry.id));
+        }
+        data.llm_models.retain(|entry| entry.text_model_id != id);
+        data.llm_fallback_models.retain(|model_id| model_id != &id);
+        if data.active_llm_model.as_deref() == Some(id.as_str()) {
+            data.active_llm_model = None;
+        }
+        Ok(())
+    })
+}
+
 fn has_user_llm_key(entry: &LlmModelStore) -> bool {
     entry
         .api_key
@@ -656,24 +1235,32 @@ fn has_user_llm_key(entry: &LlmModelStore) -> bool {
         .unwrap_or(false)
 }
 
-pub fn check_llm_quota(app: &AppHandle<Wry>, entry: &LlmModelStore) -> Result<(), String> {
-    if has_user_llm_key(entry) {
+/// 是否使用内置体验密钥，受每日体验额度限制；自定义服务与本地服务使用用户自己的网关与凭据
+pub fn uses_trial_quota(entry: &LlmModelStore) -> bool {
+    !has_user_llm_key(entry) && entry.provider != CUSTOM_PROVIDER_ID
+}
+
+pub fn check_llm_quota(app: &AppHandle<Wry>, entry: &LlmModelStore) -> Result<(), LlmError> {
+    if !uses_trial_quota(entry) {
         return Ok(());
     }
 
     let entry_id = entry.id.clone();
+    let mut exceeded = false;
     with_models_store(app, |_, data| {
         let Some(target) = data.llm_models.iter_mut().find(|item| item.id == entry_id) else {
             return Err("未知文本模型".to_string());
         };
         let today = today_string();
         reset_llm_daily_usage(target, &today);
-        if target.free_total_token_usage >= LLM_DAILY_TOKEN_LIMIT {
-            return Err("体验额度已用完，请在“模型管理”配置 API 密钥。".to_string());
-        }
+        exceeded = target.free_total_token_usage >= LLM_DAILY_TOKEN_LIMIT;
         Ok(())
     })
-    .map(|_| ())
+    .map_err(LlmError::Internal)?;
+    if exceeded {
+        return Err(LlmError::QuotaExceeded { trial: true });
+    }
+    Ok(())
 }
 
 pub fn record_llm_usage(
@@ -781,3 +1368,95 @@ pub fn reset_usage_stats(app: &AppHandle<Wry>) -> Result<(), String> {
     })
     .map(|_| ())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn use_mock_keyring() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            keyring::set_default_credential_builder(keyring::mock::default_credential_builder())
+        });
+    }
+
+    fn llm_entry(id: &str, api_key: Option<&str>) -> LlmModelStore {
+        LlmModelStore {
+            id: id.to_string(),
+            provider: "openai".to_string(),
+            api_key: api_key.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plaintext_keys_move_to_keyring() {
+        use_mock_keyring();
+        let mut data = ModelsStore {
+            llm_models: vec![llm_entry("migrate-llm", Some("plaintext-llm-key-0001"))],
+            asr_models: vec![AsrModelStore {
+                id: "migrate-asr".to_string(),
+                provider: "volcengine".to_string(),
+                access_token: Some("plaintext-asr-token-0001".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(load_secrets(&mut data));
+        let on_disk = prepare_for_disk(&data);
+        assert_eq!(on_disk.llm_models[0].api_key, None);
+        assert_eq!(on_disk.asr_models[0].access_token, None);
+        // 内存中的配置仍带着密钥
+        assert_eq!(
+            data.llm_models[0].api_key.as_deref(),
+            Some("plaintext-llm-key-0001")
+        );
+
+        let mut reloaded = on_disk;
+        assert!(!load_secrets(&mut reloaded));
+        assert_eq!(
+            reloaded.llm_models[0].api_key.as_deref(),
+            Some("plaintext-llm-key-0001")
+        );
+        assert_eq!(
+            reloaded.asr_models[0].access_token.as_deref(),
+            Some("plaintext-asr-token-0001")
+        );
+    }
+
+    #[test]
+    fn saving_without_key_keeps_keyring_entry() {
+        use_mock_keyring();
+        let account = llm_secret_account("keep-llm");
+        secrets::set(&account, Some("stored-llm-key-0002")).unwrap();
+
+        let data = ModelsStore {
+            llm_models: vec![llm_entry("keep-llm", None)],
+            ..Default::default()
+        };
+        prepare_for_disk(&data);
+        assert_eq!(
+            secrets::get(&account).unwrap().as_deref(),
+            Some("stored-llm-key-0002")
+        );
+    }
+
+    #[test]
+    fn clearing_skips_unreadable_entries() {
+        use_mock_keyring();
+        let unreadable = llm_secret_account("unreadable-llm");
+        secrets::set(&unreadable, Some("stored-llm-key-0003")).unwrap();
+        unreadable_secrets().insert(unreadable.clone());
+        clear_secret(&unreadable);
+        assert_eq!(
+            secrets::get(&unreadable).unwrap().as_deref(),
+            Some("stored-llm-key-0003")
+        );
+
+        let readable = llm_secret_account("readable-llm");
+        secrets::set(&readable, Some("stored-llm-key-0004")).unwrap();
+        clear_secret(&readable);
+        assert_eq!(secrets::get(&readable).unwrap(), None);
+    }
+}
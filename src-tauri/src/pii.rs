@@ -0,0 +1,602 @@
+//! 发送给云端文本模型前的隐私信息脱敏：把手机号、身份证号、银行卡号等替换为
+//! `[[PHONE_1]]` 形式的占位符，润色结果返回后再还原为原文。
+
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+
+use crate::settings::SettingsStore;
+
+/// 追加到系统提示词末尾，要求模型保留占位符
+const PLACEHOLDER_HINT: &str =
+    "文本中形如 [[PHONE_1]] 的占位符代表已隐藏的个人信息，请原样保留，不要修改、翻译或删除。";
+/// 自定义规则命中时使用的占位符前缀
+const CUSTOM_LABEL: &str = "PII";
+
+/// 内置的识别规则
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PiiRule {
+    /// 中国大陆手机号，支持 +86 前缀与空格、短横线分隔
+    MobilePhone,
+    /// 带区号的固定电话，如 010-12345678、(0755)1234567
+    Landline,
+    /// 18 位居民身份证号，校验末位校验码
+    IdCard,
+    /// 16 至 19 位银行卡号，校验 Luhn
+    BankCard,
+    Email,
+}
+
+impl PiiRule {
+    const ALL: [PiiRule; 5] = [
+        PiiRule::IdCard,
+        PiiRule::BankCard,
+        PiiRule::MobilePhone,
+        PiiRule::Landline,
+        PiiRule::Email,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PiiRule::MobilePhone => "PHONE",
+            PiiRule::Landline => "TEL",
+            PiiRule::IdCard => "ID",
+            PiiRule::BankCard => "CARD",
+            PiiRule::Email => "EMAIL",
+        }
+    }
+
+    fn regex(self) -> &'static Regex {
+        static MOBILE_PHONE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?:\+?86[ -]?)?1[3-9]\d(?:[ -]?\d{4}){2}").expect("手机号正则无效")
+        });
+        static LANDLINE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?:[(（]0\d{2,3}[)）]|0\d{2,3}-)\d{7,8}(?:-\d{1,6})?")
+                .expect("固定电话正则无效")
+        });
+        static ID_CARD: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r"[1-9]\d{5}(?:18|19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])\d{3}[\dXx]",
+            )
+            .expect("身份证号正则无效")
+        });
+        static BANK_CARD: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"[1-9]\d{3}(?:[ -]?\d{4}){3}(?:[ -]?\d{1,3})?").expect("银行卡号正则无效")
+        });
+        static EMAIL: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+                .expect("邮箱正则无效")
+        });
+
+        match self {
+            PiiRule::MobilePhone => &MOBILE_PHONE,
+            PiiRule::Landline => &LANDLINE,
+            PiiRule::IdCard => &ID_CARD,
+            PiiRule::BankCard => &BANK_CARD,
+            PiiRule::Email => &EMAIL,
+        }
+    }
+
+    /// 正则无法表达的校验：前后不能紧跟数字、校验码正确等
+    fn accepts(self, text: &str, start: usize, end: usize) -> bool {
+        let matched = &text[start..end];
+        let digit_bounded = !text[..start].ends_with(|c: char| c.is_ascii_digit())
+            && !text[end..].starts_with(|c: char| c.is_ascii_digit());
+        match self {
+            PiiRule::MobilePhone | PiiRule::Landline => digit_bounded,
+            PiiRule::IdCard => digit_bounded && id_card_checksum_valid(matched),
+            PiiRule::BankCard => {
+                let digits: String = matched.chars().filter(char::is_ascii_digit).collect();
+                digit_bounded && (16..=19).contains(&digits.len()) && luhn_valid(&digits)
+            }
+            PiiRule::Email => true,
+        }
+    }
+}
+
+/// GB 11643 身份证号校验码（ISO 7064 MOD 11-2）
+fn id_card_checksum_valid(id: &str) -> bool {
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    const CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() != 18 {
+        return false;
+    }
+    let mut sum = 0;
+    for (c, weight) in chars.iter().zip(WEIGHTS) {
+        match c.to_digit(10) {
+            Some(digit) => sum += digit * weight,
+            None => return false,
+        }
+    }
+    chars[17].to_ascii_uppercase() == CHECK_CODES[(sum % 11) as usize]
+}
+
+fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (index, c) in digits.chars().rev().enumerate() {
+        let Some(mut digit) = c.to_digit(10) else {
+            return false;
+        };
+        if index % 2 == 1 {
+            digit *= 2;
+            if digit > 9 {
+                digit -= 9;
+            }
+        }
+        sum += digit;
+    }
+    sum % 10 == 0
+}
+
+/// 用户自定义的识别规则
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiPattern {
+    pub name: String,
+    /// Rust regex 语法的正则表达式
+    pub pattern: String,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiMaskingSettings {
+    /// 是否在调用云端文本模型前脱敏，发往本机地址的请求不受影响
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_builtin_rules")]
+    pub builtin_rules: Vec<PiiRule>,
+    #[serde(default)]
+    pub custom_patterns: Vec<PiiPattern>,
+}
+
+fn default_builtin_rules() -> Vec<PiiRule> {
+    PiiRule::ALL.to_vec()
+}
+
+impl Default for PiiMaskingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            builtin_rules: default_builtin_rules(),
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+impl PiiMaskingSettings {
+    fn compile_custom_patterns(&self) -> Result<Vec<Regex>, String> {
+        self.custom_patterns
+            .iter()
+            .map(|item| {
+                Regex::new(&item.pattern)
+                    .map_err(|e| format!("自定义规则“{}”的正则表达式无效: {e}", item.name))
+            })
+            .collect()
+    }
+
+    fn normalize(&mut self) {
+        // 内置规则按固定优先级排列，身份证号优先于其中可能包含的手机号片段
+        self.builtin_rules = PiiRule::ALL
+            .into_iter()
+            .filter(|rule| self.builtin_rules.contains(rule))
+            .collect();
+        for item in &mut self.custom_patterns {
+            item.name = item.name.trim().to_string();
+            item.pattern = item.pattern.trim().to_string();
+        }
+        self.custom_patterns.retain(|item| !item.pattern.is_empty());
+    }
+}
+
+/// 脱敏后的文本与占位符对应的原文
+#[derive(Debug, Default)]
+pub struct MaskedText {
+    pub text: String,
+    originals: HashMap<String, String>,
+}
+
+impl MaskedText {
+    /// 替换掉的信息条数
+    pub fn len(&self) -> usize {
+        self.originals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.originals.is_empty()
+    }
+
+    /// 需要追加到系统提示词的说明，未替换任何内容时为 `None`
+    pub fn prompt_hint(&self) -> Option<&'static str> {
+        (!self.is_empty()).then_some(PLACEHOLDER_HINT)
+    }
+
+    /// 将模型输出中的占位符还原为原文；模型有时会把方括号改成全角或插入空格，一并兼容。
+    /// 输入中的占位符必须全部还原，且不能残留无法识别的占位符，否则返回错误
+    pub fn restore(&self, text: &str) -> Result<String, String> {
+        if self.is_empty() {
+            return Ok(text.to_string());
+        }
+        let mut restored_keys = HashSet::new();
+        let restored = PLACEHOLDER.replace_all(text, |caps: &regex::Captures| {
+            match self.originals.get_key_value(&caps[1]) {
+                Some((key, original)) => {
+                    restored_keys.insert(key.as_str());
+                    original.clone()
+                }
+                None => caps[0].to_string(),
+            }
+        });
+        // 上下文中的占位符不一定出现在结果里，只要求本次输入中的占位符都被保留
+        if let Some(missing) = PLACEHOLDER
+            .captures_iter(&self.text)
+            .map(|caps| caps.get(1).map_or("", |key| key.as_str()))
+            .find(|key| !restored_keys.contains(key))
+        {
+            return Err(format!("润色结果丢失了隐私信息占位符 {missing}"));
+        }
+        let remainder = PLACEHOLDER.replace_all(text, |caps: &regex::Captures| {
+            if self.originals.contains_key(&caps[1]) {
+                String::new()
+            } else {
+                caps[0].to_string()
+            }
+        });
+        if let Some(leftover) = PLACEHOLDER_LIKE.find(&remainder) {
+            return Err(format!(
+                "润色结果包含无法还原的占位符 {}",
+                leftover.as_str()
+            ));
+        }
+        Ok(restored.into_owned())
+    }
+
+    /// 流式预览使用的还原：结果尚不完整，只替换已识别的占位符
+    pub fn restore_partial(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+        PLACEHOLDER
+            .replace_all(text, |caps: &regex::Captures| {
+                match self.originals.get(&caps[1]) {
+                    Some(original) => original.clone(),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// 模型输出中的占位符，兼容全角方括号与多余空格
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\[【]{2}\s*([A-Z]+_\d+)\s*[\]】]{2}").expect("占位符正则无效"));
+/// 被模型改坏的占位符，例如只剩一层方括号
+static PLACEHOLDER_LIKE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\[【]\s*[A-Z]+_\d+\s*[\]】]").expect("占位符正则无效"));
+
+struct Span {
+    start: usize,
+    end: usize,
+    label: &'static str,
+}
+
+/// 按规则优先级收集命中位置，与已接受的片段重叠的结果丢弃
+fn find_spans(text: &str, settings: &PiiMaskingSettings, custom: &[Regex]) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    let overlaps = |spans: &[Span], start: usize, end: usize| {
+        spans
+            .iter()
+            .any(|span| start < span.end && span.start < end)
+    };
+    for rule in PiiRule::ALL
+        .into_iter()
+        .filter(|rule| settings.builtin_rules.contains(rule))
+    {
+        for found in rule.regex().find_iter(text) {
+            if rule.accepts(text, found.start(), found.end())
+                && !overlaps(&spans, found.start(), found.end())
+            {
+                spans.push(Span {
+                    start: found.start(),
+                    end: found.end(),
+                    label: rule.label(),
+                });
+            }
+        }
+    }
+    for regex in custom {
+        for found in regex.find_iter(text) {
+            if !found.is_empty() && !overlaps(&spans, found.start(), found.end()) {
+                spans.push(Span {
+                    start: found.start(),
+                    end: found.end(),
+                    label: CUSTOM_LABEL,
+                });
+            }
+        }
+    }
+    spans.sort_by_key(|span| span.start);
+    spans
+}
+
+/// 占位符分配表，多段文本共用时同一原文得到同一个占位符
+#[derive(Default)]
+struct Placeholders {
+    originals: HashMap<String, String>,
+    keys: HashMap<String, String>,
+    counters: HashMap<&'static str, usize>,
+}
+
+impl Placeholders {
+    fn replace(&mut self, text: &str, spans: &[Span]) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut cursor = 0;
+        for span in spans {
+            let original = &text[span.start..span.end];
+            let key = match self.keys.get(original) {
+                Some(key) => key.clone(),
+                None => {
+                    let counter = self.counters.entry(span.label).or_default();
+                    *counter += 1;
+                    let key = format!("{}_{}", span.label, counter);
+                    self.keys.insert(original.to_string(), key.clone());
+                    self.originals.insert(key.clone(), original.to_string());
+                    key
+                }
+            };
+            output.push_str(&text[cursor..span.start]);
+            output.push_str("[[");
+            output.push_str(&key);
+            output.push_str("]]");
+            cursor = span.end;
+        }
+        output.push_str(&text[cursor..]);
+        output
+    }
+}
+
+fn mask_texts(
+    texts: &[&str],
+    settings: &PiiMaskingSettings,
+) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    if !settings.enabled {
+        return Ok((
+            texts.iter().map(|text| text.to_string()).collect(),
+            HashMap::new(),
+        ));
+    }
+    let custom = settings.compile_custom_patterns()?;
+    let mut placeholders = Placeholders::default();
+    let masked = texts
+        .iter()
+        .map(|text| placeholders.replace(text, &find_spans(text, settings, &custom)))
+        .collect();
+    Ok((masked, placeholders.originals))
+}
+
+/// 按设置识别并替换文本中的隐私信息；同一原文重复出现时使用同一个占位符
+pub fn mask(text: &str, settings: &PiiMaskingSettings) -> Result<MaskedText, String> {
+    let (mut masked, originals) = mask_texts(&[text], settings)?;
+    Ok(MaskedText {
+        text: masked.pop().unwrap_or_default(),
+        originals,
+    })
+}
+
+/// 同时替换本次输入与附带的上下文，两者共用占位符编号，返回脱敏后的上下文
+pub fn mask_with_context(
+    text: &str,
+    context: &str,
+    settings: &PiiMaskingSettings,
+) -> Result<(MaskedText, String), String> {
+    let (mut masked, originals) = mask_texts(&[text, context], settings)?;
+    let context = masked.pop().unwrap_or_default();
+    Ok((
+        MaskedText {
+            text: masked.pop().unwrap_or_default(),
+            originals,
+        },
+        context,
+    ))
+}
+
+pub fn masking_settings(app: &AppHandle<Wry>) -> PiiMaskingSettings {
+    SettingsStore::load(app).pii_masking
+}
+
+/// 更新隐私信息脱敏设置
+#[tauri::command]
+#[specta::specta]
+pub fn set_pii_masking(
+    app: AppHandle,
+    mut settings: PiiMaskingSettings,
+) -> Result<PiiMaskingSettings, String> {
+    settings.normalize();
+    settings.compile_custom_patterns()?;
+    SettingsStore::update(&app, |store| store.pii_masking = settings.clone())?;
+    Ok(settings)
+}
+
+/// 预览脱敏效果，用于在设置页测试规则；忽略 `enabled` 开关
+#[tauri::command]
+#[specta::specta]
+pub fn preview_pii_masking(
+    mut settings: PiiMaskingSettings,
+    text: String,
+) -> Result<String, String> {
+    settings.normalize();
+    settings.enabled = true;
+    Ok(mask(&text, &settings)?.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> PiiMaskingSettings {
+        PiiMaskingSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn masked(text: &str) -> String {
+        mask(text, &enabled()).unwrap().text
+    }
+
+    #[test]
+    fn masks_mobile_numbers_in_common_formats() {
+        for phone in [
+            "13812345678",
+            "+8613812345678",
+            "+86 138 1234 5678",
+            "86-138-1234-5678",
+            "138 1234 5678",
+            "138-1234-5678",
+        ] {
+            assert_eq!(
+                masked(&format!("电话{phone}，")),
+                "电话[[PHONE_1]]，",
+                "{phone}"
+            );
+        }
+        // 前后紧跟数字的是更长的编号，不是手机号
+        assert_eq!(masked("订单号9138123456780"), "订单号9138123456780");
+        assert_eq!(masked("12345678901"), "12345678901");
+    }
+
+    #[test]
+    fn masks_landlines_with_area_codes() {
+        for tel in [
+            "010-12345678",
+            "(0755)1234567",
+            "（021）87654321",
+            "0571-8888888-123",
+        ] {
+            assert_eq!(masked(&format!("座机{tel}")), "座机[[TEL_1]]", "{tel}");
+        }
+        assert_eq!(masked("编号12345678"), "编号12345678");
+    }
+
+    #[test]
+    fn masks_id_cards_with_valid_checksum() {
+        assert_eq!(masked("身份证11010519491231002X"), "身份证[[ID_1]]");
+        assert_eq!(masked("身份证11010519491231002x"), "身份证[[ID_1]]");
+        assert_eq!(masked("身份证440304199001011233。"), "身份证[[ID_1]]。");
+        // 校验码错误
+        assert_eq!(
+            masked("身份证110105194912310021"),
+            "身份证110105194912310021"
+        );
+        assert_eq!(
+            masked("身份证110105194912310022"),
+            "身份证110105194912310022"
+        );
+    }
+
+    #[test]
+    fn masks_bank_cards_passing_luhn() {
+        assert_eq!(masked("卡号6222021000000005"), "卡号[[CARD_1]]");
+        assert_eq!(masked("卡号6222 0210 0000 0005"), "卡号[[CARD_1]]");
+        assert_eq!(masked("卡号6217000000000000004"), "卡号[[CARD_1]]");
+        assert_eq!(masked("卡号4111-1111-1111-1111"), "卡号[[CARD_1]]");
+        assert_eq!(masked("卡号4111111111111112"), "卡号4111111111111112");
+        // 62 开头同样要求通过 Luhn，避免误伤订单号等长数字
+        assert_eq!(masked("单号6222021000000006"), "单号6222021000000006");
+        assert_eq!(masked("单号6200000000000000001"), "单号6200000000000000001");
+    }
+
+    #[test]
+    fn masks_emails() {
+        assert_eq!(
+            masked("发到 zhang.san+work@mail.example.com.cn 就行"),
+            "发到 [[EMAIL_1]] 就行"
+        );
+        assert_eq!(masked("用 @张三 提醒一下"), "用 @张三 提醒一下");
+    }
+
+    #[test]
+    fn higher_priority_rules_win_overlaps() {
+        // 同时满足 Luhn 的身份证号按身份证处理
+        assert!(luhn_valid("440304199001011049"));
+        assert_eq!(masked("440304199001011049"), "[[ID_1]]");
+
+        let settings = PiiMaskingSettings {
+            enabled: true,
+            builtin_rules: vec![PiiRule::MobilePhone],
+            custom_patterns: vec![PiiPattern {
+                name: "工号".to_string(),
+                pattern: r"E\d{11}".to_string(),
+            }],
+        };
+        assert_eq!(
+            mask("E13812345678 和 13812345678", &settings).unwrap().text,
+            "E[[PHONE_1]] 和 [[PHONE_1]]"
+        );
+        assert_eq!(
+            mask("E13812345678 和 13812345678", &enabled())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn repeated_values_share_placeholders_across_context() {
+        let (text, context) = mask_with_context(
+            "给13812345678回电话，抄送a@example.com",
+            "用户：13900001111\n助手：好的，13812345678",
+            &enabled(),
+        )
+        .unwrap();
+        assert_eq!(text.text, "给[[PHONE_1]]回电话，抄送[[EMAIL_1]]");
+        assert_eq!(context, "用户：[[PHONE_2]]\n助手：好的，[[PHONE_1]]");
+        assert_eq!(text.len(), 3);
+        // 只在上下文中出现的占位符不要求出现在结果里
+        assert_eq!(
+            text.restore("给[[PHONE_1]]回电话，抄送[[EMAIL_1]]。")
+                .unwrap(),
+            "给13812345678回电话，抄送a@example.com。"
+        );
+    }
+
+    #[test]
+    fn restores_full_width_and_spaced_placeholders() {
+        let text = mask("电话13812345678，邮箱a@example.com", &enabled()).unwrap();
+        assert_eq!(
+            text.restore("电话：【【PHONE_1】】；邮箱：[[ EMAIL_1 ]]")
+                .unwrap(),
+            "电话：13812345678；邮箱：a@example.com"
+        );
+        assert_eq!(
+            text.restore("【【EMAIL_1】】、【【PHONE_1】】、[[PHONE_1]]")
+                .unwrap(),
+            "a@example.com、13812345678、13812345678"
+        );
+    }
+
+    #[test]
+    fn restore_rejects_dropped_or_broken_placeholders() {
+        let text = mask("电话13812345678，邮箱a@example.com", &enabled()).unwrap();
+        assert!(text.restore("电话已隐藏，邮箱[[EMAIL_1]]").is_err());
+        assert!(text.restore("电话[PHONE_1]，邮箱[[EMAIL_1]]").is_err());
+        assert!(text
+            .restore("电话[[PHONE_1]]，邮箱[[EMAIL_1]]，[[PHONE_2]]")
+            .is_err());
+        // 流式预览只替换已识别的占位符
+        assert_eq!(
+            text.restore_partial("电话[[PHONE_1]]，邮箱[[EMA"),
+            "电话13812345678，邮箱[[EMA"
+        );
+        // 未脱敏时原样返回
+        assert_eq!(
+            MaskedText::default().restore("[[PHONE_1]]").unwrap(),
+            "[[PHONE_1]]"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! 润色结果的偏离校验：模型有时会回答口述内容里的问题，或在结果前加上
+//! “好的，以下是优化后的文本”之类的说明，这些结果不能直接粘贴给用户。
+
+/// 原文过短时编辑距离与长度比例波动很大，不做这两项校验
+const MIN_CHECK_CHARS: usize = 12;
+/// 归一化编辑距离超过该值视为偏离原文
+const MAX_EDIT_DISTANCE: f32 = 0.6;
+const MIN_LENGTH_RATIO: f32 = 0.5;
+const MAX_LENGTH_RATIO: f32 = 2.0;
+
+/// 模型附加说明时常见的开头
+const PREAMBLE_PATTERNS: &[&str] = &[
+    "好的，",
+    "好的,",
+    "好的！",
+    "当然，",
+    "当然可以",
+    "以下是",
+    "下面是",
+    "这是优化后",
+    "优化后的文本",
+    "润色后的文本",
+    "修改后的文本",
+    "sure,",
+    "sure!",
+    "certainly",
+    "here is",
+    "here's",
+];
+
+/// 只保留文字与数字，忽略润色时正常调整的标点与空白
+fn normalize(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+fn preamble(original: &str, polished: &str) -> Option<&'static str> {
+    let original = original.trim_start().to_lowercase();
+    let polished = polished.trim_start().to_lowercase();
+    PREAMBLE_PATTERNS
+        .iter()
+        .find(|pattern| polished.starts_with(**pattern) && !original.starts_with(**pattern))
+        .copied()
+}
+
+/// 检查润色结果是否偏离原文，返回写入历史记录的原因；
+/// `allow_rewrite` 为 true 的模式（翻译、总结等）只检查附加说明
+pub fn check(original: &str, polished: &str, allow_rewrite: bool) -> Option<String> {
+    if let Some(pattern) = preamble(original, polished) {
+        return Some(format!("润色结果以“{pattern}”开头，疑似附加了说明"));
+    }
+    if allow_rewrite {
+        return None;
+    }
+
+    let original = normalize(original);
+    let polished = normalize(polished);
+    if original.len() < MIN_CHECK_CHARS {
+        return None;
+    }
+
+    let ratio = polished.len() as f32 / original.len() as f32;
+    if !(MIN_LENGTH_RATIO..=MAX_LENGTH_RATIO).contains(&ratio) {
+        return Some(format!("润色结果长度为原文的 {ratio:.1} 倍"));
+    }
+
+    let distance =
+        edit_distance(&original, &polished) as f32 / original.len().max(polished.len()) as f32;
+    if distance > MAX_EDIT_DISTANCE {
+        return Some(format!("润色结果与原文差异过大（编辑距离 {distance:.2}）"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repeat(c: char, n: usize) -> String {
+        std::iter::repeat_n(c, n).collect()
+    }
+
+    #[test]
+    fn length_ratio_bounds_are_inclusive() {
+        let original = repeat('a', 100);
+        assert!(check(&original, &repeat('a', 49), false).is_some());
+        assert_eq!(check(&original, &repeat('a', 50), false), None);
+        assert_eq!(check(&original, &repeat('a', 200), false), None);
+        assert!(check(&original, &repeat('a', 201), false).is_some());
+    }
+
+    #[test]
+    fn edit_distance_bound_is_inclusive() {
+        let original = repeat('a', 100);
+        let at_limit = repeat('b', 60) + &repeat('a', 40);
+        let over_limit = repeat('b', 61) + &repeat('a', 39);
+        assert_eq!(check(&original, &at_limit, false), None);
+        assert!(
+            check(&original, &over_limit, false).is_some_and(|reason| reason.contains("编辑距离"))
+        );
+    }
+
+    #[test]
+    fn punctuation_and_case_do_not_count_as_changes() {
+        assert_eq!(
+            check(
+                "明天下午三点开会 记得带上电脑和充电器",
+                "明天下午三点开会，记得带上电脑和充电器。",
+                false
+            ),
+            None
+        );
+        assert_eq!(
+            edit_distance(&normalize("Hello, World"), &normalize("hello world")),
+            0
+        );
+    }
+
+    #[test]
+    fn short_text_skips_distance_checks() {
+        let short = repeat('a', MIN_CHECK_CHARS - 1);
+        let long = repeat('a', MIN_CHECK_CHARS);
+        assert_eq!(
+            check(&short, &repeat('z', MIN_CHECK_CHARS - 1), false),
+            None
+        );
+        assert!(check(&long, &repeat('z', MIN_CHECK_CHARS), false).is_some());
+    }
+
+    #[test]
+    fn detects_preambles() {
+        let original = "帮我把这段话改得正式一点";
+        assert!(check(
+            original,
+            "好的，以下是优化后的文本：帮我把这段话改得正式一点",
+            false
+        )
+        .is_some_and(|reason| reason.contains("好的，")));
+        assert!(check(
+            "send the report",
+            "Here is the text: send the report",
+            false
+        )
+        .is_some());
+        // 原文本身以相同措辞开头时不算附加说明
+        assert_eq!(check("好的，明天见", "好的，明天见。", false), None);
+    }
+
+    #[test]
+    fn allow_rewrite_only_checks_preambles() {
+        let original = repeat('a', 100);
+        assert_eq!(check(&original, &repeat('b', 300), true), None);
+        assert_eq!(check(&original, &repeat('b', 10), true), None);
+        assert!(check(&original, "以下是翻译结果：bbbb", true).is_some());
+    }
+}
@@ -0,0 +1,776 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    history::HistoryKind, hotkeys::HotkeyAction, llm::DEFAULT_SYSTEM_PROMPT,
+    settings::SettingsStore, translation,
+};
+
+const PROMPTS_STORE_NAME: &str = "store";
+const PROMPTS_STORE_KEY: &str = "prompts";
+pub const DEFAULT_TEMPLATE_ID: &str = "polish";
+/// 翻译输入未单独指定模式时使用的模板
+pub const TRANSLATE_TEMPLATE_ID: &str = "translate";
+/// 语音编辑选中文本使用的模板，请求内容由选中文本与口述指令组成
+pub const EDIT_TEMPLATE_ID: &str = "edit-selection";
+/// 不能删除的内置模板，缺失时总会补回
+const REQUIRED_TEMPLATE_IDS: [&str; 3] =
+    [DEFAULT_TEMPLATE_ID, TRANSLATE_TEMPLATE_ID, EDIT_TEMPLATE_ID];
+/// 历次版本内置的默认润色提示词，修改 `DEFAULT_SYSTEM_PROMPT` 时把旧值追加到这里
+const SHIPPED_DEFAULT_PROMPTS: &[&str] = &[DEFAULT_SYSTEM_PROMPT];
+
+/// 一种润色模式：独立的系统提示词、温度与可选的文本模型
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub system_prompt: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// 使用指定的文本模型 ID，为空时使用当前激活的文本模型
+    #[serde(default)]
+    pub model_override: Option<String>,
+    #[serde(default)]
+    pub builtin: bool,
+    /// 内置模板的提示词被用户修改过；未修改的内置模板会随版本更新默认提示词
+    #[serde(default)]
+    pub customized: bool,
+    /// 翻译、总结等会大幅改写原文的模式，润色结果不做编辑距离与长度校验
+    #[serde(default)]
+    pub allow_rewrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateInput {
+    pub name: String,
+    pub system_prompt: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub model_override: Option<String>,
+    #[serde(default)]
+    pub allow_rewrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsStore {
+    #[serde(default)]
+    pub templates: Vec<PromptTemplate>,
+    #[serde(default = "default_template_id")]
+    pub default_template: String,
+    /// 各快捷键对应的润色模式，未设置时使用默认模式
+    #[serde(default)]
+    pub hotkey_templates: HashMap<HotkeyAction, String>,
+    /// 专有名词表，通过 `{glossary}` 注入提示词
+    #[serde(default)]
+    pub glossary: Vec<String>,
+    /// 用户删除过的内置模板，新版本补充内置模板时不再加回
+    #[serde(default)]
+    pub removed_builtins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDetails {
+    pub template_id: String,
+    pub system_prompt: String,
+    /// 内置模板的默认提示词，自定义模板为空
+    pub default_prompt: Option<String>,
+    pub customized: bool,
+}
+
+/// 提示词中可用的变量，在每次请求时展开
+pub struct PromptVariables<'a> {
+    pub kind: HistoryKind,
+    pub source_app: Option<&'a str>,
+}
+
+fn default_template_id() -> String {
+    DEFAULT_TEMPLATE_ID.to_string()
+}
+
+impl Default for PromptsStore {
+    fn default() -> Self {
+        Self {
+            templates: builtin_templates(),
+            default_template: default_template_id(),
+            hotkey_templates: HashMap::new(),
+            glossary: Vec::new(),
+            removed_builtins: Vec::new(),
+        }
+    }
+}
+
+fn builtin_template(
+    id: &str,
+    name: &str,
+    system_prompt: &str,
+    temperature: f32,
+    allow_rewrite: bool,
+) -> PromptTemplate {
+    PromptTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        system_prompt: system_prompt.to_string(),
+        temperature: Some(temperature),
+        model_override: None,
+        builtin: true,
+        customized: false,
+        allow_rewrite,
+    }
+}
+
+fn builtin_default(id: &str) -> Option<PromptTemplate> {
+    builtin_templates()
+        .into_iter()
+        .find(|template| template.id == id)
+}
+
+fn builtin_default_prompt(id: &str) -> Option<String> {
+    builtin_default(id).map(|template| template.system_prompt)
+}
+
+fn builtin_templates() -> Vec<PromptTemplate> {
+    vec![
+        builtin_template(DEFAULT_TEMPLATE_ID, "润色", DEFAULT_SYSTEM_PROMPT, 0.3, false),
+        builtin_template(
+            "formal-email",
+            "正式邮件",
+            "你是一个商务写作助手。请把用户提供的语音识别文本改写为一封得体的正式邮件：
+1. 修正语音识别可能出现的错误
+2. 使用礼貌、专业的书面语，按称呼、正文、结尾敬语组织
+3. 保持原意不变，不要编造事实、时间或人名
+4. 直接返回邮件正文，不要添加任何解释或前缀",
+            0.4,
+            true,
+        ),
+        builtin_template(
+            "bullet-summary",
+            "要点总结",
+            "你是一个信息整理助手。请把用户提供的语音识别文本整理为简洁的要点列表：
+1. 修正语音识别可能出现的错误
+2. 每个要点单独一行，以“- ”开头，按重要程度排序
+3. 只保留关键信息，不要添加原文没有的内容
+4. 直接返回要点列表，不要添加任何解释或前缀",
+            0.2,
+            true,
+        ),
+        builtin_template(
+            TRANSLATE_TEMPLATE_ID,
+            "翻译",
+            "You are a professional translator. Translate the user's speech-recognized text from {source_language} into natural, fluent {target_language}:
+1. Fix obvious speech recognition errors before translating
+2. Keep the original meaning, tone and formatting; keep names, code and URLs unchanged
+3. If the text is already in {target_language}, only fix the errors
+4. Return only the translation without any explanation or prefix",
+            0.2,
+            true,
+        ),
+        builtin_template(
+            EDIT_TEMPLATE_ID,
+            "语音编辑",
+            "你是一个文本编辑助手。用户会提供一段选中的文本（<selection> 标签内）和一条语音识别得到的修改指令（<instruction> 标签内）：
+1. 按指令修改选中的文本，例如改写语气、翻译、缩写、扩写或修正错误
+2. 指令可能含有语音识别错误，请按最合理的意图理解
+3. 未要求修改的部分保持原样，保留原文的格式与换行
+4. 直接返回修改后的完整文本，不要包含标签，不要添加任何解释或前缀",
+            0.3,
+            true,
+        ),
+        builtin_template(
+            "translate-en",
+            "翻译为英文",
+            "You are a professional translator. Translate the user's speech-recognized text into natural, fluent English:
+1. Fix obvious speech recognition errors before translating
+2. Keep the original meaning, tone and formatting
+3. Return only the translation without any explanation or prefix",
+            0.2,
+            true,
+        ),
+        builtin_template(
+            "code-comment",
+            "代码注释",
+            "你是一个资深程序员。请把用户提供的语音识别文本改写为简洁的代码注释：
+1. 修正语音识别可能出现的错误，技术名词使用正确的大小写与拼写
+2. 语言精炼，说明意图而不是复述代码
+3. 不要添加注释符号（如 // 或 #），不要使用 Markdown
+4. 直接返回注释内容，不要添加任何解释或前缀",
+            0.2,
+            true,
+        ),
+    ]
+}
+
+fn read_store(app: &AppHandle<Wry>) -> Result<(Arc<Store<Wry>>, PromptsStore), String> {
+    let handle = app
+        .store(PROMPTS_STORE_NAME)
+        .map_err(|e| format!("加载提示词存储失败: {e}"))?;
+
+    // 解析失败时报错而不是回退到默认值，避免随后的保存覆盖用户的模板与快捷键绑定
+    let data = match handle.get(PROMPTS_STORE_KEY) {
+        Some(value) => serde_json::from_value::<PromptsStore>(value)
+            .map_err(|e| format!("提示词存储格式错误: {e}"))?,
+        None => migrate_legacy_prompt(app),
+    };
+
+    Ok((handle, data))
+}
+
+/// 旧版本只有 `SettingsStore.llm_system_prompt` 一个提示词，首次加载时迁移到“润色”模式
+fn migrate_legacy_prompt(app: &AppHandle<Wry>) -> PromptsStore {
+    store_from_legacy_prompt(SettingsStore::load(app).llm_system_prompt)
+}
+
+/// 旧版本不提供编辑入口，却会把当时的默认提示词写入设置；只有与历次默认值都不同的提示词才视为用户修改，
+/// 否则沿用最新的默认提示词
+fn store_from_legacy_prompt(legacy: Option<String>) -> PromptsStore {
+    let mut data = PromptsStore::default();
+    let legacy = legacy.filter(|prompt| {
+        !prompt.trim().is_empty()
+            && !SHIPPED_DEFAULT_PROMPTS
+                .iter()
+                .any(|default| same_prompt(default, prompt))
+    });
+    if let (Some(prompt), Some(template)) = (
+        legacy,
+        data.templates
+            .iter_mut()
+            .find(|template| template.id == DEFAULT_TEMPLATE_ID),
+    ) {
+        template.system_prompt = prompt;
+        template.customized = true;
+    }
+    data
+}
+
+/// 忽略换行符与首尾空白的差异
+fn same_prompt(a: &str, b: &str) -> bool {
+    a.trim()
+        .lines()
+        .map(str::trim_end)
+        .eq(b.trim().lines().map(str::trim_end))
+}
+
+fn persist_store(handle: &Arc<Store<Wry>>, data: &PromptsStore) -> Result<(), String> {
+    handle.set(
+        PROMPTS_STORE_KEY,
+        serde_json::to_value(data).map_err(|e| e.to_string())?,
+    );
+    handle.save().map_err(|e| e.to_string())
+}
+
+/// 保证默认模式与快捷键绑定始终指向存在的模板，补上新版本增加的内置模板，
+/// 并为未修改的内置模板同步最新默认提示词
+fn normalize(data: &mut PromptsStore) {
+    for template in data
+        .templates
+        .iter_mut()
+        .filter(|template| template.builtin)
+    {
+        let Some(default) = builtin_default(&template.id) else {
+            continue;
+        };
+        template.allow_rewrite = default.allow_rewrite;
+        // 早先迁移时被误标为已修改的旧默认提示词
+        if template.id == DEFAULT_TEMPLATE_ID
+            && SHIPPED_DEFAULT_PROMPTS
+                .iter()
+                .any(|shipped| same_prompt(shipped, &template.system_prompt))
+        {
+            template.customized = false;
+        }
+        if !template.customized {
+            template.system_prompt = default.system_prompt;
+        }
+    }
+
+    data.removed_builtins
+        .retain(|id| !REQUIRED_TEMPLATE_IDS.contains(&id.as_str()));
+    for (index, builtin) in builtin_templates().into_iter().enumerate() {
+        let missing = !data
+            .templates
+            .iter()
+            .any(|template| template.id == builtin.id);
+        if missing && !data.removed_builtins.contains(&builtin.id) {
+            data.templates
+                .insert(index.min(data.templates.len()), builtin);
+        }
+    }
+
+    let exists = |id: &str| data.templates.iter().any(|template| template.id == id);
+    if !exists(&data.default_template) {
+        data.default_template = default_template_id();
+    }
+    let valid: Vec<String> = data
+        .templates
+        .iter()
+        .map(|template| template.id.clone())
+        .collect();
+    data.hotkey_templates.retain(|_, id| valid.contains(id));
+}
+
+fn with_prompts_store<F>(app: &AppHandle<Wry>, mutator: F) -> Result<PromptsStore, String>
+where
+    F: FnOnce(&mut PromptsStore) -> Result<(), String>,
+{
+    let (handle, mut data) = read_store(app)?;
+    normalize(&mut data);
+    mutator(&mut data)?;
+    normalize(&mut data);
+    persist_store(&handle, &data)?;
+    Ok(data)
+}
+
+pub fn init(app: &AppHandle<Wry>) {
+    if let Err(error) = with_prompts_store(app, |_| Ok(())) {
+        warn!(target = "miaoyu_prompts", error = %error, "初始化提示词模板失败");
+    }
+}
+
+/// 解析本次润色使用的模板：显式指定 > 默认模式 > 内置“润色”
+pub fn resolve_template(app: &AppHandle<Wry>, template_id: Option<&str>) -> PromptTemplate {
+    let data = match read_store(app) {
+        Ok((_, mut data)) => {
+            normalize(&mut data);
+            data
+        }
+        Err(error) => {
+            warn!(target = "miaoyu_prompts", error = %error, "读取提示词模板失败，使用默认模板");
+            PromptsStore::default()
+        }
+    };
+
+    let find = |id: &str| {
+        data.templates
+            .iter()
+            .find(|template| template.id == id)
+            .cloned()
+    };
+    template_id
+        .and_then(|id| {
+            // 被删除的内置模板（例如正式邮件）仍按默认提示词使用
+            let found = find(id).or_else(|| builtin_default(id));
+            if found.is_none() {
+                warn!(
+                    target = "miaoyu_prompts",
+                    template_id = id,
+                    "未知的提示词模板，使用默认模板"
+                );
+            }
+            found
+        })
+        .or_else(|| find(&data.default_template))
+        .unwrap_or_else(|| builtin_templates().remove(0))
+}
+
+static PROMPT_VARIABLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{(date|kind|source_app|glossary|source_language|target_language)\}")
+        .expect("提示词变量正则无效")
+});
+
+/// 一次性替换所有变量，已代入的值中即使含有 `{...}` 也不会再被展开
+fn substitute_variables(prompt: &str, value: impl Fn(&str) -> String) -> String {
+    PROMPT_VARIABLE
+        .replace_all(prompt, |captures: &regex::Captures| value(&captures[1]))
+        .into_owned()
+}
+
+/// 展开提示词变量：`{date}`、`{kind}`、`{source_app}`、`{glossary}`、
+/// `{source_language}`、`{target_language}`
+pub fn expand_variables(app: &AppHandle<Wry>, prompt: &str, variables: &PromptVariables) -> String {
+    if !prompt.contains('{') {
+        return prompt.to_string();
+    }
+
+    let glossary = read_store(app)
+        .map(|(_, data)| data.glossary.join("、"))
+        .unwrap_or_default();
+    let kind = match variables.kind {
+        HistoryKind::Dictation => "语音输入",
+        HistoryKind::Diary => "语音日记",
+        HistoryKind::Translation => "翻译输入",
+        HistoryKind::Edit => "语音编辑",
+        HistoryKind::Digest => "日记摘要",
+    };
+    let languages = translation::translation_settings(app);
+
+    substitute_variables(prompt, |name| match name {
+        "date" => chrono::Local::now().format("%Y-%m-%d").to_string(),
+        "kind" => kind.to_string(),
+        "source_app" => variables.source_app.unwrap_or("未知应用").to_string(),
+        "glossary" => glossary.clone(),
+        "source_language" => languages.source_language.prompt_name().to_string(),
+        "target_language" => languages.target_language.prompt_name().to_string(),
+        _ => String::new(),
+    })
+}
+
+/// 解析模板并展开变量，得到本次请求实际使用的模板
+pub fn prepare_template(
+    app: &AppHandle<Wry>,
+    template_id: Option<&str>,
+    variables: &PromptVariables,
+) -> PromptTemplate {
+    let mut template = resolve_template(app, template_id);
+    template.system_prompt = expand_variables(app, &template.system_prompt, variables);
+    template
+}
+
+/// 快捷键绑定的润色模式 ID
+pub fn hotkey_template(app: &AppHandle<Wry>, action: HotkeyAction) -> Option<String> {
+    let (_, data) = read_store(app).ok()?;
+    data.hotkey_templates.get(&action).cloned()
+}
+
+fn set_system_prompt(template: &mut PromptTemplate, prompt: String) {
+    template.customized = match builtin_default_prompt(&template.id) {
+        Some(default) if template.builtin => prompt != default,
+        _ => true,
+    };
+    template.system_prompt = prompt;
+}
+
+fn find_template_mut<'a>(
+    data: &'a mut PromptsStore,
+    template_id: Option<&str>,
+) -> Result<&'a mut PromptTemplate, String> {
+    let id = template_id
+        .map(str::to_string)
+        .unwrap_or_else(|| data.default_template.clone());
+    data.templates
+        .iter_mut()
+        .find(|template| template.id == id)
+        .ok_or_else(|| "未知的提示词模板".to_string())
+}
+
+fn validate_input(input: PromptTemplateInput) -> Result<PromptTemplateInput, String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("请填写模板名称".to_string());
+    }
+    if input.system_prompt.trim().is_empty() {
+        return Err("请填写系统提示词".to_string());
+    }
+    if let Some(temperature) = input.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err("温度需在 0 到 2 之间".to_string());
+        }
+    }
+    Ok(PromptTemplateInput {
+        name,
+        model_override: input
+            .model_override
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()),
+        ..input
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_prompts_store(app: AppHandle) -> Result<PromptsStore, String> {
+    with_prompts_store(&app, |_| Ok(()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_prompt_template(
+    app: AppHandle,
+    template: PromptTemplateInput,
+) -> Result<PromptsStore, String> {
+    let input = validate_input(template)?;
+    with_prompts_store(&app, |data| {
+        data.templates.push(PromptTemplate {
+            id: format!("template-{}", Uuid::new_v4()),
+            name: input.name,
+            system_prompt: input.system_prompt,
+            temperature: input.temperature,
+            model_override: input.model_override,
+            builtin: false,
+            customized: true,
+            allow_rewrite: input.allow_rewrite,
+        });
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_prompt_template(
+    app: AppHandle,
+    id: String,
+    template: PromptTemplateInput,
+) -> Result<PromptsStore, String> {
+    let input = validate_input(template)?;
+    with_prompts_store(&app, |data| {
+        let existing = data
+            .templates
+            .iter_mut()
+            .find(|template| template.id == id)
+            .ok_or_else(|| "未知的提示词模板".to_string())?;
+        existing.name = input.name;
+        set_system_prompt(existing, input.system_prompt);
+        existing.temperature = input.temperature;
+        existing.model_override = input.model_override;
+        // 内置模板是否允许改写由版本决定
+        if !existing.builtin {
+            existing.allow_rewrite = input.allow_rewrite;
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_prompt_template(app: AppHandle, id: String) -> Result<PromptsStore, String> {
+    if REQUIRED_TEMPLATE_IDS.contains(&id.as_str()) {
+        return Err("该内置模板不能删除".to_string());
+    }
+    with_prompts_store(&app, |data| {
+        let index = data
+            .templates
+            .iter()
+            .position(|template| template.id == id)
+            .ok_or_else(|| "未知的提示词模板".to_string())?;
+        if data.templates.remove(index).builtin && !data.removed_builtins.contains(&id) {
+            data.removed_builtins.push(id);
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_default_prompt_template(app: AppHandle, id: String) -> Result<PromptsStore, String> {
+    with_prompts_store(&app, |data| {
+        if !data.templates.iter().any(|template| template.id == id) {
+            return Err("未知的提示词模板".to_string());
+        }
+        data.default_template = id;
+        Ok(())
+    })
+}
+
+/// 为快捷键指定润色模式；`templateId` 为空时恢复使用默认模式
+#[tauri::command]
+#[specta::specta]
+pub fn set_hotkey_prompt_template(
+    app: AppHandle,
+    action: HotkeyAction,
+    template_id: Option<String>,
+) -> Result<PromptsStore, String> {
+    with_prompts_store(&app, |data| {
+        match template_id {
+            Some(id) => {
+                if !data.templates.iter().any(|template| template.id == id) {
+                    return Err("未知的提示词模板".to_string());
+                }
+                data.hotkey_templates.insert(action, id);
+            }
+            None => {
+                data.hotkey_templates.remove(&action);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// 读取提示词；`templateId` 为空时读取默认模式
+#[tauri::command]
+#[specta::specta]
+pub fn get_prompt(app: AppHandle, template_id: Option<String>) -> Result<PromptDetails, String> {
+    let mut data = with_prompts_store(&app, |_| Ok(()))?;
+    let template = find_template_mut(&mut data, template_id.as_deref())?;
+    Ok(PromptDetails {
+        template_id: template.id.clone(),
+        system_prompt: template.system_prompt.clone(),
+        default_prompt: template
+            .builtin
+            .then(|| builtin_default_prompt(&template.id))
+            .flatten(),
+        customized: template.customized,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_prompt(
+    app: AppHandle,
+    template_id: Option<String>,
+    prompt: String,
+) -> Result<PromptsStore, String> {
+    if prompt.trim().is_empty() {
+        return Err("请填写系统提示词".to_string());
+    }
+    with_prompts_store(&app, |data| {
+        let template = find_template_mut(data, template_id.as_deref())?;
+        set_system_prompt(template, prompt);
+        Ok(())
+    })
+}
+
+/// 恢复内置模板的默认提示词，之后会继续跟随版本更新
+#[tauri::command]
+#[specta::specta]
+pub fn reset_prompt(app: AppHandle, template_id: Option<String>) -> Result<PromptsStore, String> {
+    with_prompts_store(&app, |data| {
+        let template = find_template_mut(data, template_id.as_deref())?;
+        let default = template
+            .builtin
+            .then(|| builtin_default_prompt(&template.id))
+            .flatten()
+            .ok_or_else(|| "自定义模板没有默认提示词".to_string())?;
+        template.system_prompt = default;
+        template.customized = false;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_prompt_glossary(app: AppHandle, terms: Vec<String>) -> Result<PromptsStore, String> {
+    with_prompts_store(&app, |data| {
+        let mut glossary: Vec<String> = Vec::new();
+        for term in terms {
+            let term = term.trim();
+            if !term.is_empty() && !glossary.iter().any(|existing| existing == term) {
+                glossary.push(term.to_string());
+            }
+        }
+        data.glossary = glossary;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polish_template(data: &PromptsStore) -> &PromptTemplate {
+        data.templates
+            .iter()
+            .find(|template| template.id == DEFAULT_TEMPLATE_ID)
+            .unwrap()
+    }
+
+    #[test]
+    fn variables_are_expanded_in_a_single_pass() {
+        let expanded = substitute_variables(
+            "应用：{source_app}；术语：{glossary}；{unknown}",
+            |name| match name {
+                "source_app" => "{glossary}".to_string(),
+                "glossary" => "{target_language}".to_string(),
+                _ => unreachable!("unexpected variable {name}"),
+            },
+        );
+        assert_eq!(
+            expanded,
+            "应用：{glossary}；术语：{target_language}；{unknown}"
+        );
+    }
+
+    #[test]
+    fn legacy_default_prompt_is_not_customized() {
+        for legacy in [
+            None,
+            Some(String::new()),
+            Some(DEFAULT_SYSTEM_PROMPT.to_string()),
+            Some(format!(
+                "{}\r\n",
+                DEFAULT_SYSTEM_PROMPT.replace('\n', "\r\n")
+            )),
+        ] {
+            let data = store_from_legacy_prompt(legacy.clone());
+            let template = polish_template(&data);
+            assert!(!template.customized, "{legacy:?}");
+            assert_eq!(template.system_prompt, DEFAULT_SYSTEM_PROMPT);
+        }
+    }
+
+    #[test]
+    fn normalize_unpins_shipped_default_prompt() {
+        let mut data = PromptsStore::default();
+        let template = data
+            .templates
+            .iter_mut()
+            .find(|template| template.id == DEFAULT_TEMPLATE_ID)
+            .unwrap();
+        template.customized = true;
+        normalize(&mut data);
+        assert!(!polish_template(&data).customized);
+    }
+
+    #[test]
+    fn edited_legacy_prompt_is_customized() {
+        let data = store_from_legacy_prompt(Some("只修正错别字".to_string()));
+        let template = polish_template(&data);
+        assert!(template.customized);
+        assert_eq!(template.system_prompt, "只修正错别字");
+    }
+
+    #[test]
+    fn normalize_restores_missing_builtins() {
+        let mut data = PromptsStore::default();
+        data.templates
+            .retain(|template| template.id == "formal-email" || template.id == "code-comment");
+        normalize(&mut data);
+        let ids: Vec<&str> = data
+            .templates
+            .iter()
+            .map(|template| template.id.as_str())
+            .collect();
+        let expected: Vec<String> = builtin_templates()
+            .into_iter()
+            .map(|template| template.id)
+            .collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn normalize_keeps_deleted_optional_builtins_removed() {
+        let mut data = PromptsStore::default();
+        data.templates.retain(|template| {
+            template.id != "formal-email" && template.id != TRANSLATE_TEMPLATE_ID
+        });
+        data.removed_builtins = vec![
+            "formal-email".to_string(),
+            TRANSLATE_TEMPLATE_ID.to_string(),
+        ];
+        normalize(&mut data);
+        let has = |id: &str| data.templates.iter().any(|template| template.id == id);
+        assert!(!has("formal-email"));
+        assert!(has(TRANSLATE_TEMPLATE_ID));
+        assert_eq!(data.removed_builtins, vec!["formal-email".to_string()]);
+    }
+
+    #[test]
+    fn upgraded_store_gains_edit_selection_template() {
+        // 加入语音编辑之前保存的配置
+        let mut data: PromptsStore = serde_json::from_value(serde_json::json!({
+            "templates": [polish_template(&PromptsStore::default())],
+            "defaultTemplate": DEFAULT_TEMPLATE_ID,
+            "removedBuiltins": [EDIT_TEMPLATE_ID],
+        }))
+        .unwrap();
+        normalize(&mut data);
+        let template = data
+            .templates
+            .iter()
+            .find(|template| template.id == EDIT_TEMPLATE_ID)
+            .unwrap();
+        assert!(template.builtin && template.allow_rewrite);
+        assert!(data.removed_builtins.is_empty());
+    }
+}
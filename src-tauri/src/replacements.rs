@@ -0,0 +1,396 @@
+//! 文本替换规则：按用户维护的顺序执行字面量或正则替换，用于统一产品名大小写、展开常用片段等
+//! 不应依赖文本模型的处理。每条规则可限定记录类型，并选择在润色前或润色后执行
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, Wry};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{history::HistoryKind, settings::SettingsStore};
+
+/// 导出文件名前缀，后接导出时间，避免覆盖之前导出的文件
+const EXPORT_FILE_PREFIX: &str = "miaoyu-replacement-rules";
+const EXPORT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplacementMatch {
+    /// 按原文匹配；两端为英文字母或数字时只匹配完整单词
+    #[default]
+    Literal,
+    /// 正则表达式，替换内容中可用 `$1` 引用分组
+    Regex,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplacementStage {
+    /// 发送给文本模型前执行，结果会交给模型继续润色
+    BeforePolish,
+    /// 润色完成、粘贴前执行，不受模型改写影响
+    #[default]
+    AfterPolish,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub match_type: ReplacementMatch,
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+    /// 忽略英文大小写
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// 适用的记录类型，为空表示全部
+    #[serde(default)]
+    pub scopes: Vec<HistoryKind>,
+    #[serde(default)]
+    pub stage: ReplacementStage,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ReplacementRule {
+    fn compile(&self) -> Result<Regex, String> {
+        let mut pattern = match self.match_type {
+            ReplacementMatch::Literal => {
+                let is_word = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+                // 只用 ASCII 单词边界，汉字与英文相邻时仍能匹配
+                format!(
+                    "{}{}{}",
+                    if is_word(self.pattern.chars().next()) {
+                        r"(?-u:\b)"
+                    } else {
+                        ""
+                    },
+                    regex::escape(&self.pattern),
+                    if is_word(self.pattern.chars().last()) {
+                        r"(?-u:\b)"
+                    } else {
+                        ""
+                    },
+                )
+            }
+            ReplacementMatch::Regex => self.pattern.clone(),
+        };
+        if self.case_insensitive {
+            pattern.insert_str(0, "(?i)");
+        }
+        Regex::new(&pattern).map_err(|e| format!("替换规则“{}”无效: {e}", self.pattern))
+    }
+
+    fn applies_to(&self, kind: Option<HistoryKind>, stage: ReplacementStage) -> bool {
+        self.enabled
+            && self.stage == stage
+            && (self.scopes.is_empty() || kind.is_none_or(|kind| self.scopes.contains(&kind)))
+    }
+}
+
+/// 导入导出使用的文件格式
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplacementRulesFile {
+    version: u32,
+    rules: Vec<ReplacementRule>,
+}
+
+/// 替换结果与实际生效的规则 ID
+#[derive(Serialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementPreview {
+    pub text: String,
+    pub applied_rule_ids: Vec<String>,
+}
+
+/// 按顺序执行该阶段适用的规则；`kind` 为空时不限制记录类型
+fn run_rules(
+    rules: &[ReplacementRule],
+    kind: Option<HistoryKind>,
+    stage: ReplacementStage,
+    text: &str,
+) -> ReplacementPreview {
+    let mut result = text.to_string();
+    let mut applied_rule_ids = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.applies_to(kind, stage)) {
+        let regex = match rule.compile() {
+            Ok(regex) => regex,
+            Err(error) => {
+                warn!(target = "miaoyu_replacements", error = %error, "跳过无效的替换规则");
+                continue;
+            }
+        };
+        if !regex.is_match(&result) {
+            continue;
+        }
+        result = match rule.match_type {
+            ReplacementMatch::Literal => regex
+                .replace_all(&result, NoExpand(&rule.replacement))
+                .into_owned(),
+            ReplacementMatch::Regex => regex
+                .replace_all(&result, rule.replacement.as_str())
+                .into_owned(),
+        };
+        applied_rule_ids.push(rule.id.clone());
+    }
+    ReplacementPreview {
+        text: result,
+        applied_rule_ids,
+    }
+}
+
+/// 在听写流程中执行指定阶段的规则
+pub fn apply(
+    app: &AppHandle<Wry>,
+    kind: HistoryKind,
+    stage: ReplacementStage,
+    text: &str,
+) -> String {
+    let rules = replacement_rules(app);
+    if rules.is_empty() || text.trim().is_empty() {
+        return text.to_string();
+    }
+    run_rules(&rules, Some(kind), stage, text).text
+}
+
+fn replacement_rules(app: &AppHandle<Wry>) -> Vec<ReplacementRule> {
+    SettingsStore::load(app).replacement_rules
+}
+
+/// 去掉空规则、补全缺失或重复的 ID，并检查正则是否有效
+fn normalize(rules: Vec<ReplacementRule>) -> Result<Vec<ReplacementRule>, String> {
+    let mut normalized: Vec<ReplacementRule> = Vec::new();
+    for mut rule in rules {
+        if rule.pattern.is_empty() {
+            continue;
+        }
+        rule.compile()?;
+        rule.id = rule.id.trim().to_string();
+        if rule.id.is_empty() || normalized.iter().any(|existing| existing.id == rule.id) {
+            rule.id = Uuid::new_v4().to_string();
+        }
+        let mut scopes = Vec::new();
+        for kind in rule.scopes {
+            if !scopes.contains(&kind) {
+                scopes.push(kind);
+            }
+        }
+        rule.scopes = scopes;
+        normalized.push(rule);
+    }
+    Ok(normalized)
+}
+
+fn save_rules(
+    app: &AppHandle,
+    rules: Vec<ReplacementRule>,
+) -> Result<Vec<ReplacementRule>, String> {
+    let rules = normalize(rules)?;
+    SettingsStore::update(app, |settings| settings.replacement_rules = rules.clone())?;
+    Ok(rules)
+}
+
+/// 保存替换规则，列表顺序即执行顺序
+#[tauri::command]
+#[specta::specta]
+pub fn set_replacement_rules(
+    app: AppHandle,
+    rules: Vec<ReplacementRule>,
+) -> Result<Vec<ReplacementRule>, String> {
+    save_rules(&app, rules)
+}
+
+/// 用示例文本测试规则，依次执行润色前与润色后的规则；`kind` 为空时忽略适用范围
+#[tauri::command]
+#[specta::specta]
+pub fn preview_replacement_rules(
+    rules: Vec<ReplacementRule>,
+    text: String,
+    kind: Option<HistoryKind>,
+) -> Result<ReplacementPreview, String> {
+    let rules = normalize(rules)?;
+    let before = run_rules(&rules, kind, ReplacementStage::BeforePolish, &text);
+    let after = run_rules(&rules, kind, ReplacementStage::AfterPolish, &before.text);
+    Ok(ReplacementPreview {
+        text: after.text,
+        applied_rule_ids: [before.applied_rule_ids, after.applied_rule_ids].concat(),
+    })
+}
+
+/// 把替换规则导出为 JSON 文件保存到下载目录，返回文件路径
+#[tauri::command]
+#[specta::specta]
+pub async fn export_replacement_rules(app: AppHandle) -> Result<String, String> {
+    let file = ReplacementRulesFile {
+        version: EXPORT_VERSION,
+        rules: replacement_rules(&app),
+    };
+    if file.rules.is_empty() {
+        return Err("没有可导出的替换规则".to_string());
+    }
+    let content =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("序列化替换规则失败: {e}"))?;
+    let path = app
+        .path()
+        .download_dir()
+        .map_err(|e| format!("无法获取下载目录: {e}"))?
+        .join(format!(
+            "{EXPORT_FILE_PREFIX}-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("写入替换规则文件失败: {e}"))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 从 JSON 导入替换规则，`replaceExisting` 为真时覆盖现有规则，否则追加到末尾
+#[tauri::command]
+#[specta::specta]
+pub fn import_replacement_rules(
+    app: AppHandle,
+    json: String,
+    replace_existing: bool,
+) -> Result<Vec<ReplacementRule>, String> {
+    // 同时接受导出文件与单纯的规则数组
+    let imported = match serde_json::from_str::<ReplacementRulesFile>(&json) {
+        Ok(file) => file.rules,
+        Err(_) => serde_json::from_str::<Vec<ReplacementRule>>(&json)
+            .map_err(|e| format!("无法解析替换规则: {e}"))?,
+    };
+    if imported.is_empty() {
+        return Err("文件中没有替换规则".to_string());
+    }
+    let rules = if replace_existing {
+        imported
+    } else {
+        let mut rules = replacement_rules(&app);
+        rules.extend(imported);
+        rules
+    };
+    save_rules(&app, rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, pattern: &str, replacement: &str) -> ReplacementRule {
+        ReplacementRule {
+            id: id.to_string(),
+            enabled: true,
+            match_type: ReplacementMatch::Literal,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            case_insensitive: false,
+            scopes: Vec::new(),
+            stage: ReplacementStage::AfterPolish,
+        }
+    }
+
+    fn run(rules: &[ReplacementRule], text: &str) -> String {
+        run_rules(
+            rules,
+            Some(HistoryKind::Dictation),
+            ReplacementStage::AfterPolish,
+            text,
+        )
+        .text
+    }
+
+    #[test]
+    fn literal_words_match_ascii_boundaries_only() {
+        let rules = [ReplacementRule {
+            case_insensitive: true,
+            ..rule("1", "github", "GitHub")
+        }];
+        assert_eq!(
+            run(&rules, "用github提交，Github和githubber"),
+            "用GitHub提交，GitHub和githubber"
+        );
+        // 两端不是英文时不加边界
+        let rules = [rule("1", "微信", "WeChat")];
+        assert_eq!(run(&rules, "发微信给他"), "发WeChat给他");
+    }
+
+    #[test]
+    fn literal_replacements_are_not_expanded() {
+        let rules = [rule("1", "价格", "$1 元")];
+        assert_eq!(run(&rules, "价格"), "$1 元");
+        let rules = [ReplacementRule {
+            match_type: ReplacementMatch::Regex,
+            ..rule("1", r"(\d+)块", "$1 元")
+        }];
+        assert_eq!(run(&rules, "30块"), "30 元");
+    }
+
+    #[test]
+    fn rules_respect_scope_stage_and_order() {
+        let rules = [
+            ReplacementRule {
+                scopes: vec![HistoryKind::Diary],
+                ..rule("diary", "今天", "今日")
+            },
+            ReplacementRule {
+                stage: ReplacementStage::BeforePolish,
+                ..rule("before", "嗯", "")
+            },
+            rule("first", "aaa", "bbb"),
+            rule("second", "bbb", "ccc"),
+            ReplacementRule {
+                enabled: false,
+                ..rule("disabled", "ccc", "ddd")
+            },
+        ];
+        let preview = run_rules(
+            &rules,
+            Some(HistoryKind::Dictation),
+            ReplacementStage::AfterPolish,
+            "嗯今天 aaa",
+        );
+        assert_eq!(preview.text, "嗯今天 ccc");
+        assert_eq!(preview.applied_rule_ids, vec!["first", "second"]);
+
+        let preview = run_rules(&rules, None, ReplacementStage::BeforePolish, "嗯今天");
+        assert_eq!(preview.text, "今天");
+        assert_eq!(preview.applied_rule_ids, vec!["before"]);
+        // 不限记录类型时范围规则也会执行
+        let preview = run_rules(&rules, None, ReplacementStage::AfterPolish, "今天");
+        assert_eq!(preview.applied_rule_ids, vec!["diary"]);
+    }
+
+    #[test]
+    fn normalize_fills_ids_and_rejects_invalid_patterns() {
+        let rules = normalize(vec![
+            rule("same", "a", "b"),
+            rule(" same ", "c", "d"),
+            rule("", "e", "f"),
+            rule("empty", "", "x"),
+            ReplacementRule {
+                scopes: vec![HistoryKind::Diary, HistoryKind::Diary],
+                ..rule("scoped", "g", "h")
+            },
+        ])
+        .unwrap();
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].id, "same");
+        assert!(!rules[1].id.is_empty() && rules[1].id != "same");
+        assert!(!rules[2].id.is_empty() && rules[2].id != rules[1].id);
+        assert_eq!(rules[3].scopes, vec![HistoryKind::Diary]);
+
+        let invalid = ReplacementRule {
+            match_type: ReplacementMatch::Regex,
+            ..rule("bad", "(", "")
+        };
+        assert!(normalize(vec![invalid]).is_err());
+    }
+}
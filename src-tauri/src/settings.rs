@@ -6,28 +6,60 @@ use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_store::StoreExt;
 use tracing::error;
 
-use crate::{llm::DEFAULT_SYSTEM_PROMPT, tray, windows::ShowAppWindow};
+use crate::{
+    chinese_conversion::ChineseConversionSettings, context::ConversationContextSettings,
+    pii::PiiMaskingSettings, replacements::ReplacementRule, translation::TranslationSettings, tray,
+    typography::TypographySettings, voice_commands::VoiceCommandSettings, windows::ShowAppWindow,
+};
 
 #[derive(Serialize, Deserialize, Type, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingsStore {
     #[serde(default)]
     pub theme: AppTheme,
+    /// 已迁移到提示词模板，仅用于读取旧版本数据
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub llm_system_prompt: Option<String>,
     #[serde(default)]
     pub autostart_enabled: bool,
     #[serde(default)]
     pub onboarding_completed: bool,
+    /// 调用云端文本模型前的隐私信息脱敏
+    #[serde(default)]
+    pub pii_masking: PiiMaskingSettings,
+    #[serde(default)]
+    pub translation: TranslationSettings,
+    /// 润色时附带同一应用中最近的记录作为上下文
+    #[serde(default)]
+    pub conversation_context: ConversationContextSettings,
+    /// 识别结果中的口述格式指令
+    #[serde(default)]
+    pub voice_commands: VoiceCommandSettings,
+    /// 文本替换规则，按顺序执行
+    #[serde(default)]
+    pub replacement_rules: Vec<ReplacementRule>,
+    /// 粘贴前的中文排版规范化
+    #[serde(default)]
+    pub typography: TypographySettings,
+    /// 输出文本的简繁转换
+    #[serde(default)]
+    pub chinese_conversion: ChineseConversionSettings,
 }
 
 impl Default for SettingsStore {
     fn default() -> Self {
         Self {
             theme: AppTheme::System,
-            llm_system_prompt: Some(DEFAULT_SYSTEM_PROMPT.to_string()),
+            llm_system_prompt: None,
             autostart_enabled: false,
             onboarding_completed: false,
+            pii_masking: PiiMaskingSettings::default(),
+            translation: TranslationSettings::default(),
+            conversation_context: ConversationContextSettings::default(),
+            voice_commands: VoiceCommandSettings::default(),
+            replacement_rules: Vec::new(),
+            typography: TypographySettings::default(),
+            chinese_conversion: ChineseConversionSettings::default(),
         }
     }
 }
@@ -52,7 +84,23 @@ impl SettingsStore {
         }
     }
 
-    fn save(&self, app: &AppHandle) -> Result<(), String> {
+    /// 读取设置，尚未保存或无法解析时使用默认值
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        Self::get(app).ok().flatten().unwrap_or_default()
+    }
+
+    /// 读取设置、交给 `change` 修改后保存，返回 `change` 的结果
+    pub(crate) fn update<T>(
+        app: &AppHandle,
+        change: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, String> {
+        let mut settings = Self::load(app);
+        let result = change(&mut settings);
+        settings.save(app)?;
+        Ok(result)
+    }
+
+    pub(crate) fn save(&self, app: &AppHandle) -> Result<(), String> {
         let store = match app.store("store") {
             Ok(store) => store,
             Err(_) => return Err("Store not found".to_string()),
@@ -79,9 +127,7 @@ pub fn init(app: &AppHandle) {
 #[tauri::command]
 #[specta::specta]
 pub fn set_onboarding_completed(app: AppHandle, completed: bool) -> Result<bool, String> {
-    let mut settings = SettingsStore::get(&app).ok().flatten().unwrap_or_default();
-    settings.onboarding_completed = completed;
-    settings.save(&app)?;
+    SettingsStore::update(&app, |settings| settings.onboarding_completed = completed)?;
     if completed {
         if app.tray_by_id("tray").is_none() {
             let _ = tray::create_tray(&app);
@@ -91,15 +137,11 @@ pub fn set_onboarding_completed(app: AppHandle, completed: bool) -> Result<bool,
             let _ = ShowAppWindow::Dashboard.show(&app_handle).await;
         });
     }
-    Ok(settings.onboarding_completed)
+    Ok(completed)
 }
 
 pub fn is_onboarding_completed(app: &AppHandle<Wry>) -> bool {
-    SettingsStore::get(app)
-        .ok()
-        .flatten()
-        .unwrap_or_default()
-        .onboarding_completed
+    SettingsStore::load(app).onboarding_completed
 }
 
 /// 获取开机自启动状态
@@ -129,9 +171,7 @@ pub fn set_autostart_enabled(app: AppHandle, enabled: bool) -> Result<(), String
     }
 
     // 保存到设置
-    let mut settings = SettingsStore::get(&app).ok().flatten().unwrap_or_default();
-    settings.autostart_enabled = enabled;
-    settings.save(&app)?;
+    SettingsStore::update(&app, |settings| settings.autostart_enabled = enabled)?;
 
     Ok(())
 }
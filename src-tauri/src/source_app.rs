@@ -0,0 +1,19 @@
+/// 录音开始时位于前台的应用名称，用于历史记录与提示词中的 `{source_app}`
+#[cfg(target_os = "macos")]
+pub fn frontmost_app_name() -> Option<String> {
+    use objc2_app_kit::NSWorkspace;
+
+    #[allow(unused_unsafe)]
+    let name = unsafe {
+        NSWorkspace::sharedWorkspace()
+            .frontmostApplication()?
+            .localizedName()?
+    };
+    let name = name.to_string();
+    (!name.trim().is_empty()).then_some(name)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn frontmost_app_name() -> Option<String> {
+    None
+}
@@ -0,0 +1,1068 @@
+//! 待办与日程：从语音日记中提取待办事项和带日期的安排，保存在历史数据库的 `tasks` 表，
+//! 可标记完成并导出为 iCalendar 文件。已配置文本模型时由模型提取，否则使用离线日期规则
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_specta::Event;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    history::{self, HistoryEntry},
+    llm::{self, LLMService},
+    models,
+    notification::{self, NotificationType},
+    prompts::PromptTemplate,
+};
+
+const TASKS_TEMPLATE_ID: &str = "extract-tasks";
+const TASKS_SYSTEM_PROMPT: &str = "你是一个日程助手。请从用户的文本中找出尚未完成的待办事项和带时间的安排：
+1. title 为简短的动宾短语，不超过 20 个字，不包含日期和时间
+2. due 为事项的时间，有具体时间时写成 YYYY-MM-DD HH:MM，只有日期时写成 YYYY-MM-DD，没有提到时间时为 null
+3. 相对日期（明天、下周三等）以下面给出的记录时间为准换算
+4. 已经完成的事情和单纯的感想不要提取
+5. 只输出 JSON，格式为 {\"tasks\": [{\"title\": \"事项\", \"due\": \"2024-01-01 15:00\"}]}，没有待办时输出 {\"tasks\": []}";
+/// 发送给模型的正文上限
+const MAX_INPUT_CHARS: usize = 4000;
+const MAX_TITLE_CHARS: usize = 40;
+/// 导出日程时带具体时间的事项默认持续时长
+const EVENT_DURATION_MINUTES: i64 = 60;
+const ICS_FILE_NAME: &str = "miaoyu-tasks.ics";
+const WEEKDAYS: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+
+/// 中文数字或阿拉伯数字
+const NUMBER: &str = r"\d{1,2}|[零一二两三四五六七八九十]{1,3}";
+
+static RELATIVE_DAY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"大后天|后天|明天|明日|明早|明晚|今天|今日|今早|今晚").expect("相对日期正则无效")
+});
+static WEEKDAY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(下下个?|下个?|这个?|本)?(?:周|星期|礼拜)([一二三四五六日天])")
+        .expect("星期正则无效")
+});
+static MONTH_DAY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(?:(\d{{4}})年)?({NUMBER})月({NUMBER})[日号]")).expect("月日正则无效")
+});
+/// 没有月份时只认“号”，“一日三餐”“一日游”里的“日”不是日期
+static DAY_ONLY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"({NUMBER})号")).expect("日期正则无效"));
+static TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(早上|早晨|上午|中午|下午|傍晚|晚上|凌晨)?({NUMBER})(?:点(钟)?|时|[:：](\d{{2}}))(?:(半)|(一刻)|(三刻)|({NUMBER})分?)?"
+    ))
+    .expect("时间正则无效")
+});
+static TODO_KEYWORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"记得|别忘了|不要忘了|别忘记|提醒我|待办|要去|得去|需要|必须|截止|计划|打算")
+        .expect("待办关键词正则无效")
+});
+/// 只有日期或时间的句子还需要包含一个动作，“今天天气很好”不是待办
+static ACTION_VERB: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"开会|会议|见面|面试|考试|上课|上班|提交|交|寄|买|取|付|缴|还|打电话|联系|约|拜访|参加|出发|出差|去|接|送|订|预约|报名|准备|完成|写|改|整理|处理|检查|复习|办|签|汇报|讨论|安排|吃饭|聚餐|看医生|体检",
+    )
+    .expect("动作正则无效")
+});
+/// 去掉日期、时间时留下的占位符，用于识别紧跟其后的“前”“之前”
+const DATE_MARK: &str = "\u{0}";
+/// 去掉日期、时间后标题开头残留的词
+const TITLE_PREFIXES: [&str; 14] = [
+    "提醒我",
+    "记得",
+    "别忘了",
+    "不要忘了",
+    "别忘记",
+    "之前",
+    "以前",
+    "我要",
+    "我得",
+    "我们",
+    "我",
+    "要",
+    "得",
+    "在",
+];
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskItem {
+    pub id: String,
+    /// 提取来源的历史记录，记录删除时一并删除
+    pub entry_id: Option<String>,
+    pub title: String,
+    /// 截止或发生时间（RFC 3339），为空表示没有日期的待办
+    pub due_at: Option<String>,
+    /// 只有日期、没有具体时间
+    pub all_day: bool,
+    pub done: bool,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskListFilter {
+    /// 是否包含已完成的事项
+    #[serde(default)]
+    pub include_done: bool,
+}
+
+/// 后台提取到新的待办后通知前端刷新
+#[derive(Serialize, Type, tauri_specta::Event, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnTasksExtracted {
+    pub entry_id: String,
+    pub count: u32,
+}
+
+/// 提取出的事项时间
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Due {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ExtractedTask {
+    title: String,
+    due: Option<Due>,
+}
+
+#[derive(Deserialize)]
+struct TasksResponse {
+    #[serde(default)]
+    tasks: Vec<TaskResponseItem>,
+}
+
+#[derive(Deserialize)]
+struct TaskResponseItem {
+    title: String,
+    #[serde(default)]
+    due: Option<String>,
+}
+
+pub(crate) fn init_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS tasks (
+            id TEXT PRIMARY KEY,
+            entry_id TEXT,
+            title TEXT NOT NULL,
+            due_at TEXT,
+            all_day INTEGER NOT NULL DEFAULT 0,
+            done INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            completed_at TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tasks_entry_id ON tasks(entry_id);
+        "#,
+    )
+    .map_err(|e| format!("初始化待办表失败: {e}"))
+}
+
+pub(crate) fn delete_for_entry(conn: &Connection, entry_id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM tasks WHERE entry_id = ?1", params![entry_id])
+        .map_err(|e| format!("删除关联待办失败: {e}"))?;
+    Ok(())
+}
+
+pub(crate) fn delete_all(conn: &Connection) -> Result<(), String> {
+    conn.execute("DELETE FROM tasks", [])
+        .map_err(|e| format!("清空待办失败: {e}"))?;
+    Ok(())
+}
+
+fn map_task_row(row: &Row<'_>) -> rusqlite::Result<TaskItem> {
+    Ok(TaskItem {
+        id: row.get("id")?,
+        entry_id: row.get("entry_id")?,
+        title: row.get("title")?,
+        due_at: row.get("due_at")?,
+        all_day: row.get::<_, i64>("all_day")? != 0,
+        done: row.get::<_, i64>("done")? != 0,
+        created_at: row.get("created_at")?,
+        completed_at: row.get("completed_at")?,
+    })
+}
+
+fn query_tasks(conn: &Connection, filter: &TaskListFilter) -> Result<Vec<TaskItem>, String> {
+    // 未完成的在前；有日期的按时间先后，没有日期的排在最后
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE ?1 OR done = 0
+            ORDER BY done ASC, due_at IS NULL ASC, due_at ASC, created_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![filter.include_done], map_task_row)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 用新的提取结果替换该记录下尚未完成的事项，与已完成事项同名的不再重复添加
+fn replace_entry_tasks(
+    conn: &Connection,
+    entry_id: &str,
+    tasks: &[ExtractedTask],
+) -> Result<Vec<TaskItem>, String> {
+    conn.execute(
+        "DELETE FROM tasks WHERE entry_id = ?1 AND done = 0",
+        params![entry_id],
+    )
+    .map_err(|e| format!("删除旧待办失败: {e}"))?;
+    let mut stmt = conn
+        .prepare("SELECT title FROM tasks WHERE entry_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let completed = stmt
+        .query_map(params![entry_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let created_at = Utc::now().to_rfc3339();
+    let mut saved = Vec::new();
+    for task in tasks {
+        if completed.contains(&task.title) {
+            continue;
+        }
+        let (due_at, all_day) = match task.due {
+            Some(due) => (Some(due_to_rfc3339(due)), matches!(due, Due::Date(_))),
+            None => (None, false),
+        };
+        let item = TaskItem {
+            id: Uuid::new_v4().to_string(),
+            entry_id: Some(entry_id.to_string()),
+            title: task.title.clone(),
+            due_at,
+            all_day,
+            done: false,
+            created_at: created_at.clone(),
+            completed_at: None,
+        };
+        conn.execute(
+            "INSERT INTO tasks (id, entry_id, title, due_at, all_day, done, created_at, completed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, NULL)",
+            params![
+                item.id,
+                item.entry_id,
+                item.title,
+                item.due_at,
+                item.all_day,
+                item.created_at
+            ],
+        )
+        .map_err(|e| format!("保存待办失败: {e}"))?;
+        saved.push(item);
+    }
+    Ok(saved)
+}
+
+fn update_done(conn: &Connection, id: &str, done: bool) -> Result<TaskItem, String> {
+    let completed_at = done.then(|| Utc::now().to_rfc3339());
+    let changed = conn
+        .execute(
+            "UPDATE tasks SET done = ?2, completed_at = ?3 WHERE id = ?1",
+            params![id, done, completed_at],
+        )
+        .map_err(|e| format!("更新待办失败: {e}"))?;
+    if changed == 0 {
+        return Err("待办事项不存在".to_string());
+    }
+    conn.query_row(
+        "SELECT * FROM tasks WHERE id = ?1",
+        params![id],
+        map_task_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "待办事项不存在".to_string())
+}
+
+fn due_to_rfc3339(due: Due) -> String {
+    let local = match due {
+        Due::Date(date) => date.and_time(NaiveTime::MIN),
+        Due::DateTime(time) => time,
+    };
+    // 夏令时切换造成的不存在时间退回按 UTC 理解
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local).to_rfc3339())
+}
+
+/// 在后台提取新保存日记中的待办，提取到事项时通知用户；失败只记录日志
+pub fn spawn(app: &AppHandle<Wry>, entry: HistoryEntry) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match extract_and_save(&app, &entry).await {
+            Ok(tasks) if !tasks.is_empty() => {
+                let count = tasks.len() as u32;
+                OnTasksExtracted {
+                    entry_id: entry.id,
+                    count,
+                }
+                .emit(&app)
+                .ok();
+                let _ = notification::show_notification(
+                    app,
+                    format!("已从日记中提取 {count} 条待办"),
+                    NotificationType::Info,
+                    None,
+                )
+                .await;
+            }
+            Ok(_) => {}
+            Err(error) => warn!(
+                target = "miaoyu_tasks",
+                error = %error,
+                "提取待办失败"
+            ),
+        }
+    });
+}
+
+async fn extract_and_save(
+    app: &AppHandle<Wry>,
+    entry: &HistoryEntry,
+) -> Result<Vec<TaskItem>, String> {
+    // 相对日期以记录时间为准，重新提取旧日记时“明天”仍指记录的次日
+    let reference = DateTime::parse_from_rfc3339(&entry.created_at)
+        .map(|time| time.with_timezone(&Local).naive_local())
+        .unwrap_or_else(|_| Local::now().naive_local());
+    let tasks = extract(app, &entry.text, reference).await;
+    let entry_id = entry.id.clone();
+    history::with_connection(app.clone(), move |conn| {
+        replace_entry_tasks(&conn, &entry_id, &tasks)
+    })
+    .await
+}
+
+async fn extract(app: &AppHandle<Wry>, text: &str, reference: NaiveDateTime) -> Vec<ExtractedTask> {
+    if llm::has_configured_api_key(app) {
+        match llm_extract(app, text, reference).await {
+            Ok(tasks) => return tasks,
+            Err(error) => warn!(
+                target = "miaoyu_tasks",
+                error = %error,
+                "文本模型提取待办失败，改用日期规则"
+            ),
+        }
+    }
+    rule_extract(text, reference)
+}
+
+async fn llm_extract(
+    app: &AppHandle<Wry>,
+    text: &str,
+    reference: NaiveDateTime,
+) -> Result<Vec<ExtractedTask>, String> {
+    let template = PromptTemplate {
+        id: TASKS_TEMPLATE_ID.to_string(),
+        name: "提取待办".to_string(),
+        system_prompt: format!(
+            "{TASKS_SYSTEM_PROMPT}\n记录时间：{} {}",
+            reference.format("%Y-%m-%d %H:%M"),
+            WEEKDAYS[reference.weekday().num_days_from_monday() as usize]
+        ),
+        temperature: Some(0.0),
+        model_override: None,
+        builtin: true,
+        customized: false,
+        allow_rewrite: true,
+    };
+    let input: String = text.chars().take(MAX_INPUT_CHARS).collect();
+    let result = LLMService::complete(app, &input, &template)
+        .await
+        .map_err(|error| error.to_string())?;
+    if let (Some(variant_id), Some(tokens)) = (result.variant_id.as_deref(), result.total_tokens) {
+        if let Err(error) = models::record_llm_usage(app, variant_id, tokens) {
+            warn!(
+                target = "miaoyu_tasks",
+                error = %error,
+                "记录文本模型使用统计失败"
+            );
+        }
+    }
+
+    let json = match (result.text.find('{'), result.text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &result.text[start..=end],
+        _ => return Err("文本模型未返回 JSON".to_string()),
+    };
+    let response: TasksResponse =
+        serde_json::from_str(json).map_err(|e| format!("解析待办失败: {e}"))?;
+    let mut tasks = Vec::new();
+    for item in response.tasks {
+        let title = clean_title(&item.title);
+        if title.is_empty() {
+            continue;
+        }
+        let due = item.due.as_deref().and_then(parse_due);
+        push_unique(&mut tasks, ExtractedTask { title, due });
+    }
+    Ok(tasks)
+}
+
+fn parse_due(value: &str) -> Option<Due> {
+    let value = value.trim();
+    for format in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Due::DateTime(time));
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(Due::Date)
+}
+
+fn clean_title(title: &str) -> String {
+    let title = title
+        .trim()
+        .trim_matches(|c: char| "\"'“”‘’《》「」".contains(c))
+        .trim_end_matches(|c: char| "。，、；：！？.,;:!?".contains(c))
+        .trim();
+    title.chars().take(MAX_TITLE_CHARS).collect()
+}
+
+fn push_unique(tasks: &mut Vec<ExtractedTask>, task: ExtractedTask) {
+    if !tasks.iter().any(|existing| existing.title == task.title) {
+        tasks.push(task);
+    }
+}
+
+/// 离线规则：含日期、时间或待办关键词的句子作为一条事项
+fn rule_extract(text: &str, reference: NaiveDateTime) -> Vec<ExtractedTask> {
+    let mut tasks = Vec::new();
+    for sentence in text
+        .split(['。', '！', '？', '!', '?', '\n', '；', ';'])
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+    {
+        let keyword = TODO_KEYWORD.is_match(sentence);
+        // 已经发生的事情不是待办
+        if !keyword && (sentence.replace("了解", "").contains('了') || sentence.contains("已经"))
+        {
+            continue;
+        }
+        let date = find_date(sentence, reference.date());
+        let time = find_time(sentence);
+        let dated_action = (date.is_some() || time.is_some()) && ACTION_VERB.is_match(sentence);
+        if !keyword && !dated_action {
+            continue;
+        }
+        let due = match (date, time) {
+            (Some(date), Some((time, next_day))) => Some(Due::DateTime(
+                date.and_time(time) + Duration::days(i64::from(next_day)),
+            )),
+            (Some(date), None) => Some(Due::Date(date)),
+            // 只说了时间：今天该时间已过则视为明天
+            (None, Some((time, next_day))) => {
+                let today = reference.date().and_time(time) + Duration::days(i64::from(next_day));
+                Some(Due::DateTime(if today < reference {
+                    today + Duration::days(1)
+                } else {
+                    today
+                }))
+            }
+            (None, None) => None,
+        };
+        if matches!(due, Some(Due::Date(date)) if date < reference.date()) {
+            continue;
+        }
+        let title = rule_title(sentence);
+        if title.chars().count() < 2 {
+            continue;
+        }
+        push_unique(&mut tasks, ExtractedTask { title, due });
+    }
+    tasks
+}
+
+fn rule_title(sentence: &str) -> String {
+    let mut title = sentence.to_string();
+    for pattern in [&*MONTH_DAY, &*TIME, &*WEEKDAY, &*RELATIVE_DAY, &*DAY_ONLY] {
+        title = pattern.replace_all(&title, DATE_MARK).into_owned();
+    }
+    // “晚上12点前提交”去掉时间后会留下“前提交”
+    let title: String = title
+        .split(DATE_MARK)
+        .enumerate()
+        .map(|(index, part)| {
+            if index == 0 {
+                return part;
+            }
+            ["之前", "以前"]
+                .iter()
+                .find_map(|suffix| part.strip_prefix(suffix))
+                .or_else(|| {
+                    part.strip_prefix('前')
+                        .filter(|rest| !rest.starts_with('往'))
+                })
+                .unwrap_or(part)
+        })
+        .collect();
+    let mut rest = title.trim_matches(|c: char| c.is_whitespace() || "，,、".contains(c));
+    while let Some(stripped) = TITLE_PREFIXES
+        .iter()
+        .find_map(|prefix| rest.strip_prefix(prefix))
+    {
+        rest = stripped.trim_start_matches(|c: char| c.is_whitespace() || "，,、".contains(c));
+    }
+    clean_title(rest)
+}
+
+/// 解析“三”“十二”“两”“15”等数字
+fn parse_number(value: &str) -> Option<u32> {
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+    let digit = |c: char| match c {
+        '零' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    };
+    let chars: Vec<char> = value.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        [unit] => digit(*unit),
+        ['十', unit] => Some(10 + digit(*unit)?),
+        [tens, '十'] => Some(digit(*tens)? * 10),
+        [tens, '十', unit] => Some(digit(*tens)? * 10 + digit(*unit)?),
+        _ => None,
+    }
+}
+
+fn find_date(sentence: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Some(found) = RELATIVE_DAY.find(sentence) {
+        let days = match found.as_str() {
+            "大后天" => 3,
+            "后天" => 2,
+            "明天" | "明日" | "明早" | "明晚" => 1,
+            _ => 0,
+        };
+        return Some(today + Duration::days(days));
+    }
+    if let Some(captures) = WEEKDAY.captures(sentence) {
+        let target = match &captures[2] {
+            "一" => 0,
+            "二" => 1,
+            "三" => 2,
+            "四" => 3,
+            "五" => 4,
+            "六" => 5,
+            _ => 6,
+        };
+        let current = today.weekday().num_days_from_monday() as i64;
+        let monday = today - Duration::days(current);
+        let date = match captures.get(1).map(|prefix| prefix.as_str()) {
+            // 没有前缀时指最近的一个，今天本身也算
+            None => today + Duration::days((target - current).rem_euclid(7)),
+            Some(prefix) if prefix.starts_with("下下") => monday + Duration::days(14 + target),
+            Some(prefix) if prefix.starts_with('下') => monday + Duration::days(7 + target),
+            Some(_) => monday + Duration::days(target),
+        };
+        return Some(date);
+    }
+    if let Some(captures) = MONTH_DAY.captures(sentence) {
+        let month = parse_number(&captures[2])?;
+        let day = parse_number(&captures[3])?;
+        if let Some(year) = captures.get(1) {
+            return NaiveDate::from_ymd_opt(year.as_str().parse().ok()?, month, day);
+        }
+        // 没有年份时取最近的将来，已过的日期视为明年
+        let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+        return if date < today {
+            NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+        } else {
+            Some(date)
+        };
+    }
+    let day = DAY_ONLY
+        .captures_iter(sentence)
+        .find_map(|captures| parse_number(&captures[1]))?;
+    let date = NaiveDate::from_ymd_opt(today.year(), today.month(), day)?;
+    if date >= today {
+        return Some(date);
+    }
+    let (year, month) = if today.month() == 12 {
+        (today.year() + 1, 1)
+    } else {
+        (today.year(), today.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn find_time(sentence: &str) -> Option<(NaiveTime, bool)> {
+    TIME.captures_iter(sentence)
+        .find_map(|captures| parse_time(&captures))
+}
+
+/// 解析时间；第二项表示时间落在次日零点之后，例如“晚上12点”
+fn parse_time(captures: &Captures<'_>) -> Option<(NaiveTime, bool)> {
+    // “快一点”“有两点想法”里的“一点”“两点”不是时间，需带“钟”、分钟或上下午
+    let bare = (3..=8).all(|group| captures.get(group).is_none());
+    if captures.get(1).is_none() && bare && matches!(&captures[2], "一" | "两") {
+        return None;
+    }
+    let mut hour = parse_number(&captures[2])?;
+    let minute = if let Some(minute) = captures.get(4) {
+        minute.as_str().parse().ok()?
+    } else if captures.get(5).is_some() {
+        30
+    } else if captures.get(6).is_some() {
+        15
+    } else if captures.get(7).is_some() {
+        45
+    } else if let Some(minute) = captures.get(8) {
+        parse_number(minute.as_str())?
+    } else {
+        0
+    };
+    let mut next_day = false;
+    match captures.get(1).map(|period| period.as_str()) {
+        // 晚上12点是当天结束时的零点，凌晨12点是当天开始时的零点
+        Some("晚上") if hour == 12 => {
+            hour = 0;
+            next_day = true;
+        }
+        Some("凌晨") if hour == 12 => hour = 0,
+        Some("下午" | "傍晚" | "晚上") if hour < 12 => hour += 12,
+        Some("中午") if hour < 3 => hour += 12,
+        // 没有说明上下午时，1 到 6 点通常指下午
+        None if captures.get(4).is_none() && (1..=6).contains(&hour) => hour += 12,
+        _ => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|time| (time, next_day))
+}
+
+fn ics_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// 按 RFC 5545 把超过 75 字节的行折叠，续行以空格开头
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+fn ics_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn to_ics(tasks: &[TaskItem], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Miaoyu//Tasks//ZH".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for task in tasks {
+        let due = task
+            .due_at
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+        // 已完成的事项不再占用日程，导出为带截止时间的已完成待办
+        let component = if due.is_some() && !task.done {
+            "VEVENT"
+        } else {
+            "VTODO"
+        };
+        lines.push(format!("BEGIN:{component}"));
+        lines.push(format!("UID:{}@miaoyu", task.id));
+        lines.push(format!("DTSTAMP:{}", ics_utc(now)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&task.title)));
+        match due {
+            Some(due) if task.all_day => {
+                let date = due.with_timezone(&Local).date_naive();
+                if task.done {
+                    lines.push(format!("DUE;VALUE=DATE:{}", date.format("%Y%m%d")));
+                } else {
+                    lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+                    lines.push(format!(
+                        "DTEND;VALUE=DATE:{}",
+                        (date + Duration::days(1)).format("%Y%m%d")
+                    ));
+                }
+            }
+            Some(due) => {
+                let start = due.with_timezone(&Utc);
+                if task.done {
+                    lines.push(format!("DUE:{}", ics_utc(start)));
+                } else {
+                    lines.push(format!("DTSTART:{}", ics_utc(start)));
+                    lines.push(format!(
+                        "DTEND:{}",
+                        ics_utc(start + Duration::minutes(EVENT_DURATION_MINUTES))
+                    ));
+                }
+            }
+            None => {}
+        }
+        if component == "VTODO" {
+            let status = if task.done {
+                "COMPLETED"
+            } else {
+                "NEEDS-ACTION"
+            };
+            lines.push(format!("STATUS:{status}"));
+            if let Some(completed_at) = task
+                .completed_at
+                .as_deref()
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            {
+                lines.push(format!(
+                    "COMPLETED:{}",
+                    ics_utc(completed_at.with_timezone(&Utc))
+                ));
+            }
+        }
+        lines.push(format!("END:{component}"));
+    }
+    lines.push("END:VCALENDAR".to_string());
+    let mut content = lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    content.push_str("\r\n");
+    content
+}
+
+/// 列出待办事项：未完成的在前，按时间先后排序
+#[tauri::command]
+#[specta::specta]
+pub async fn list_tasks(
+    app: AppHandle,
+    filter: Option<TaskListFilter>,
+) -> Result<Vec<TaskItem>, String> {
+    let filter = filter.unwrap_or_default();
+    history::with_connection(app, move |conn| query_tasks(&conn, &filter)).await
+}
+
+/// 标记待办事项完成或未完成
+#[tauri::command]
+#[specta::specta]
+pub async fn set_task_done(app: AppHandle, id: String, done: bool) -> Result<TaskItem, String> {
+    history::with_connection(app, move |conn| update_done(&conn, &id, done)).await
+}
+
+/// 重新提取指定记录中的待办，替换该记录下尚未完成的事项
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_tasks(app: AppHandle, entry_id: String) -> Result<Vec<TaskItem>, String> {
+    let entry = history::get_entry(&app, entry_id)
+        .await?
+        .ok_or_else(|| "历史记录不存在".to_string())?;
+    extract_and_save(&app, &entry).await
+}
+
+/// 把待办导出为 iCalendar 文件保存到下载目录，返回文件路径。
+/// 带日期的事项导出为日程，其余导出为待办
+#[tauri::command]
+#[specta::specta]
+pub async fn export_tasks_ics(app: AppHandle, include_done: bool) -> Result<String, String> {
+    let filter = TaskListFilter { include_done };
+    let tasks =
+        history::with_connection(app.clone(), move |conn| query_tasks(&conn, &filter)).await?;
+    if tasks.is_empty() {
+        return Err("没有可导出的待办".to_string());
+    }
+    let dir = app
+        .path()
+        .download_dir()
+        .map_err(|e| format!("无法获取下载目录: {e}"))?;
+    let path = dir.join(ICS_FILE_NAME);
+    tokio::fs::write(&path, to_ics(&tasks, Utc::now()))
+        .await
+        .map_err(|e| format!("写入日历文件失败: {e}"))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// 2024-03-13 是周三
+    fn reference() -> NaiveDateTime {
+        date(2024, 3, 13).and_time(time(10, 0))
+    }
+
+    fn task(title: &str, due: Option<Due>) -> (String, Option<Due>) {
+        (title.to_string(), due)
+    }
+
+    fn extract(text: &str) -> Vec<(String, Option<Due>)> {
+        rule_extract(text, reference())
+            .into_iter()
+            .map(|task| (task.title, task.due))
+            .collect()
+    }
+
+    #[test]
+    fn parses_chinese_and_arabic_numbers() {
+        for (value, expected) in [
+            ("15", Some(15)),
+            ("三", Some(3)),
+            ("两", Some(2)),
+            ("十", Some(10)),
+            ("十二", Some(12)),
+            ("二十", Some(20)),
+            ("二十五", Some(25)),
+            ("三十一", Some(31)),
+            ("百", None),
+            ("十十", None),
+        ] {
+            assert_eq!(parse_number(value), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn finds_relative_and_calendar_dates() {
+        let today = reference().date();
+        for (sentence, expected) in [
+            ("明天下午三点开会", date(2024, 3, 14)),
+            ("大后天出发", date(2024, 3, 16)),
+            ("今晚吃饭", date(2024, 3, 13)),
+            ("周三交周报", date(2024, 3, 13)),
+            ("周一交周报", date(2024, 3, 18)),
+            ("这周一的会", date(2024, 3, 11)),
+            ("下周三复查", date(2024, 3, 20)),
+            ("下下周日聚餐", date(2024, 3, 31)),
+            ("3月20号交房租", date(2024, 3, 20)),
+            ("2024年3月5日的发票", date(2024, 3, 5)),
+            ("十五号还信用卡", date(2024, 3, 15)),
+            ("5号还信用卡", date(2024, 4, 5)),
+        ] {
+            assert_eq!(find_date(sentence, today), Some(expected), "{sentence}");
+        }
+        assert_eq!(find_date("随时都行", today), None);
+        assert_eq!(find_date("一日三餐要规律", today), None);
+    }
+
+    #[test]
+    fn past_dates_roll_over_to_next_month_or_year() {
+        assert_eq!(
+            find_date("3月5号交房租", reference().date()),
+            Some(date(2025, 3, 5))
+        );
+        let december = date(2024, 12, 20);
+        assert_eq!(find_date("1月3号年会", december), Some(date(2025, 1, 3)));
+        assert_eq!(find_date("5号还信用卡", december), Some(date(2025, 1, 5)));
+        assert_eq!(find_date("12月25号", december), Some(date(2024, 12, 25)));
+    }
+
+    #[test]
+    fn parses_times_of_day() {
+        for (sentence, expected) in [
+            ("明天下午三点开会", (time(15, 0), false)),
+            ("两点半打电话", (time(14, 30), false)),
+            ("上午9:15到", (time(9, 15), false)),
+            ("10点三刻出发", (time(10, 45), false)),
+            ("晚上八点一刻", (time(20, 15), false)),
+            ("中午12点吃饭", (time(12, 0), false)),
+            ("下午3点20分", (time(15, 20), false)),
+            ("早上七点跑步", (time(7, 0), false)),
+            ("晚上12点提交", (time(0, 0), true)),
+            ("晚上12点半睡觉", (time(0, 30), true)),
+            ("凌晨12点发布", (time(0, 0), false)),
+            ("凌晨三点起床", (time(3, 0), false)),
+        ] {
+            assert_eq!(find_time(sentence), Some(expected), "{sentence}");
+        }
+        assert_eq!(find_time("两点钟见"), Some((time(14, 0), false)));
+        assert_eq!(find_time("走快一点"), None);
+        assert_eq!(find_time("我有两点想法"), None);
+    }
+
+    #[test]
+    fn extracts_tasks_with_rules() {
+        assert_eq!(
+            extract("明天下午三点开会。记得买牛奶！今天看了电影。"),
+            vec![
+                task(
+                    "开会",
+                    Some(Due::DateTime(date(2024, 3, 14).and_time(time(15, 0))))
+                ),
+                task("买牛奶", None),
+            ]
+        );
+        assert_eq!(
+            extract("3月5号交房租"),
+            vec![task("交房租", Some(Due::Date(date(2025, 3, 5))))]
+        );
+        // 只说时间且已过时顺延到明天
+        assert_eq!(
+            extract("九点开会"),
+            vec![task(
+                "开会",
+                Some(Due::DateTime(date(2024, 3, 14).and_time(time(9, 0))))
+            )]
+        );
+        // 过去的日期不是待办
+        assert!(extract("2024年3月1日交房租").is_empty());
+    }
+
+    #[test]
+    fn ignores_sentences_that_only_look_like_dates() {
+        for text in [
+            "今天天气很好",
+            "我有两点想法要和大家分享",
+            "一日三餐要规律",
+            "明天的风应该不大",
+        ] {
+            assert!(extract(text).is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn midnight_tonight_is_the_start_of_the_next_day() {
+        assert_eq!(
+            extract("晚上12点提交报告"),
+            vec![task(
+                "提交报告",
+                Some(Due::DateTime(date(2024, 3, 14).and_time(time(0, 0))))
+            )]
+        );
+        assert_eq!(
+            extract("明天晚上12点提交报告"),
+            vec![task(
+                "提交报告",
+                Some(Due::DateTime(date(2024, 3, 15).and_time(time(0, 0))))
+            )]
+        );
+    }
+
+    #[test]
+    fn titles_drop_dates_and_filler_words() {
+        for (sentence, expected) in [
+            ("下周三记得去医院复查", "去医院复查"),
+            ("提醒我明天上午10点给张三打电话", "给张三打电话"),
+            ("3月5号之前我要交房租。", "交房租"),
+            ("明天上午十点半，去机场接人", "去机场接人"),
+            ("今天晚上12点前提交", "提交"),
+            ("下周三之前交报告", "交报告"),
+            ("明天前往机场", "前往机场"),
+        ] {
+            assert_eq!(rule_title(sentence), expected, "{sentence}");
+        }
+    }
+
+    #[test]
+    fn escapes_and_folds_ics_lines() {
+        assert_eq!(ics_escape("a,b;c\\d\r\ne"), r"a\,b\;c\\d\ne");
+
+        let short = "SUMMARY:买牛奶";
+        assert_eq!(fold_line(short), short);
+        let line = format!("SUMMARY:{}", "待办".repeat(30));
+        let folded = fold_line(&line);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        let segments: Vec<&str> = folded.split("\r\n").collect();
+        assert!(segments.len() > 1);
+        assert!(segments.iter().all(|segment| segment.len() <= 75));
+        assert!(segments[1..].iter().all(|segment| segment.starts_with(' ')));
+    }
+
+    #[test]
+    fn exports_events_and_todos() {
+        let item =
+            |id: &str, title: &str, due_at: Option<String>, all_day: bool, done: bool| TaskItem {
+                id: id.to_string(),
+                entry_id: None,
+                title: title.to_string(),
+                due_at,
+                all_day,
+                done,
+                created_at: "2024-03-13T02:00:00Z".to_string(),
+                completed_at: done.then(|| "2024-03-13T08:30:00Z".to_string()),
+            };
+        let day = Local
+            .from_local_datetime(&date(2024, 3, 20).and_time(time(0, 0)))
+            .single()
+            .unwrap()
+            .to_rfc3339();
+        let tasks = [
+            item("open", "买牛奶, 鸡蛋", None, false, false),
+            item(
+                "meeting",
+                "开会",
+                Some("2024-03-14T07:00:00Z".to_string()),
+                false,
+                false,
+            ),
+            item("all-day", "复查", Some(day), true, false),
+            item(
+                "done",
+                "交房租",
+                Some("2024-03-12T07:00:00Z".to_string()),
+                false,
+                true,
+            ),
+        ];
+        let now = Utc.with_ymd_and_hms(2024, 3, 13, 9, 0, 0).unwrap();
+        let ics = to_ics(&tasks, now);
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        let components: Vec<&str> = ics
+            .split("BEGIN:")
+            .skip(2)
+            .map(|component| component.trim_end())
+            .collect();
+        assert_eq!(
+            components,
+            vec![
+                "VTODO\r\nUID:open@miaoyu\r\nDTSTAMP:20240313T090000Z\r\nSUMMARY:买牛奶\\, 鸡蛋\r\n\
+                 STATUS:NEEDS-ACTION\r\nEND:VTODO",
+                "VEVENT\r\nUID:meeting@miaoyu\r\nDTSTAMP:20240313T090000Z\r\nSUMMARY:开会\r\n\
+                 DTSTART:20240314T070000Z\r\nDTEND:20240314T080000Z\r\nEND:VEVENT",
+                "VEVENT\r\nUID:all-day@miaoyu\r\nDTSTAMP:20240313T090000Z\r\nSUMMARY:复查\r\n\
+                 DTSTART;VALUE=DATE:20240320\r\nDTEND;VALUE=DATE:20240321\r\nEND:VEVENT",
+                "VTODO\r\nUID:done@miaoyu\r\nDTSTAMP:20240313T090000Z\r\nSUMMARY:交房租\r\n\
+                 DUE:20240312T070000Z\r\nSTATUS:COMPLETED\r\nCOMPLETED:20240313T083000Z\r\n\
+                 END:VTODO\r\nEND:VCALENDAR",
+            ]
+        );
+    }
+}
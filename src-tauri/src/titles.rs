@@ -0,0 +1,289 @@
+//! 历史记录的标题与标签：保存后在后台生成，用户配置了自己的文本模型时由模型概括，
+//! 否则使用离线关键词规则；后台请求不占用体验额度，也不计入模型使用统计
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+use tauri_specta::Event;
+use tracing::warn;
+
+use crate::{
+    history,
+    llm::{self, LLMService},
+    models,
+    prompts::PromptTemplate,
+};
+
+const TITLE_TEMPLATE_ID: &str = "history-title";
+const TITLE_SYSTEM_PROMPT: &str =
+    "你是一个笔记整理助手。请为用户提供的文本生成一个简短的标题和不超过 3 个标签：
+1. 标题不超过 16 个字，概括主要内容，不要加引号或句末标点
+2. 标签为 2 到 6 个字的名词或短语
+3. 只输出 JSON，格式为 {\"title\": \"标题\", \"tags\": [\"标签\"]}，不要输出任何解释";
+/// 发送给模型的正文上限，长日记只取开头即可概括
+const MAX_INPUT_CHARS: usize = 1500;
+const MAX_TITLE_CHARS: usize = 20;
+/// 离线规则截取首句作为标题时的长度
+const HEURISTIC_TITLE_CHARS: usize = 16;
+const MAX_TAGS: usize = 3;
+const MAX_TAG_CHARS: usize = 12;
+/// 手动补全标题时每次最多处理的条数，其余留到下次
+const BACKFILL_BATCH: u32 = 50;
+/// 不适合作为关键词的常用字，包含其中任意一个的二字词会被跳过
+const STOP_CHARS: &str = "的了是在我你他她它们这那有和就也都要会不没很还个吧呢吗啊呀把被对给说一上下到去来里么什怎为所以而且但然如果可能着过得地之与及或等该此其";
+const STOP_WORDS: [&str; 12] = [
+    "the", "and", "for", "that", "this", "with", "you", "are", "was", "have", "not", "but",
+];
+
+/// 后台任务写回标题与标签后通知前端刷新列表
+#[derive(Serialize, Type, tauri_specta::Event, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnHistoryTitled {
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TitleResponse {
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// 在后台为新保存的记录生成标题与标签，失败只记录日志
+pub fn spawn(app: &AppHandle<Wry>, id: String, text: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = generate_and_save(&app, id, &text).await {
+            warn!(
+                target = "miaoyu_titles",
+                error = %error,
+                "生成历史记录标题失败"
+            );
+        }
+    });
+}
+
+async fn generate_and_save(app: &AppHandle<Wry>, id: String, text: &str) -> Result<(), String> {
+    let (title, tags) = generate(app, text).await;
+    history::set_title_and_tags(app, id.clone(), title.clone(), tags.clone()).await?;
+    OnHistoryTitled { id, title, tags }.emit(app).ok();
+    Ok(())
+}
+
+/// 只在用户配置了自己的密钥、自定义服务或本地服务时调用文本模型，内置体验密钥的额度留给润色
+fn uses_own_llm(app: &AppHandle<Wry>) -> bool {
+    llm::has_configured_api_key(app)
+        && matches!(
+            models::active_llm_entry(app, None, None),
+            Ok(Some(entry)) if !models::uses_trial_quota(&entry)
+        )
+}
+
+async fn generate(app: &AppHandle<Wry>, text: &str) -> (String, Vec<String>) {
+    if uses_own_llm(app) {
+        match llm_title(app, text).await {
+            Ok(result) => return result,
+            Err(error) => warn!(
+                target = "miaoyu_titles",
+                error = %error,
+                "文本模型生成标题失败，改用关键词规则"
+            ),
+        }
+    }
+    keyword_title(text)
+}
+
+async fn llm_title(app: &AppHandle<Wry>, text: &str) -> Result<(String, Vec<String>), String> {
+    let template = PromptTemplate {
+        id: TITLE_TEMPLATE_ID.to_string(),
+        name: "生成标题".to_string(),
+        system_prompt: TITLE_SYSTEM_PROMPT.to_string(),
+        temperature: Some(0.2),
+        model_override: None,
+        builtin: true,
+        customized: false,
+        allow_rewrite: true,
+    };
+    let input: String = text.chars().take(MAX_INPUT_CHARS).collect();
+    let result = LLMService::complete(app, &input, &template)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    // 模型偶尔会用代码块包裹 JSON，只取最外层的花括号
+    let json = match (result.text.find('{'), result.text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &result.text[start..=end],
+        _ => return Err("文本模型未返回 JSON".to_string()),
+    };
+    let response: TitleResponse =
+        serde_json::from_str(json).map_err(|e| format!("解析标题失败: {e}"))?;
+    let title = clean_title(&response.title);
+    if title.is_empty() {
+        return Err("文本模型返回的标题为空".to_string());
+    }
+    Ok((title, clean_tags(response.tags)))
+}
+
+fn clean_title(title: &str) -> String {
+    let title = title
+        .trim()
+        .trim_matches(|c: char| "\"'“”‘’《》「」#".contains(c))
+        .trim_end_matches(|c: char| "。，、；：！？.,;:!?".contains(c))
+        .trim();
+    truncate(title, MAX_TITLE_CHARS)
+}
+
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_CHARS {
+            continue;
+        }
+        if !cleaned.iter().any(|existing| existing == tag) {
+            cleaned.push(tag.to_string());
+        }
+        if cleaned.len() == MAX_TAGS {
+            break;
+        }
+    }
+    cleaned
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max_chars).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// 离线规则：首句作为标题，重复出现的二字词与英文单词作为标签
+fn keyword_title(text: &str) -> (String, Vec<String>) {
+    let first_sentence = text
+        .split(['。', '！', '？', '!', '?', '\n', '；', ';'])
+        .map(str::trim)
+        .find(|sentence| !sentence.is_empty())
+        .unwrap_or_default();
+    let title = truncate(
+        first_sentence.trim_end_matches(['，', ',', '、']),
+        HEURISTIC_TITLE_CHARS,
+    );
+    (title, keywords(text))
+}
+
+fn keywords(text: &str) -> Vec<String> {
+    static LATIN_WORD: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"[A-Za-z][A-Za-z0-9+#.\-]{2,}").expect("英文单词正则无效"));
+
+    // 每个候选词的出现次数与首次出现位置，次数相同时先出现的优先
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut order = 0;
+    let mut count = |word: String| {
+        let entry = counts.entry(word).or_insert((0, order));
+        entry.0 += 1;
+        order += 1;
+    };
+    for found in LATIN_WORD.find_iter(text) {
+        let word = found.as_str().trim_end_matches('.').to_string();
+        if !STOP_WORDS.contains(&word.to_lowercase().as_str()) {
+            count(word);
+        }
+    }
+    let chars: Vec<char> = text.chars().collect();
+    for pair in chars.windows(2) {
+        if pair.iter().all(|c| is_cjk(*c) && !STOP_CHARS.contains(*c)) {
+            count(pair.iter().collect());
+        }
+    }
+
+    let mut candidates: Vec<(String, (usize, usize))> = counts
+        .into_iter()
+        .filter(|(_, (count, _))| *count >= 2)
+        .collect();
+    candidates.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.1 .1.cmp(&b.1 .1)));
+
+    // “语音识别”会同时产生“语音”“音识”“识别”，与已选词共享汉字的候选跳过
+    let mut tags: Vec<String> = Vec::new();
+    for (word, _) in candidates {
+        let overlaps = tags
+            .iter()
+            .any(|tag| tag.chars().any(|c| is_cjk(c) && word.contains(c)));
+        if !overlaps {
+            tags.push(word);
+        }
+        if tags.len() == MAX_TAGS {
+            break;
+        }
+    }
+    tags
+}
+
+fn is_cjk(c: char) -> bool {
+    ('\u{4e00}'..='\u{9fff}').contains(&c)
+}
+
+/// 为尚未生成标题的历史记录补全标题与标签，每次最多处理 50 条，返回成功的条数
+#[tauri::command]
+#[specta::specta]
+pub async fn backfill_history_titles(app: AppHandle) -> Result<u32, String> {
+    let entries = history::untitled_entries(&app, BACKFILL_BATCH).await?;
+    let mut processed = 0;
+    for entry in entries {
+        match generate_and_save(&app, entry.id.clone(), &entry.text).await {
+            Ok(()) => processed += 1,
+            Err(error) => warn!(
+                target = "miaoyu_titles",
+                error = %error,
+                id = entry.id,
+                "补全历史记录标题失败，跳过该条"
+            ),
+        }
+    }
+    Ok(processed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_title_uses_first_sentence() {
+        let (title, _) = keyword_title("\n  明天上午十点和设计组开会，讨论新版首页。然后写周报");
+        assert_eq!(title, "明天上午十点和设计组开会，讨论新…");
+
+        let (title, _) = keyword_title("好的，\n收到");
+        assert_eq!(title, "好的");
+
+        let (title, tags) = keyword_title("");
+        assert_eq!(title, "");
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn keywords_keep_repeated_words_without_overlap() {
+        assert_eq!(
+            keywords("语音识别很准。语音识别也很快。"),
+            vec!["语音", "识别"]
+        );
+        assert_eq!(
+            keywords("Rust and rust: the Tauri app uses Tauri and Rust"),
+            vec!["Rust", "Tauri"]
+        );
+        // 只出现一次的词与含常用字的词不作为标签
+        assert!(keywords("今天的天气很好，我们去公园散步").is_empty());
+    }
+
+    #[test]
+    fn keywords_are_limited_and_ordered_by_count() {
+        let tags = keywords("预算预算。周报周报周报。项目项目。客户客户。");
+        assert_eq!(tags, vec!["周报", "预算", "项目"]);
+    }
+}
@@ -0,0 +1,100 @@
+//! 翻译输入：识别结果经文本模型翻译为目标语言后粘贴，历史记录同时保存识别原文
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+
+use crate::{settings::SettingsStore, tray};
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TranslationLanguage {
+    /// 由模型自动判断，仅可作为源语言
+    Auto,
+    Chinese,
+    TraditionalChinese,
+    English,
+    Japanese,
+    Korean,
+    French,
+    German,
+    Spanish,
+    Russian,
+}
+
+impl TranslationLanguage {
+    /// 写入提示词的语言名称
+    pub fn prompt_name(self) -> &'static str {
+        match self {
+            TranslationLanguage::Auto => "the detected language",
+            TranslationLanguage::Chinese => "Simplified Chinese",
+            TranslationLanguage::TraditionalChinese => "Traditional Chinese",
+            TranslationLanguage::English => "English",
+            TranslationLanguage::Japanese => "Japanese",
+            TranslationLanguage::Korean => "Korean",
+            TranslationLanguage::French => "French",
+            TranslationLanguage::German => "German",
+            TranslationLanguage::Spanish => "Spanish",
+            TranslationLanguage::Russian => "Russian",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSettings {
+    #[serde(default = "default_source_language")]
+    pub source_language: TranslationLanguage,
+    #[serde(default = "default_target_language")]
+    pub target_language: TranslationLanguage,
+    /// 语音识别快捷键也按翻译输入处理，可在托盘菜单中切换
+    #[serde(default)]
+    pub dictation_translates: bool,
+}
+
+fn default_source_language() -> TranslationLanguage {
+    TranslationLanguage::Auto
+}
+
+fn default_target_language() -> TranslationLanguage {
+    TranslationLanguage::English
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            source_language: default_source_language(),
+            target_language: default_target_language(),
+            dictation_translates: false,
+        }
+    }
+}
+
+pub fn translation_settings(app: &AppHandle<Wry>) -> TranslationSettings {
+    SettingsStore::load(app).translation
+}
+
+/// 切换语音识别快捷键是否翻译，供托盘菜单使用
+pub fn set_dictation_translates(app: &AppHandle<Wry>, enabled: bool) -> Result<(), String> {
+    SettingsStore::update(app, |settings| {
+        settings.translation.dictation_translates = enabled;
+    })
+}
+
+/// 更新翻译输入的源语言、目标语言与语音识别快捷键的翻译开关
+#[tauri::command]
+#[specta::specta]
+pub fn set_translation_settings(
+    app: AppHandle,
+    translation: TranslationSettings,
+) -> Result<TranslationSettings, String> {
+    if translation.target_language == TranslationLanguage::Auto {
+        return Err("请选择翻译的目标语言".to_string());
+    }
+    if translation.source_language == translation.target_language {
+        return Err("源语言与目标语言不能相同".to_string());
+    }
+    SettingsStore::update(&app, |settings| settings.translation = translation.clone())?;
+    tray::sync_translation_mode(&app, translation.dictation_translates);
+    Ok(translation)
+}
@@ -1,26 +1,31 @@
-use crate::{windows::ShowAppWindow, AppState};
+use crate::{translation, windows::ShowAppWindow, AppState};
 use serde::Serialize;
 use tauri::menu::MenuId;
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
 
 pub enum TrayItem {
     Home,
     Models,
     Settings,
+    TranslationMode,
     Quit,
 }
 
+/// 托盘中的翻译模式勾选项，设置页修改后需要同步勾选状态
+struct TranslationModeItem(CheckMenuItem<Wry>);
+
 impl From<TrayItem> for MenuId {
     fn from(value: TrayItem) -> Self {
         match value {
             TrayItem::Home => "home",
             TrayItem::Models => "models",
             TrayItem::Settings => "settings",
+            TrayItem::TranslationMode => "translation_mode",
             TrayItem::Quit => "quit",
         }
         .into()
@@ -35,6 +40,7 @@ impl TryFrom<MenuId> for TrayItem {
             "home" => Ok(TrayItem::Home),
             "models" => Ok(TrayItem::Models),
             "settings" => Ok(TrayItem::Settings),
+            "translation_mode" => Ok(TrayItem::TranslationMode),
             "quit" => Ok(TrayItem::Quit),
             value => Err(format!("Invalid tray item id {value}")),
         }
@@ -83,9 +89,33 @@ pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
         Some("CmdOrCtrl+,"),
     )?;
 
+    let translation_item = CheckMenuItem::with_id(
+        app,
+        TrayItem::TranslationMode,
+        "语音识别时翻译",
+        true,
+        translation::translation_settings(app).dictation_translates,
+        None::<&str>,
+    )?;
+    app.manage(TranslationModeItem(translation_item.clone()));
+    // 同一个菜单项不能在菜单中出现两次，每处分隔线单独创建
+    let translation_separator = PredefinedMenuItem::separator(app)?;
+    let quit_separator = PredefinedMenuItem::separator(app)?;
+
     let quit_item = MenuItem::with_id(app, TrayItem::Quit, "退出应用", true, Some("CmdOrCtrl+Q"))?;
 
-    let menu = Menu::with_items(app, &[&home_item, &models_item, &settings_item, &quit_item])?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &home_item,
+            &models_item,
+            &settings_item,
+            &translation_separator,
+            &translation_item,
+            &quit_separator,
+            &quit_item,
+        ],
+    )?;
     let app = app.clone();
     TrayIconBuilder::with_id("tray")
         .icon(Image::from_bytes(include_bytes!("../icons/tray.png"))?)
@@ -135,6 +165,20 @@ pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
                         emit_navigation(&app_handle, "/settings");
                     });
                 }
+                Ok(TrayItem::TranslationMode) => {
+                    let Some(item) = app.try_state::<TranslationModeItem>() else {
+                        return;
+                    };
+                    // 勾选状态由菜单自动切换，这里只同步到设置
+                    let enabled = item.0.is_checked().unwrap_or(false);
+                    if let Err(error) = translation::set_dictation_translates(app, enabled) {
+                        tracing::warn!(
+                            target = "miaoyu_tray",
+                            error = %error,
+                            "保存翻译模式失败"
+                        );
+                    }
+                }
                 Ok(TrayItem::Quit) => {
                     app.exit(0);
                 }
@@ -154,3 +198,10 @@ pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
 
     Ok(())
 }
+
+/// 设置页切换翻译模式后同步托盘勾选状态
+pub fn sync_translation_mode(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<TranslationModeItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
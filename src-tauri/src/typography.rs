@@ -0,0 +1,473 @@
+//! 中文排版规范化：统一标点宽度、中西文间距、引号样式，并可去掉行末标点。
+//! 规则是确定性的，在所有处理完成后、粘贴前执行
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+
+use crate::settings::SettingsStore;
+
+/// 可在全角与半角之间转换的标点
+const PUNCTUATION_PAIRS: [(char, char); 6] = [
+    (',', '，'),
+    ('.', '。'),
+    ('!', '！'),
+    ('?', '？'),
+    (';', '；'),
+    (':', '：'),
+];
+/// 行末去掉的标点，问号、感叹号与省略号带有语气，保留
+const TRAILING_PUNCTUATION: &str = "。.，,；;、：:";
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PunctuationWidth {
+    #[default]
+    Keep,
+    /// 中文语境中的半角标点转为全角
+    FullWidth,
+    /// 全角标点转为半角并在其后补空格
+    HalfWidth,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CjkLatinSpacing {
+    #[default]
+    Keep,
+    /// 汉字与英文字母、数字之间加空格
+    Add,
+    /// 去掉汉字与英文字母、数字之间的空格
+    Remove,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteStyle {
+    #[default]
+    Keep,
+    /// “弯引号”
+    Curly,
+    /// 「直角引号」
+    Corner,
+    /// "直引号"
+    Straight,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TypographySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub punctuation_width: PunctuationWidth,
+    #[serde(default)]
+    pub cjk_latin_spacing: CjkLatinSpacing,
+    #[serde(default)]
+    pub quote_style: QuoteStyle,
+    /// 去掉每行末尾的句号、逗号等
+    #[serde(default)]
+    pub strip_trailing_punctuation: bool,
+}
+
+/// 汉字、假名与谚文
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+    )
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+/// 判断标点是否处在中文语境：紧邻的前一个字符是中文或全角符号
+fn is_cjk_context(c: Option<char>) -> bool {
+    c.is_some_and(|c| is_cjk(c) || "”’」』）》".contains(c))
+}
+
+fn convert_quotes(text: &str, style: QuoteStyle) -> String {
+    let (open_double, close_double, open_single, close_single) = match style {
+        QuoteStyle::Keep => return text.to_string(),
+        QuoteStyle::Curly => ('“', '”', '‘', '’'),
+        QuoteStyle::Corner => ('「', '」', '『', '』'),
+        QuoteStyle::Straight => ('"', '"', '\'', '\''),
+    };
+    let mut result = String::with_capacity(text.len());
+    // 直引号不区分开闭，按出现次序交替配对，每行重新开始
+    let mut straight_open = false;
+    for c in text.chars() {
+        let converted = match c {
+            '“' | '「' => open_double,
+            '”' | '」' => close_double,
+            '‘' | '『' => open_single,
+            '’' | '』' => close_single,
+            // 直单引号多为英文缩写中的撇号，不转换
+            '"' if style != QuoteStyle::Straight => {
+                straight_open = !straight_open;
+                if straight_open {
+                    open_double
+                } else {
+                    close_double
+                }
+            }
+            '\n' => {
+                straight_open = false;
+                c
+            }
+            _ => c,
+        };
+        result.push(converted);
+    }
+    result
+}
+
+fn to_full_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let prev = result.trim_end_matches(' ').chars().last();
+        let next = chars.get(index + 1).copied();
+        if c == '.' && next == Some('.') && is_cjk_context(prev) {
+            // 中文里的省略号
+            while chars.get(index) == Some(&'.') {
+                index += 1;
+            }
+            result.push_str("……");
+            continue;
+        }
+        let full = PUNCTUATION_PAIRS
+            .iter()
+            .find(|(half, _)| *half == c)
+            .map(|(_, full)| *full);
+        let next_visible = chars[index + 1..].iter().find(|c| **c != ' ').copied();
+        // 小数点、文件扩展名和时间中的符号后面紧跟字母或数字，不转换
+        let inside_word = matches!(c, '.' | ':') && next.is_some_and(is_latin);
+        // 跟在英文或数字后的标点，后面接中文或位于中文句末时同样视为中文标点
+        let line_end = next_visible.is_none_or(|c| c == '\n');
+        let line_has_cjk = result
+            .rsplit('\n')
+            .next()
+            .is_some_and(|line| line.chars().any(is_cjk));
+        // 英文缩写的点后面常隔着空格接中文，只看紧邻的字符
+        let next_cjk = if c == '.' { next } else { next_visible }.is_some_and(is_cjk);
+        let cjk_context = is_cjk_context(prev) || next_cjk || (line_end && line_has_cjk);
+        let converted = match c {
+            '(' => next_visible.is_some_and(is_cjk).then_some('（'),
+            ')' => is_cjk_context(prev).then_some('）'),
+            _ => full.filter(|_| cjk_context && !inside_word),
+        };
+        match converted {
+            Some(full) => {
+                // 全角标点自带间距，去掉两侧的空格
+                let trimmed = result.trim_end_matches(' ').len();
+                result.truncate(trimmed);
+                result.push(full);
+                index += 1;
+                while chars.get(index) == Some(&' ') {
+                    index += 1;
+                }
+            }
+            None => {
+                result.push(c);
+                index += 1;
+            }
+        }
+    }
+    result
+}
+
+fn to_half_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    for (index, &c) in chars.iter().enumerate() {
+        let next = chars.get(index + 1).copied();
+        // 时间、小数中的符号两侧都是字母或数字，转换后不补空格
+        let inside_word = matches!(c, '：' | '。')
+            && index > 0
+            && is_latin(chars[index - 1])
+            && next.is_some_and(is_latin);
+        // 后面紧跟文字时补一个空格，后面是空白、标点或结尾时不补
+        let needs_space = !inside_word
+            && next
+                .is_some_and(|next| is_cjk(next) || is_latin(next) || "“‘「『(（".contains(next));
+        if let Some((half, _)) = PUNCTUATION_PAIRS.iter().find(|(_, full)| *full == c) {
+            result.push(*half);
+            if needs_space {
+                result.push(' ');
+            }
+            continue;
+        }
+        match c {
+            '（' => {
+                if result
+                    .chars()
+                    .last()
+                    .is_some_and(|last| !last.is_whitespace())
+                {
+                    result.push(' ');
+                }
+                result.push('(');
+            }
+            '）' => {
+                result.push(')');
+                if needs_space {
+                    result.push(' ');
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn adjust_spacing(text: &str, spacing: CjkLatinSpacing) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let prev = result.chars().last();
+        match spacing {
+            CjkLatinSpacing::Keep => {}
+            CjkLatinSpacing::Add => {
+                let crosses = prev.is_some_and(|prev| {
+                    (is_cjk(prev) && is_latin(c)) || (is_latin(prev) && is_cjk(c))
+                });
+                if crosses {
+                    result.push(' ');
+                }
+            }
+            CjkLatinSpacing::Remove => {
+                if c == ' ' && prev.is_some_and(|prev| is_cjk(prev) || is_latin(prev)) {
+                    let mut end = index;
+                    while chars.get(end) == Some(&' ') {
+                        end += 1;
+                    }
+                    let removable = chars.get(end).is_some_and(|next| {
+                        prev.is_some_and(|prev| {
+                            (is_cjk(prev) && is_latin(*next)) || (is_latin(prev) && is_cjk(*next))
+                        })
+                    });
+                    if removable {
+                        index = end;
+                        continue;
+                    }
+                }
+            }
+        }
+        result.push(c);
+        index += 1;
+    }
+    result
+}
+
+fn strip_trailing_punctuation(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let trimmed = line.trim_end();
+            let mut chars = trimmed.chars().rev();
+            match (chars.next(), chars.next()) {
+                // 连续的点是省略号，保留
+                (Some('.'), Some('.')) => trimmed,
+                (Some(last), _) if TRAILING_PUNCTUATION.contains(last) => {
+                    trimmed[..trimmed.len() - last.len_utf8()].trim_end()
+                }
+                _ => trimmed,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 按设置依次处理引号、标点宽度、中西文间距与行末标点；未开启时原样返回
+pub fn apply(text: &str, settings: &TypographySettings) -> String {
+    if !settings.enabled || text.trim().is_empty() {
+        return text.to_string();
+    }
+    let mut result = convert_quotes(text, settings.quote_style);
+    result = match settings.punctuation_width {
+        PunctuationWidth::Keep => result,
+        PunctuationWidth::FullWidth => to_full_width(&result),
+        PunctuationWidth::HalfWidth => to_half_width(&result),
+    };
+    result = adjust_spacing(&result, settings.cjk_latin_spacing);
+    if settings.strip_trailing_punctuation {
+        result = strip_trailing_punctuation(&result);
+    }
+    result
+}
+
+pub fn typography_settings(app: &AppHandle<Wry>) -> TypographySettings {
+    SettingsStore::load(app).typography
+}
+
+/// 更新排版规范化设置
+#[tauri::command]
+#[specta::specta]
+pub fn set_typography(
+    app: AppHandle,
+    typography: TypographySettings,
+) -> Result<TypographySettings, String> {
+    SettingsStore::update(&app, |settings| settings.typography = typography.clone())?;
+    Ok(typography)
+}
+
+/// 预览排版效果，用于在设置页测试；忽略 `enabled` 开关
+#[tauri::command]
+#[specta::specta]
+pub fn preview_typography(
+    mut typography: TypographySettings,
+    text: String,
+) -> Result<String, String> {
+    typography.enabled = true;
+    Ok(apply(&text, &typography))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use CjkLatinSpacing as S;
+    use PunctuationWidth as W;
+    use QuoteStyle as Q;
+
+    fn settings(width: W, spacing: S, quotes: Q, strip: bool) -> TypographySettings {
+        TypographySettings {
+            enabled: true,
+            punctuation_width: width,
+            cjk_latin_spacing: spacing,
+            quote_style: quotes,
+            strip_trailing_punctuation: strip,
+        }
+    }
+
+    #[test]
+    fn golden_cases() {
+        let cases: &[(&str, TypographySettings, &str)] = &[
+            // 全角
+            (
+                "你好,世界!今天用Python写代码.",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "你好，世界！今天用Python写代码。",
+            ),
+            (
+                "版本是3.5,网址是example.com,时间12:30.",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "版本是3.5，网址是example.com，时间12:30。",
+            ),
+            (
+                "等等...好的",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "等等……好的",
+            ),
+            (
+                "他说(这是备注)没问题",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "他说（这是备注）没问题",
+            ),
+            (
+                "Hello, world. I'm fine...",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "Hello, world. I'm fine...",
+            ),
+            (
+                "见 e.g. 文档",
+                settings(W::FullWidth, S::Keep, Q::Keep, false),
+                "见 e.g. 文档",
+            ),
+            // 半角
+            (
+                "你好，世界！今天很好。",
+                settings(W::HalfWidth, S::Keep, Q::Keep, false),
+                "你好, 世界! 今天很好.",
+            ),
+            (
+                "版本是3.5，网址是example.com，时间12：30。",
+                settings(W::HalfWidth, S::Keep, Q::Keep, false),
+                "版本是3.5, 网址是example.com, 时间12:30.",
+            ),
+            (
+                "备注（草稿）完成",
+                settings(W::HalfWidth, S::Keep, Q::Keep, false),
+                "备注 (草稿) 完成",
+            ),
+            // 中英文间距
+            (
+                "用Python3写了100行代码",
+                settings(W::Keep, S::Add, Q::Keep, false),
+                "用 Python3 写了 100 行代码",
+            ),
+            (
+                "用 Python3 写了  100 行代码",
+                settings(W::Keep, S::Remove, Q::Keep, false),
+                "用Python3写了100行代码",
+            ),
+            (
+                "Hello world 你好",
+                settings(W::Keep, S::Remove, Q::Keep, false),
+                "Hello world你好",
+            ),
+            // 引号
+            (
+                "他说\"好的\"然后走了",
+                settings(W::Keep, S::Keep, Q::Keep, false),
+                "他说\"好的\"然后走了",
+            ),
+            (
+                "他说\"好的\"，「嗯」",
+                settings(W::Keep, S::Keep, Q::Curly, false),
+                "他说“好的”，“嗯”",
+            ),
+            (
+                "他说\"没说完\n她说\"嗯\"",
+                settings(W::Keep, S::Keep, Q::Curly, false),
+                "他说“没说完\n她说“嗯”",
+            ),
+            (
+                "他说“好的”，‘嗯’，\"行\"",
+                settings(W::Keep, S::Keep, Q::Corner, false),
+                "他说「好的」，『嗯』，「行」",
+            ),
+            (
+                "他说「好的」，『嗯』，“行”，I'm",
+                settings(W::Keep, S::Keep, Q::Straight, false),
+                "他说\"好的\"，'嗯'，\"行\"，I'm",
+            ),
+            // 行末标点
+            (
+                "好的。\n明天见，\n真的吗？\n太好了！\n然后...\n等等……",
+                settings(W::Keep, S::Keep, Q::Keep, true),
+                "好的\n明天见\n真的吗？\n太好了！\n然后...\n等等……",
+            ),
+            // 依次处理引号、标点宽度、间距与行末标点
+            (
+                "今天用GitHub提交了代码,好的.",
+                settings(W::FullWidth, S::Add, Q::Keep, true),
+                "今天用 GitHub 提交了代码，好的",
+            ),
+            (
+                "他说\"用Python\".",
+                settings(W::FullWidth, S::Add, Q::Curly, true),
+                "他说“用 Python”",
+            ),
+        ];
+        for (input, settings, expected) in cases {
+            assert_eq!(apply(input, settings), *expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn disabled_settings_keep_text() {
+        let mut settings = settings(W::FullWidth, S::Add, Q::Curly, true);
+        settings.enabled = false;
+        assert_eq!(apply("你好,world.", &settings), "你好,world.");
+    }
+}
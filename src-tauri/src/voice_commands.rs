@@ -0,0 +1,536 @@
+//! 口述格式指令：识别完成后、润色之前，把“换行”“句号”“删除上一句”等短语转换为对应的格式操作；
+//! 末尾说出的“发送”在粘贴后模拟回车，方便在聊天应用中直接发出。Paraformer 等模型不输出标点，
+//! 短语既可以单独成句，也可以夹在没有标点的文字中
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Wry};
+
+use crate::settings::SettingsStore;
+
+/// 分隔子句的标点
+const CLAUSE_DELIMITERS: &str = "，,。.！!？?；;、\n";
+/// 插入标点或换行前从结果末尾去掉的标点
+const SOFT_PUNCTUATION: &str = "，,、；;：:";
+const SENTENCE_ENDINGS: &str = "。.！!？?\n";
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoiceCommandLanguage {
+    Chinese,
+    English,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoiceCommandAction {
+    NewLine,
+    NewParagraph,
+    Period,
+    Comma,
+    QuestionMark,
+    ExclamationMark,
+    DeleteLastSentence,
+    /// 只在整段话末尾生效：粘贴后模拟回车
+    Send,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommandPhrase {
+    /// 决定插入全角还是半角标点
+    pub language: VoiceCommandLanguage,
+    pub action: VoiceCommandAction,
+    pub phrase: String,
+}
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommandSettings {
+    /// 默认关闭：开启前口述的“发送”“句号”等词都按普通内容处理，升级后不应改变
+    #[serde(default)]
+    pub enabled: bool,
+    /// 指令短语表，同一动作可以有多个说法
+    #[serde(default = "default_phrases")]
+    pub phrases: Vec<VoiceCommandPhrase>,
+}
+
+fn default_phrases() -> Vec<VoiceCommandPhrase> {
+    use VoiceCommandAction::*;
+    use VoiceCommandLanguage::*;
+
+    let table: [(VoiceCommandLanguage, VoiceCommandAction, &str); 20] = [
+        (Chinese, NewLine, "换行"),
+        (Chinese, NewParagraph, "新段落"),
+        (Chinese, NewParagraph, "另起一段"),
+        (Chinese, Period, "句号"),
+        (Chinese, Comma, "逗号"),
+        (Chinese, QuestionMark, "问号"),
+        (Chinese, ExclamationMark, "感叹号"),
+        (Chinese, DeleteLastSentence, "删除上一句"),
+        (Chinese, DeleteLastSentence, "删掉上一句"),
+        (Chinese, Send, "发送"),
+        (English, NewLine, "new line"),
+        (English, NewParagraph, "new paragraph"),
+        (English, Period, "period"),
+        (English, Period, "full stop"),
+        (English, Comma, "comma"),
+        (English, QuestionMark, "question mark"),
+        (English, ExclamationMark, "exclamation mark"),
+        (English, DeleteLastSentence, "delete last sentence"),
+        (English, DeleteLastSentence, "scratch that"),
+        (English, Send, "send"),
+    ];
+    table
+        .into_iter()
+        .map(|(language, action, phrase)| VoiceCommandPhrase {
+            language,
+            action,
+            phrase: phrase.to_string(),
+        })
+        .collect()
+}
+
+impl Default for VoiceCommandSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            phrases: default_phrases(),
+        }
+    }
+}
+
+impl VoiceCommandSettings {
+    /// 去掉空白短语与重复项
+    fn normalize(&mut self) {
+        let mut seen: Vec<(VoiceCommandLanguage, String)> = Vec::new();
+        self.phrases.retain_mut(|item| {
+            item.phrase = item.phrase.trim().to_string();
+            let key = (item.language, normalize_phrase(&item.phrase));
+            if key.1.is_empty() || seen.contains(&key) {
+                return false;
+            }
+            seen.push(key);
+            true
+        });
+    }
+
+    /// 在没有标点的子句中找出夹在文字里的指令短语，同一位置优先匹配较长的短语；
+    /// 英文短语前后需要是单词边界，避免 “periodic” 里的 “period” 生效
+    fn split_embedded<'a>(&'a self, content: &'a str) -> Vec<Piece<'a>> {
+        let mut phrases: Vec<(&VoiceCommandPhrase, String)> = self
+            .phrases
+            .iter()
+            .map(|item| (item, normalize_phrase(&item.phrase)))
+            .filter(|(_, phrase)| !phrase.is_empty())
+            .collect();
+        phrases.sort_by_key(|(_, phrase)| std::cmp::Reverse(phrase.chars().count()));
+
+        let mut pieces = Vec::new();
+        let mut text_start = 0;
+        let mut index = 0;
+        while index < content.len() {
+            let found = phrases.iter().find_map(|(item, phrase)| {
+                let end = match_phrase_at(content, index, phrase)?;
+                let is_word = phrase.starts_with(|c: char| c.is_ascii_alphanumeric());
+                let bounded = !is_word
+                    || (!content[..index].ends_with(|c: char| c.is_ascii_alphanumeric())
+                        && !content[end..].starts_with(|c: char| c.is_ascii_alphanumeric()));
+                bounded.then_some((*item, end))
+            });
+            if let Some((command, end)) = found {
+                if text_start < index {
+                    pieces.push(Piece::text(&content[text_start..index]));
+                }
+                pieces.push(Piece {
+                    content: &content[index..end],
+                    tail: "",
+                    command: Some(command),
+                });
+                text_start = end;
+                index = end;
+            } else {
+                index += content[index..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        if text_start < content.len() {
+            pieces.push(Piece::text(&content[text_start..]));
+        }
+        pieces
+    }
+
+    fn lookup(&self, clause: &str) -> Option<&VoiceCommandPhrase> {
+        let key = normalize_phrase(clause);
+        if key.is_empty() {
+            return None;
+        }
+        self.phrases
+            .iter()
+            .find(|item| normalize_phrase(&item.phrase) == key)
+    }
+}
+
+/// 指令处理后的文本，以及是否需要在粘贴后模拟回车
+#[derive(Serialize, Type, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommandOutcome {
+    pub text: String,
+    pub send: bool,
+    /// 生效的指令数量
+    pub applied: u32,
+}
+
+fn normalize_phrase(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// 按标点切分的子句：内容与其后的分隔符
+struct Clause<'a> {
+    content: &'a str,
+    tail: &'a str,
+}
+
+/// 子句中的一段普通文字或一个指令短语
+struct Piece<'a> {
+    content: &'a str,
+    tail: &'a str,
+    command: Option<&'a VoiceCommandPhrase>,
+}
+
+impl<'a> Piece<'a> {
+    fn text(content: &'a str) -> Self {
+        Self {
+            content,
+            tail: "",
+            command: None,
+        }
+    }
+}
+
+/// `text` 从 `start` 起是否为 `phrase`（已规范化），返回匹配结束的位置；
+/// 英文不区分大小写，短语中的空格可以对应一个或多个空白
+fn match_phrase_at(text: &str, start: usize, phrase: &str) -> Option<usize> {
+    let mut chars = text[start..].char_indices().peekable();
+    for expected in phrase.chars() {
+        let (_, actual) = chars.next()?;
+        if expected == ' ' {
+            if !actual.is_whitespace() {
+                return None;
+            }
+            while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        } else if actual.to_lowercase().ne(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(
+        chars
+            .peek()
+            .map_or(text.len(), |(offset, _)| start + offset),
+    )
+}
+
+fn split_clauses(text: &str) -> Vec<Clause<'_>> {
+    let mut clauses = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if !CLAUSE_DELIMITERS.contains(c) {
+            continue;
+        }
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !CLAUSE_DELIMITERS.contains(next) && !next.is_whitespace() {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            chars.next();
+        }
+        clauses.push(Clause {
+            content: &text[start..index],
+            tail: &text[index..end],
+        });
+        start = end;
+    }
+    if start < text.len() {
+        clauses.push(Clause {
+            content: &text[start..],
+            tail: "",
+        });
+    }
+    clauses
+}
+
+fn trim_end_matching<'a>(text: &'a str, chars: &str) -> &'a str {
+    text.trim_end_matches(|c: char| c.is_whitespace() || chars.contains(c))
+}
+
+fn apply_action(output: &mut String, language: VoiceCommandLanguage, action: VoiceCommandAction) {
+    let chinese = language == VoiceCommandLanguage::Chinese;
+    let mark = match action {
+        VoiceCommandAction::NewLine => "\n",
+        VoiceCommandAction::NewParagraph => "\n\n",
+        VoiceCommandAction::Period if chinese => "。",
+        VoiceCommandAction::Period => ". ",
+        VoiceCommandAction::Comma if chinese => "，",
+        VoiceCommandAction::Comma => ", ",
+        VoiceCommandAction::QuestionMark if chinese => "？",
+        VoiceCommandAction::QuestionMark => "? ",
+        VoiceCommandAction::ExclamationMark if chinese => "！",
+        VoiceCommandAction::ExclamationMark => "! ",
+        VoiceCommandAction::DeleteLastSentence => {
+            let kept = trim_end_matching(output, SENTENCE_ENDINGS)
+                .trim_end_matches(|c: char| SOFT_PUNCTUATION.contains(c));
+            let end = kept
+                .char_indices()
+                .rfind(|(_, c)| SENTENCE_ENDINGS.contains(*c))
+                .map(|(index, c)| index + c.len_utf8())
+                .unwrap_or(0);
+            output.truncate(end);
+            // 英文句末标点后保留空格，避免与接着口述的内容连在一起
+            if output.ends_with(['.', '!', '?']) {
+                output.push(' ');
+            }
+            return;
+        }
+        VoiceCommandAction::Send => return,
+    };
+    let kept = trim_end_matching(output, SOFT_PUNCTUATION).len();
+    output.truncate(kept);
+    // 连续口述“句号”时不重复添加
+    if !matches!(
+        action,
+        VoiceCommandAction::NewLine | VoiceCommandAction::NewParagraph
+    ) && output.ends_with(|c: char| SENTENCE_ENDINGS.contains(c))
+    {
+        return;
+    }
+    output.push_str(mark);
+}
+
+/// 把识别结果中的指令短语转换为格式操作；没有指令生效时原样返回
+pub fn apply(text: &str, settings: &VoiceCommandSettings) -> VoiceCommandOutcome {
+    let unchanged = VoiceCommandOutcome {
+        text: text.to_string(),
+        send: false,
+        applied: 0,
+    };
+    if !settings.enabled || settings.phrases.is_empty() {
+        return unchanged;
+    }
+
+    let mut pieces = Vec::new();
+    for clause in split_clauses(text) {
+        if let Some(command) = settings.lookup(clause.content) {
+            pieces.push(Piece {
+                content: clause.content,
+                tail: clause.tail,
+                command: Some(command),
+            });
+            continue;
+        }
+        let mut embedded = settings.split_embedded(clause.content);
+        match embedded.last_mut() {
+            Some(last) => last.tail = clause.tail,
+            None => embedded.push(Piece {
+                content: "",
+                tail: clause.tail,
+                command: None,
+            }),
+        }
+        pieces.extend(embedded);
+    }
+    let last = pieces
+        .iter()
+        .rposition(|piece| !piece.content.trim().is_empty());
+    let mut output = String::new();
+    let mut send = false;
+    let mut applied = 0;
+    // 指令之后的子句去掉开头空白，避免换行后多出空格
+    let mut after_command = false;
+    for (index, clause) in pieces.iter().enumerate() {
+        let is_last = Some(index) == last;
+        if let Some(command) = clause.command {
+            // “发送”出现在句中时按普通内容处理
+            if command.action != VoiceCommandAction::Send || is_last {
+                apply_action(&mut output, command.language, command.action);
+                send |= command.action == VoiceCommandAction::Send;
+                applied += 1;
+                after_command = true;
+                continue;
+            }
+        }
+        let content = if after_command {
+            clause.content.trim_start()
+        } else {
+            clause.content
+        };
+        after_command = after_command && content.is_empty();
+        output.push_str(content);
+        output.push_str(clause.tail);
+    }
+    if applied == 0 {
+        return unchanged;
+    }
+    // 以指令结尾时去掉前一子句留下的逗号等
+    let text = if after_command {
+        output.trim_end_matches(|c: char| c == ' ' || c == '\t' || SOFT_PUNCTUATION.contains(c))
+    } else {
+        output.trim_end_matches([' ', '\t'])
+    };
+    VoiceCommandOutcome {
+        text: text.to_string(),
+        send,
+        applied,
+    }
+}
+
+pub fn command_settings(app: &AppHandle<Wry>) -> VoiceCommandSettings {
+    SettingsStore::load(app).voice_commands
+}
+
+/// 更新口述格式指令设置
+#[tauri::command]
+#[specta::specta]
+pub fn set_voice_commands(
+    app: AppHandle,
+    mut voice_commands: VoiceCommandSettings,
+) -> Result<VoiceCommandSettings, String> {
+    voice_commands.normalize();
+    SettingsStore::update(&app, |settings| {
+        settings.voice_commands = voice_commands.clone()
+    })?;
+    Ok(voice_commands)
+}
+
+/// 恢复默认的指令短语表，保留开关状态
+#[tauri::command]
+#[specta::specta]
+pub fn reset_voice_commands(app: AppHandle) -> Result<VoiceCommandSettings, String> {
+    SettingsStore::update(&app, |settings| {
+        settings.voice_commands.phrases = default_phrases();
+        settings.voice_commands.clone()
+    })
+}
+
+/// 预览指令处理效果，用于在设置页测试短语表；忽略 `enabled` 开关
+#[tauri::command]
+#[specta::specta]
+pub fn preview_voice_commands(
+    mut voice_commands: VoiceCommandSettings,
+    text: String,
+) -> Result<VoiceCommandOutcome, String> {
+    voice_commands.normalize();
+    voice_commands.enabled = true;
+    Ok(apply(&text, &voice_commands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(text: &str) -> VoiceCommandOutcome {
+        let settings = VoiceCommandSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        apply(text, &settings)
+    }
+
+    #[test]
+    fn commands_are_off_by_default() {
+        let settings: VoiceCommandSettings = serde_json::from_str("{}").unwrap();
+        assert!(!settings.enabled);
+        assert!(!VoiceCommandSettings::default().enabled);
+        let outcome = apply("好的，发送", &settings);
+        assert_eq!((outcome.text.as_str(), outcome.send), ("好的，发送", false));
+    }
+
+    #[test]
+    fn splits_clauses_with_trailing_delimiters() {
+        let clauses: Vec<(&str, &str)> = split_clauses("你好， 世界。。再见")
+            .into_iter()
+            .map(|clause| (clause.content, clause.tail))
+            .collect();
+        assert_eq!(
+            clauses,
+            vec![("你好", "， "), ("世界", "。。"), ("再见", "")]
+        );
+        assert!(split_clauses("").is_empty());
+    }
+
+    #[test]
+    fn deletes_the_previous_sentence() {
+        assert_eq!(run("今天天气不错。删除上一句。明天见").text, "明天见");
+        assert_eq!(
+            run("第一句。第二句，删除上一句，第三句").text,
+            "第一句。第三句"
+        );
+        assert_eq!(run("第一句。第二句。删掉上一句。").text, "第一句。");
+    }
+
+    #[test]
+    fn repeated_periods_add_one_mark() {
+        let outcome = run("好的，句号，句号");
+        assert_eq!(outcome.text, "好的。");
+        assert_eq!(outcome.applied, 2);
+        assert_eq!(run("第一段，换行，第二段").text, "第一段\n第二段");
+        assert_eq!(run("你确定吗，问号").text, "你确定吗？");
+    }
+
+    #[test]
+    fn send_only_applies_at_the_end() {
+        let outcome = run("明天见，发送");
+        assert_eq!((outcome.text.as_str(), outcome.send), ("明天见", true));
+        let outcome = run("发送，文件给我");
+        assert_eq!(
+            (outcome.text.as_str(), outcome.send),
+            ("发送，文件给我", false)
+        );
+        assert_eq!(run("把文件发送给我").applied, 0);
+    }
+
+    #[test]
+    fn commands_apply_inside_unpunctuated_text() {
+        let outcome = run("明天见发送");
+        assert_eq!((outcome.text.as_str(), outcome.send), ("明天见", true));
+        let outcome = run("好的 发送");
+        assert_eq!((outcome.text.as_str(), outcome.send), ("好的", true));
+        assert_eq!(run("今天开会换行明天出差").text, "今天开会\n明天出差");
+        assert_eq!(run("第一句话删除上一句").text, "");
+        assert_eq!(
+            run("第一句话句号第二句话删除上一句第三句话").text,
+            "第一句话。第三句话"
+        );
+        assert_eq!(run("你确定吗问号").text, "你确定吗？");
+        let outcome = run("把文件发送给我");
+        assert_eq!(
+            (outcome.text.as_str(), outcome.applied),
+            ("把文件发送给我", 0)
+        );
+    }
+
+    #[test]
+    fn embedded_english_commands_need_word_boundaries() {
+        assert_eq!(run("hello new line world").text, "hello\nworld");
+        let outcome = run("see you Send");
+        assert_eq!((outcome.text.as_str(), outcome.send), ("see you", true));
+        assert_eq!(run("a periodic sender").applied, 0);
+    }
+
+    #[test]
+    fn english_commands_use_half_width_marks() {
+        assert_eq!(run("hello, new line, world. period").text, "hello\nworld.");
+        assert_eq!(run("is this right, question mark").text, "is this right?");
+        assert_eq!(
+            run("first. second, scratch that, third").text,
+            "first. third"
+        );
+        assert_eq!(run("first. second. scratch that.").text, "first.");
+        let outcome = run("see you, Send");
+        assert_eq!((outcome.text.as_str(), outcome.send), ("see you", true));
+    }
+}
@@ -0,0 +1,240 @@
+import { useState } from "react";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import { Textarea } from "~/components/ui/textarea";
+import {
+	type CustomLlmBackend,
+	type CustomLlmProviderInput,
+	commands,
+} from "~/lib/tauri";
+
+const BACKEND_OPTIONS: {
+	value: CustomLlmBackend;
+	label: string;
+	serverUrl: string;
+}[] = [
+	{ value: "openAiCompatible", label: "OpenAI 兼容接口", serverUrl: "" },
+	{
+		value: "ollama",
+		label: "Ollama（本地）",
+		serverUrl: "http://localhost:11434",
+	},
+	{
+		value: "llamaCpp",
+		label: "llama.cpp server（本地）",
+		serverUrl: "http://localhost:8080",
+	},
+	{
+		value: "anthropic",
+		label: "Anthropic Messages API",
+		serverUrl: "https://api.anthropic.com",
+	},
+	{
+		value: "gemini",
+		label: "Google Gemini",
+		serverUrl: "https://generativelanguage.googleapis.com/v1beta",
+	},
+];
+
+const LOCAL_BACKENDS: CustomLlmBackend[] = ["ollama", "llamaCpp"];
+
+interface CustomLlmProviderFormProps {
+	saving?: boolean;
+	error?: string | null;
+	onSubmit: (provider: CustomLlmProviderInput) => void;
+	onCancel: () => void;
+}
+
+/** 每行一个 `Header-Name: value` */
+const parseHeaders = (raw: string) => {
+	const headers: Record<string, string> = {};
+	for (const line of raw.split("\n")) {
+		const separator = line.indexOf(":");
+		if (separator <= 0) {
+			continue;
+		}
+		const name = line.slice(0, separator).trim();
+		if (name) {
+			headers[name] = line.slice(separator + 1).trim();
+		}
+	}
+	return headers;
+};
+
+export function CustomLlmProviderForm({
+	saving,
+	error,
+	onSubmit,
+	onCancel,
+}: CustomLlmProviderFormProps) {
+	const [backend, setBackend] = useState<CustomLlmBackend>("openAiCompatible");
+	const [name, setName] = useState("");
+	const [apiBaseUrl, setApiBaseUrl] = useState("");
+	const [localModels, setLocalModels] = useState<string[]>([]);
+	const [localStatus, setLocalStatus] = useState<string | null>(null);
+	const [loadingModels, setLoadingModels] = useState(false);
+	const [model, setModel] = useState("");
+	const [apiKey, setApiKey] = useState("");
+	const [apiKeyEnv, setApiKeyEnv] = useState("");
+	const [headers, setHeaders] = useState("");
+
+	const isLocal = LOCAL_BACKENDS.includes(backend);
+	const canSubmit =
+		name.trim() &&
+		(apiBaseUrl.trim() || isLocal) &&
+		model.trim() &&
+		(apiKey.trim() || apiKeyEnv.trim() || isLocal);
+
+	const handleBackendChange = (value: CustomLlmBackend) => {
+		setBackend(value);
+		const option = BACKEND_OPTIONS.find((item) => item.value === value);
+		setApiBaseUrl(option?.serverUrl ?? "");
+		setLocalModels([]);
+		setLocalStatus(null);
+	};
+
+	const handleLoadLocalModels = async () => {
+		setLoadingModels(true);
+		setLocalStatus(null);
+		try {
+			const models = await commands.listLocalLlmModels(backend, apiBaseUrl);
+			setLocalModels(models);
+			if (models.length === 0) {
+				setLocalStatus("服务在线，但尚未安装任何模型");
+			} else if (!model.trim()) {
+				setModel(models[0]);
+			}
+		} catch (error) {
+			setLocalModels([]);
+			setLocalStatus(String(error));
+		} finally {
+			setLoadingModels(false);
+		}
+	};
+
+	const fields = [
+		{ label: "名称", value: name, onChange: setName, placeholder: "公司网关" },
+		{
+			label: "接口地址",
+			value: apiBaseUrl,
+			onChange: setApiBaseUrl,
+			placeholder: "https://llm.example.com/v1",
+		},
+		{ label: "模型名称", value: model, onChange: setModel, placeholder: "" },
+		{
+			label: "API 密钥",
+			value: apiKey,
+			onChange: setApiKey,
+			placeholder: isLocal ? "本地服务可留空" : "",
+		},
+		{
+			label: "环境变量",
+			value: apiKeyEnv,
+			onChange: setApiKeyEnv,
+			placeholder: "可选，例如 GATEWAY_API_KEY",
+		},
+	];
+
+	return (
+		<div className="flex w-full flex-col gap-3 rounded-lg border p-4">
+			<div className="flex w-full flex-wrap items-center gap-3 gap-x-8">
+				<Label className="w-20 shrink-0">类型</Label>
+				<Select
+					value={backend}
+					onChange={(event) =>
+						handleBackendChange(event.target.value as CustomLlmBackend)
+					}
+					wrapperClassName="flex-1 min-w-[220px]"
+				>
+					{BACKEND_OPTIONS.map((option) => (
+						<option key={option.value} value={option.value}>
+							{option.label}
+						</option>
+					))}
+				</Select>
+			</div>
+			{fields.map((field) => (
+				<div
+					key={field.label}
+					className="flex w-full flex-wrap items-center gap-3 gap-x-8"
+				>
+					<Label className="w-20 shrink-0">{field.label}</Label>
+					<Input
+						type="text"
+						className="flex-1 min-w-[220px]"
+						value={field.value}
+						placeholder={field.placeholder}
+						list={
+							field.label === "模型名称" && localModels.length > 0
+								? "local-llm-models"
+								: undefined
+						}
+						onChange={(event) => field.onChange(event.target.value)}
+					/>
+				</div>
+			))}
+			{isLocal && (
+				<div className="flex items-center justify-end gap-3">
+					{localStatus && (
+						<p className="text-xs text-muted-foreground">{localStatus}</p>
+					)}
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={loadingModels}
+						onClick={() => void handleLoadLocalModels()}
+					>
+						{loadingModels ? "读取中..." : "读取已安装模型"}
+					</Button>
+				</div>
+			)}
+			<datalist id="local-llm-models">
+				{localModels.map((item) => (
+					<option key={item} value={item} />
+				))}
+			</datalist>
+			<div className="flex w-full flex-wrap items-start gap-3 gap-x-8">
+				<Label className="w-20 shrink-0 pt-2">额外请求头</Label>
+				<Textarea
+					className="flex-1 min-w-[220px]"
+					value={headers}
+					placeholder="每行一个，例如 X-Team: voice"
+					onChange={(event) => setHeaders(event.target.value)}
+				/>
+			</div>
+			<div className="flex items-center justify-end gap-3">
+				{error && <p className="text-xs text-destructive">{error}</p>}
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="text-muted-foreground hover:text-foreground"
+					onClick={onCancel}
+				>
+					取消
+				</Button>
+				<Button
+					type="button"
+					size="sm"
+					disabled={saving || !canSubmit}
+					onClick={() =>
+						onSubmit({
+							name,
+							backend,
+							apiBaseUrl,
+							model,
+							apiKey: apiKey.trim() || null,
+							apiKeyEnv: apiKeyEnv.trim() || null,
+							extraHeaders: parseHeaders(headers),
+						})
+					}
+				>
+					{saving ? "保存中..." : "添加"}
+				</Button>
+			</div>
+		</div>
+	);
+}
@@ -1,5 +1,5 @@
 import { getVersion } from "@tauri-apps/api/app";
-import { Cpu, Home, PanelLeftIcon, Settings } from "lucide-react";
+import { Cpu, Home, ListTodo, PanelLeftIcon, Settings } from "lucide-react";
 import { useEffect, useState } from "react";
 import { Button } from "~/components/ui/button";
 import {
@@ -26,6 +26,12 @@ const dashboardRoutes: Route[] = [
 		icon: <Home className="size-4" />,
 		link: "/",
 	},
+	{
+		id: "tasks",
+		title: "待办",
+		icon: <ListTodo className="size-4" />,
+		link: "/tasks",
+	},
 	{
 		id: "models",
 		title: "模型",
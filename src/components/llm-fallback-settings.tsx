@@ -0,0 +1,166 @@
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+import { ArrowUp, X } from "lucide-react";
+import { useEffect, useState } from "react";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import { commands, type LlmModelConfig, type ModelsStore } from "~/lib/tauri";
+
+interface LlmFallbackSettingsProps {
+	store: ModelsStore | undefined;
+	llmModels: LlmModelConfig[];
+}
+
+export function LlmFallbackSettings({
+	store,
+	llmModels,
+}: LlmFallbackSettingsProps) {
+	const queryClient = useQueryClient();
+	const timeoutSeconds = store?.llmRequest?.timeoutSeconds ?? 30;
+	const maxRetries = store?.llmRequest?.maxRetries ?? 2;
+	const fallbacks = store?.llmFallbackModels ?? [];
+	const [timeoutValue, setTimeoutValue] = useState(String(timeoutSeconds));
+	const [retriesValue, setRetriesValue] = useState(String(maxRetries));
+	const [error, setError] = useState<string | null>(null);
+
+	useEffect(() => {
+		setTimeoutValue(String(timeoutSeconds));
+		setRetriesValue(String(maxRetries));
+	}, [timeoutSeconds, maxRetries]);
+
+	const onSuccess = (data: ModelsStore) => {
+		queryClient.setQueryData(["models-store"], data);
+		setError(null);
+	};
+	const onError = (error: unknown) => setError(String(error));
+
+	const requestMutation = useMutation({
+		mutationFn: () =>
+			commands.setLlmRequestSettings({
+				timeoutSeconds: Number(timeoutValue),
+				maxRetries: Number(retriesValue),
+			}),
+		onSuccess,
+		onError,
+	});
+
+	const fallbackMutation = useMutation({
+		mutationFn: (modelIds: string[]) => commands.setLlmFallbackModels(modelIds),
+		onSuccess,
+		onError,
+	});
+
+	const titleOf = (modelId: string) =>
+		llmModels.find((model) => model.id === modelId)?.title ?? modelId;
+	const available = llmModels.filter(
+		(model) =>
+			model.id !== store?.activeLlmModel && !fallbacks.includes(model.id),
+	);
+
+	const moveUp = (index: number) => {
+		const next = [...fallbacks];
+		[next[index - 1], next[index]] = [next[index], next[index - 1]];
+		fallbackMutation.mutate(next);
+	};
+
+	return (
+		<div className="flex flex-col gap-3 rounded-lg border p-4">
+			<div className="flex flex-wrap items-center gap-3">
+				<Label className="w-20 shrink-0">请求超时</Label>
+				<Input
+					type="number"
+					min={5}
+					max={300}
+					className="w-24"
+					value={timeoutValue}
+					onChange={(event) => setTimeoutValue(event.target.value)}
+				/>
+				<span className="text-xs text-muted-foreground">秒</span>
+				<Label className="ml-4 shrink-0">失败重试</Label>
+				<Input
+					type="number"
+					min={0}
+					max={5}
+					className="w-20"
+					value={retriesValue}
+					onChange={(event) => setRetriesValue(event.target.value)}
+				/>
+				<span className="text-xs text-muted-foreground">次</span>
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="ml-auto"
+					disabled={
+						requestMutation.isPending ||
+						(timeoutValue === String(timeoutSeconds) &&
+							retriesValue === String(maxRetries))
+					}
+					onClick={() => requestMutation.mutate()}
+				>
+					保存
+				</Button>
+			</div>
+			<div className="flex flex-col gap-2">
+				<Label>备用模型</Label>
+				<p className="text-xs text-muted-foreground">
+					当前模型超时或出错时按顺序尝试，全部失败则直接粘贴识别原文。
+				</p>
+				{fallbacks.map((modelId, index) => (
+					<div
+						key={modelId}
+						className="flex items-center justify-between rounded-md bg-muted/40 px-3 py-1.5 text-sm"
+					>
+						<span>
+							{index + 1}. {titleOf(modelId)}
+						</span>
+						<div className="flex items-center gap-1">
+							{index > 0 && (
+								<Button
+									type="button"
+									size="icon-sm"
+									variant="ghost"
+									aria-label="上移"
+									onClick={() => moveUp(index)}
+								>
+									<ArrowUp className="size-4" />
+								</Button>
+							)}
+							<Button
+								type="button"
+								size="icon-sm"
+								variant="ghost"
+								aria-label="移除"
+								onClick={() =>
+									fallbackMutation.mutate(
+										fallbacks.filter((item) => item !== modelId),
+									)
+								}
+							>
+								<X className="size-4" />
+							</Button>
+						</div>
+					</div>
+				))}
+				{available.length > 0 && (
+					<Select
+						value=""
+						onChange={(event) =>
+							event.target.value &&
+							fallbackMutation.mutate([...fallbacks, event.target.value])
+						}
+					>
+						<option value="">添加备用模型...</option>
+						{available.map((model) => (
+							<option key={model.id} value={model.id}>
+								{model.title}
+							</option>
+						))}
+					</Select>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
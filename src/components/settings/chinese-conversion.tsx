@@ -0,0 +1,137 @@
+import { useMutation } from "@tanstack/react-query";
+import { useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import {
+	type ChineseConversionSettings,
+	type ChineseVariant,
+	commands,
+} from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+const VARIANT_OPTIONS: { value: ChineseVariant; label: string }[] = [
+	{ value: "unchanged", label: "不转换" },
+	{ value: "simplified", label: "简体中文" },
+	{ value: "traditional", label: "繁体中文" },
+	{ value: "taiwan", label: "繁体中文（台湾）" },
+	{ value: "hongKong", label: "繁体中文（香港）" },
+];
+
+export function ChineseConversionPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const conversion: ChineseConversionSettings =
+		settingsQuery.data?.chineseConversion ?? {};
+	const variant = conversion.variant ?? "unchanged";
+	const enabled = variant !== "unchanged";
+
+	const [sample, setSample] = useState("");
+	const [preview, setPreview] = useState<string | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<ChineseConversionSettings>) =>
+			commands.setChineseConversion({ ...conversion, ...patch }),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const previewMutation = useMutation({
+		mutationFn: () => commands.previewChineseConversion(conversion, sample),
+		onSuccess: (data) => {
+			setError(null);
+			setPreview(data);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">简繁转换</div>
+					<p className="text-xs text-muted-foreground">
+						粘贴前使用内置词典把输出转换为简体或繁体，离线完成，不依赖文本模型。
+					</p>
+				</div>
+				<Select
+					wrapperClassName="w-44 shrink-0"
+					value={variant}
+					onChange={(event) =>
+						saveMutation.mutate({
+							variant: event.target.value as ChineseVariant,
+						})
+					}
+				>
+					{VARIANT_OPTIONS.map((option) => (
+						<option key={option.value} value={option.value}>
+							{option.label}
+						</option>
+					))}
+				</Select>
+			</div>
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<Label>使用台湾常用词汇</Label>
+					<p className="text-xs text-muted-foreground">
+						例如“软件”转为“軟體”、“鼠标”转为“滑鼠”。
+					</p>
+				</div>
+				<Switch
+					checked={conversion.taiwanPhrases ?? true}
+					disabled={variant !== "taiwan"}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ taiwanPhrases: checked })
+					}
+				/>
+			</div>
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<Label>历史记录也保存转换后的文本</Label>
+					<p className="text-xs text-muted-foreground">
+						关闭时只转换粘贴的文本，历史记录保留识别原文。
+					</p>
+				</div>
+				<Switch
+					checked={conversion.applyToHistory ?? false}
+					disabled={!enabled}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ applyToHistory: checked })
+					}
+				/>
+			</div>
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>测试</Label>
+				<div className="flex items-center gap-3">
+					<Input
+						value={sample}
+						placeholder="例如：这个软件的鼠标设置在后台里面"
+						onChange={(event) => setSample(event.target.value)}
+					/>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={
+							!enabled || !sample.trim() || previewMutation.isPending
+						}
+						onClick={() => previewMutation.mutate()}
+					>
+						预览
+					</Button>
+				</div>
+				{preview !== null && (
+					<div className="rounded-md bg-muted px-3 py-2 text-xs">
+						<p className="whitespace-pre-wrap break-all">{preview}</p>
+					</div>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
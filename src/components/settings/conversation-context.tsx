@@ -0,0 +1,103 @@
+import { useMutation } from "@tanstack/react-query";
+import { useEffect, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { commands, type ConversationContextSettings } from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+type NumberField = "maxEntries" | "windowMinutes" | "tokenBudget";
+
+const NUMBER_FIELDS: { field: NumberField; label: string; unit: string }[] = [
+	{ field: "maxEntries", label: "记录条数", unit: "条" },
+	{ field: "windowMinutes", label: "时间窗口", unit: "分钟内" },
+	{ field: "tokenBudget", label: "上下文预算", unit: "字" },
+];
+
+export function ConversationContextPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const context: Required<ConversationContextSettings> = {
+		enabled: false,
+		maxEntries: 3,
+		windowMinutes: 10,
+		tokenBudget: 800,
+		...settingsQuery.data?.conversationContext,
+	};
+	const [drafts, setDrafts] = useState<Record<NumberField, string>>({
+		maxEntries: "",
+		windowMinutes: "",
+		tokenBudget: "",
+	});
+	const [error, setError] = useState<string | null>(null);
+
+	useEffect(() => {
+		setDrafts({
+			maxEntries: String(context.maxEntries),
+			windowMinutes: String(context.windowMinutes),
+			tokenBudget: String(context.tokenBudget),
+		});
+	}, [context.maxEntries, context.windowMinutes, context.tokenBudget]);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<ConversationContextSettings>) =>
+			commands.setConversationContext({ ...context, ...patch }),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: async (error) => {
+			setError(String(error));
+			await settingsQuery.refetch();
+		},
+	});
+
+	const commitField = (field: NumberField) => {
+		const value = Number.parseInt(drafts[field], 10);
+		if (Number.isNaN(value) || value === context[field]) {
+			setDrafts((prev) => ({ ...prev, [field]: String(context[field]) }));
+			return;
+		}
+		saveMutation.mutate({ [field]: value });
+	};
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">参考最近的输入</div>
+					<p className="text-xs text-muted-foreground">
+						润色时附带同一应用中最近几条同类记录（无法识别当前应用的平台上按时间窗口内的同类记录），让连续口述的人称、术语与格式保持一致。这些内容只作参考，不会出现在结果中。
+					</p>
+				</div>
+				<Switch
+					checked={context.enabled}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ enabled: checked })
+					}
+				/>
+			</div>
+			<div className="flex flex-wrap gap-x-6 gap-y-3">
+				{NUMBER_FIELDS.map((item) => (
+					<div key={item.field} className="flex items-center gap-2">
+						<Label className="shrink-0">{item.label}</Label>
+						<Input
+							type="number"
+							className="w-20"
+							value={drafts[item.field]}
+							disabled={!context.enabled}
+							onChange={(event) =>
+								setDrafts((prev) => ({
+									...prev,
+									[item.field]: event.target.value,
+								}))
+							}
+							onBlur={() => commitField(item.field)}
+						/>
+						<span className="text-xs text-muted-foreground">{item.unit}</span>
+					</div>
+				))}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
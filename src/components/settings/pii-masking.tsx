@@ -0,0 +1,173 @@
+import { useMutation } from "@tanstack/react-query";
+import { useEffect, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Textarea } from "~/components/ui/textarea";
+import {
+	commands,
+	type PiiMaskingSettings,
+	type PiiPattern,
+	type PiiRule,
+} from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+const RULE_OPTIONS: { rule: PiiRule; label: string }[] = [
+	{ rule: "mobilePhone", label: "手机号" },
+	{ rule: "landline", label: "固定电话" },
+	{ rule: "idCard", label: "身份证号" },
+	{ rule: "bankCard", label: "银行卡号" },
+	{ rule: "email", label: "邮箱" },
+];
+
+const ALL_RULES = RULE_OPTIONS.map((option) => option.rule);
+
+const formatPatterns = (patterns: PiiPattern[]) =>
+	patterns.map((item) => `${item.name}: ${item.pattern}`).join("\n");
+
+/** 每行一条“名称: 正则”，正则本身可以包含冒号 */
+const parsePatterns = (value: string): PiiPattern[] =>
+	value
+		.split("\n")
+		.map((line) => line.trim())
+		.filter(Boolean)
+		.map((line) => {
+			const match = line.match(/^([^:：]*)[:：]\s*(.+)$/);
+			return match
+				? { name: match[1].trim() || "自定义", pattern: match[2] }
+				: { name: "自定义", pattern: line };
+		});
+
+export function PiiMasking() {
+	const settingsQuery = settingsStore.useQuery();
+	const saved: PiiMaskingSettings = settingsQuery.data?.piiMasking ?? {};
+	const enabled = saved.enabled ?? false;
+	const builtinRules = saved.builtinRules ?? ALL_RULES;
+	const savedPatterns = formatPatterns(saved.customPatterns ?? []);
+
+	const [patterns, setPatterns] = useState("");
+	const [sample, setSample] = useState("");
+	const [preview, setPreview] = useState<string | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	useEffect(() => {
+		setPatterns(savedPatterns);
+	}, [savedPatterns]);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<PiiMaskingSettings>) =>
+			commands.setPiiMasking({
+				enabled,
+				builtinRules,
+				customPatterns: saved.customPatterns ?? [],
+				...patch,
+			}),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const previewMutation = useMutation({
+		mutationFn: () =>
+			commands.previewPiiMasking(
+				{ enabled, builtinRules, customPatterns: parsePatterns(patterns) },
+				sample,
+			),
+		onSuccess: (data) => {
+			setError(null);
+			setPreview(data);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const toggleRule = (rule: PiiRule, checked: boolean) => {
+		saveMutation.mutate({
+			builtinRules: checked
+				? [...builtinRules, rule]
+				: builtinRules.filter((item) => item !== rule),
+		});
+	};
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">发送前隐藏个人信息</div>
+					<p className="text-xs text-muted-foreground">
+						调用云端文本模型前，将识别到的个人信息替换为占位符，润色完成后再还原。发往本机地址（localhost、127.0.0.1）的请求不受影响。
+					</p>
+				</div>
+				<Switch
+					checked={enabled}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ enabled: checked })
+					}
+				/>
+			</div>
+			<div className="flex flex-wrap gap-x-6 gap-y-3">
+				{RULE_OPTIONS.map((option) => (
+					<div key={option.rule} className="flex items-center gap-2">
+						<Switch
+							checked={builtinRules.includes(option.rule)}
+							disabled={!enabled}
+							onCheckedChange={(checked) => toggleRule(option.rule, checked)}
+						/>
+						<Label>{option.label}</Label>
+					</div>
+				))}
+			</div>
+			<div className="flex flex-col gap-2">
+				<Label>自定义规则</Label>
+				<Textarea
+					value={patterns}
+					disabled={!enabled}
+					placeholder={"每行一条，格式为“名称: 正则表达式”，例如\n工号: MY\\d{6}"}
+					onChange={(event) => setPatterns(event.target.value)}
+				/>
+				<div className="flex justify-end">
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={
+							!enabled || saveMutation.isPending || patterns === savedPatterns
+						}
+						onClick={() =>
+							saveMutation.mutate({ customPatterns: parsePatterns(patterns) })
+						}
+					>
+						保存规则
+					</Button>
+				</div>
+			</div>
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>测试</Label>
+				<div className="flex items-center gap-3">
+					<Input
+						value={sample}
+						placeholder="输入一段文字，查看发送给模型的内容"
+						onChange={(event) => setSample(event.target.value)}
+					/>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={!sample.trim() || previewMutation.isPending}
+						onClick={() => previewMutation.mutate()}
+					>
+						预览
+					</Button>
+				</div>
+				{preview !== null && (
+					<p className="rounded-md bg-muted px-3 py-2 text-xs break-all">
+						{preview}
+					</p>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
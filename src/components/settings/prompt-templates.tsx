@@ -0,0 +1,302 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { Pencil, Plus, RotateCcw, Trash2 } from "lucide-react";
+import { useEffect, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import { Textarea } from "~/components/ui/textarea";
+import {
+	commands,
+	type PromptTemplate,
+	type PromptTemplateInput,
+} from "~/lib/tauri";
+
+const EMPTY_TEMPLATE: PromptTemplateInput = {
+	name: "",
+	systemPrompt: "",
+	temperature: null,
+	modelOverride: null,
+	allowRewrite: false,
+};
+
+/** 与后端一致，这些内置模板不能删除 */
+const REQUIRED_TEMPLATE_IDS = ["polish", "translate", "edit-selection"];
+
+type EditorState = { id: string | null; value: PromptTemplateInput } | null;
+
+const PROMPT_VARIABLES_HINT =
+	"可用变量：{date} 当天日期、{kind} 录音类型、{source_app} 前台应用、{glossary} 专有名词表、{source_language} 与 {target_language} 翻译语言";
+
+export function PromptTemplates() {
+	const queryClient = useQueryClient();
+	const promptsQuery = useQuery({
+		queryKey: ["prompts-store"],
+		queryFn: () => commands.getPromptsStore(),
+	});
+	const supportedModelsQuery = useQuery({
+		queryKey: ["supported-models"],
+		queryFn: () => commands.getSupportedModels(),
+	});
+	const [editor, setEditor] = useState<EditorState>(null);
+	const [error, setError] = useState<string | null>(null);
+	const [glossary, setGlossary] = useState("");
+
+	const templates = promptsQuery.data?.templates ?? [];
+	const llmModels = supportedModelsQuery.data?.llmModels ?? [];
+	const savedGlossary = (promptsQuery.data?.glossary ?? []).join("\n");
+
+	useEffect(() => {
+		setGlossary(savedGlossary);
+	}, [savedGlossary]);
+
+	const saveMutation = useMutation({
+		mutationFn: ({ id, value }: NonNullable<EditorState>) =>
+			id
+				? commands.updatePromptTemplate(id, value)
+				: commands.createPromptTemplate(value),
+		onSuccess: (data) => {
+			queryClient.setQueryData(["prompts-store"], data);
+			setEditor(null);
+			setError(null);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const deleteMutation = useMutation({
+		mutationFn: (id: string) => commands.deletePromptTemplate(id),
+		onSuccess: (data) => queryClient.setQueryData(["prompts-store"], data),
+	});
+
+	const resetMutation = useMutation({
+		mutationFn: (id: string) => commands.resetPrompt(id),
+		onSuccess: (data) => queryClient.setQueryData(["prompts-store"], data),
+	});
+
+	const glossaryMutation = useMutation({
+		mutationFn: (value: string) =>
+			commands.setPromptGlossary(value.split(/[\n,，、]/)),
+		onSuccess: (data) => queryClient.setQueryData(["prompts-store"], data),
+	});
+
+	const startEditing = (template: PromptTemplate | null) => {
+		setError(null);
+		setEditor(
+			template
+				? {
+						id: template.id,
+						value: {
+							name: template.name,
+							systemPrompt: template.systemPrompt,
+							temperature: template.temperature ?? null,
+							modelOverride: template.modelOverride ?? null,
+							allowRewrite: template.allowRewrite ?? false,
+						},
+					}
+				: { id: null, value: EMPTY_TEMPLATE },
+		);
+	};
+
+	const editingBuiltin = templates.some(
+		(template) => template.id === editor?.id && template.builtin,
+	);
+
+	const updateEditor = (patch: Partial<PromptTemplateInput>) => {
+		setEditor((prev) =>
+			prev ? { ...prev, value: { ...prev.value, ...patch } } : prev,
+		);
+	};
+
+	return (
+		<div className="flex flex-col gap-3">
+			<ul className="divide-y divide-border">
+				{templates.map((template) => (
+					<li
+						key={template.id}
+						className="flex items-center justify-between gap-4 py-3"
+					>
+						<div className="min-w-0 space-y-1">
+							<div className="text-sm font-medium">
+								{template.name}
+								{template.builtin && template.customized && (
+									<span className="ml-2 text-xs font-normal text-muted-foreground">
+										已修改
+									</span>
+								)}
+							</div>
+							<p className="truncate text-xs text-muted-foreground">
+								{template.systemPrompt.split("\n")[0]}
+							</p>
+						</div>
+						<div className="flex shrink-0 items-center gap-1">
+							{template.builtin && template.customized && (
+								<Button
+									type="button"
+									size="icon-sm"
+									variant="ghost"
+									aria-label="恢复默认提示词"
+									onClick={() => resetMutation.mutate(template.id)}
+								>
+									<RotateCcw className="size-4" />
+								</Button>
+							)}
+							<Button
+								type="button"
+								size="icon-sm"
+								variant="ghost"
+								aria-label="编辑模板"
+								onClick={() => startEditing(template)}
+							>
+								<Pencil className="size-4" />
+							</Button>
+							{!REQUIRED_TEMPLATE_IDS.includes(template.id) && (
+								<Button
+									type="button"
+									size="icon-sm"
+									variant="ghost"
+									aria-label="删除模板"
+									onClick={() => deleteMutation.mutate(template.id)}
+								>
+									<Trash2 className="size-4" />
+								</Button>
+							)}
+						</div>
+					</li>
+				))}
+			</ul>
+			{editor ? (
+				<div className="flex flex-col gap-3 rounded-lg border p-4">
+					<div className="flex items-center gap-3">
+						<Label className="w-20 shrink-0">名称</Label>
+						<Input
+							value={editor.value.name}
+							onChange={(event) => updateEditor({ name: event.target.value })}
+						/>
+					</div>
+					<div className="flex items-start gap-3">
+						<Label className="w-20 shrink-0 pt-2">系统提示词</Label>
+						<div className="flex w-full flex-col gap-1">
+							<Textarea
+								className="min-h-32"
+								value={editor.value.systemPrompt}
+								onChange={(event) =>
+									updateEditor({ systemPrompt: event.target.value })
+								}
+							/>
+							<p className="text-xs text-muted-foreground">
+								{PROMPT_VARIABLES_HINT}
+							</p>
+						</div>
+					</div>
+					<div className="flex items-center gap-3">
+						<Label className="w-20 shrink-0">温度</Label>
+						<Input
+							type="number"
+							min={0}
+							max={2}
+							step={0.1}
+							placeholder="使用模型默认值"
+							value={editor.value.temperature ?? ""}
+							onChange={(event) =>
+								updateEditor({
+									temperature:
+										event.target.value === ""
+											? null
+											: Number(event.target.value),
+								})
+							}
+						/>
+					</div>
+					<div className="flex items-center gap-3">
+						<Label className="w-20 shrink-0">文本模型</Label>
+						<Select
+							value={editor.value.modelOverride ?? ""}
+							onChange={(event) =>
+								updateEditor({ modelOverride: event.target.value || null })
+							}
+						>
+							<option value="">跟随当前模型</option>
+							{llmModels.map((model) => (
+								<option key={model.id} value={model.id}>
+									{model.title}
+								</option>
+							))}
+						</Select>
+					</div>
+					{!editingBuiltin && (
+						<div className="flex items-center gap-3">
+							<Label className="w-20 shrink-0">允许改写</Label>
+							<Switch
+								checked={editor.value.allowRewrite ?? false}
+								onCheckedChange={(checked) =>
+									updateEditor({ allowRewrite: checked })
+								}
+							/>
+							<p className="text-xs text-muted-foreground">
+								翻译、总结等模式请开启，否则结果偏离原文时会回退为识别原文
+							</p>
+						</div>
+					)}
+					<div className="flex items-center justify-end gap-3">
+						{error && <p className="text-xs text-destructive">{error}</p>}
+						<Button
+							type="button"
+							size="sm"
+							variant="outline"
+							className="text-muted-foreground hover:text-foreground"
+							onClick={() => setEditor(null)}
+						>
+							取消
+						</Button>
+						<Button
+							type="button"
+							size="sm"
+							disabled={
+								saveMutation.isPending ||
+								!editor.value.name.trim() ||
+								!editor.value.systemPrompt.trim()
+							}
+							onClick={() => saveMutation.mutate(editor)}
+						>
+							{saveMutation.isPending ? "保存中..." : "保存"}
+						</Button>
+					</div>
+				</div>
+			) : (
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="self-start"
+					onClick={() => startEditing(null)}
+				>
+					<Plus className="size-4" />
+					新建模板
+				</Button>
+			)}
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>专有名词表</Label>
+				<Textarea
+					value={glossary}
+					placeholder="每行一个，例如人名、产品名或行业术语"
+					onChange={(event) => setGlossary(event.target.value)}
+				/>
+				<div className="flex items-center justify-between gap-3">
+					<p className="text-xs text-muted-foreground">
+						在提示词中使用 {"{glossary}"} 引用这些名词。
+					</p>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={glossaryMutation.isPending || glossary === savedGlossary}
+						onClick={() => glossaryMutation.mutate(glossary)}
+					>
+						保存名词表
+					</Button>
+				</div>
+			</div>
+		</div>
+	);
+}
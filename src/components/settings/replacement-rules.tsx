@@ -0,0 +1,371 @@
+import { useMutation } from "@tanstack/react-query";
+import {
+	ArrowDown,
+	ArrowUp,
+	Download,
+	Plus,
+	Trash2,
+	Upload,
+} from "lucide-react";
+import { type ChangeEvent, useEffect, useRef, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import {
+	commands,
+	type HistoryKind,
+	type ReplacementMatch,
+	type ReplacementPreview,
+	type ReplacementRule,
+	type ReplacementStage,
+} from "~/lib/tauri";
+import { cn } from "~/lib/utils";
+import { settingsStore } from "~/store";
+
+const KIND_OPTIONS: { kind: HistoryKind; label: string }[] = [
+	{ kind: "dictation", label: "听写" },
+	{ kind: "diary", label: "日记" },
+	{ kind: "translation", label: "翻译" },
+	{ kind: "edit", label: "编辑" },
+];
+
+const createRule = (): ReplacementRule => ({
+	id: crypto.randomUUID(),
+	enabled: true,
+	matchType: "literal",
+	pattern: "",
+	replacement: "",
+	caseInsensitive: false,
+	scopes: [],
+	stage: "afterPolish",
+});
+
+export function ReplacementRulesPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const saved = settingsQuery.data?.replacementRules ?? [];
+	const savedKey = JSON.stringify(saved);
+
+	const [drafts, setDrafts] = useState<ReplacementRule[]>([]);
+	const [sample, setSample] = useState("");
+	const [sampleKind, setSampleKind] = useState<HistoryKind | "">("");
+	const [preview, setPreview] = useState<ReplacementPreview | null>(null);
+	const [error, setError] = useState<string | null>(null);
+	const fileInputRef = useRef<HTMLInputElement>(null);
+
+	useEffect(() => {
+		setDrafts(JSON.parse(savedKey));
+	}, [savedKey]);
+
+	const onSaved = async () => {
+		setError(null);
+		await settingsQuery.refetch();
+	};
+
+	const saveMutation = useMutation({
+		mutationFn: () => commands.setReplacementRules(drafts),
+		onSuccess: onSaved,
+		onError: (error) => setError(String(error)),
+	});
+
+	const importMutation = useMutation({
+		mutationFn: (json: string) => commands.importReplacementRules(json, false),
+		onSuccess: async (rules) => {
+			await onSaved();
+			await commands.showNotification(
+				`已导入，当前共 ${rules.length} 条规则`,
+				"info",
+				null,
+			);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const exportMutation = useMutation({
+		mutationFn: () => commands.exportReplacementRules(),
+		onSuccess: async (path) => {
+			await commands.showNotification(`已导出到 ${path}`, "info", null);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const previewMutation = useMutation({
+		mutationFn: () =>
+			commands.previewReplacementRules(drafts, sample, sampleKind || null),
+		onSuccess: (data) => {
+			setError(null);
+			setPreview(data);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const updateRule = (index: number, patch: Partial<ReplacementRule>) => {
+		setDrafts((prev) =>
+			prev.map((rule, current) =>
+				current === index ? { ...rule, ...patch } : rule,
+			),
+		);
+	};
+
+	const moveRule = (index: number, offset: number) => {
+		setDrafts((prev) => {
+			const target = index + offset;
+			if (target < 0 || target >= prev.length) {
+				return prev;
+			}
+			const next = [...prev];
+			[next[index], next[target]] = [next[target], next[index]];
+			return next;
+		});
+	};
+
+	const toggleScope = (index: number, kind: HistoryKind) => {
+		const scopes = drafts[index].scopes ?? [];
+		updateRule(index, {
+			scopes: scopes.includes(kind)
+				? scopes.filter((item) => item !== kind)
+				: [...scopes, kind],
+		});
+	};
+
+	const handleImport = async (event: ChangeEvent<HTMLInputElement>) => {
+		const file = event.target.files?.[0];
+		event.target.value = "";
+		if (file) {
+			importMutation.mutate(await file.text());
+		}
+	};
+
+	const dirty = JSON.stringify(drafts) !== savedKey;
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="space-y-1">
+				<div className="text-sm font-medium">替换规则</div>
+				<p className="text-xs text-muted-foreground">
+					按顺序执行的固定替换，用于统一产品名写法或展开常用片段，不依赖文本模型。适用范围为空时对所有记录生效。
+				</p>
+			</div>
+			{drafts.length === 0 && (
+				<p className="text-xs text-muted-foreground">
+					还没有规则，例如可以把“github”替换为“GitHub”。
+				</p>
+			)}
+			<ul className="flex flex-col gap-3">
+				{drafts.map((rule, index) => (
+					<li
+						key={rule.id ?? index}
+						className={cn(
+							"flex flex-col gap-2 rounded-md border px-3 py-3",
+							preview?.appliedRuleIds.includes(rule.id ?? "") &&
+								"border-primary",
+						)}
+					>
+						<div className="flex items-center gap-2">
+							<Switch
+								checked={rule.enabled ?? true}
+								onCheckedChange={(checked) =>
+									updateRule(index, { enabled: checked })
+								}
+							/>
+							<Select
+								wrapperClassName="w-24 shrink-0"
+								value={rule.matchType ?? "literal"}
+								onChange={(event) =>
+									updateRule(index, {
+										matchType: event.target.value as ReplacementMatch,
+									})
+								}
+							>
+								<option value="literal">文本</option>
+								<option value="regex">正则</option>
+							</Select>
+							<Input
+								value={rule.pattern}
+								placeholder="查找"
+								onChange={(event) =>
+									updateRule(index, { pattern: event.target.value })
+								}
+							/>
+							<span className="text-muted-foreground">→</span>
+							<Input
+								value={rule.replacement ?? ""}
+								placeholder="替换为"
+								onChange={(event) =>
+									updateRule(index, { replacement: event.target.value })
+								}
+							/>
+							<Button
+								type="button"
+								variant="ghost"
+								size="icon"
+								disabled={index === 0}
+								onClick={() => moveRule(index, -1)}
+							>
+								<ArrowUp className="size-4" />
+							</Button>
+							<Button
+								type="button"
+								variant="ghost"
+								size="icon"
+								disabled={index === drafts.length - 1}
+								onClick={() => moveRule(index, 1)}
+							>
+								<ArrowDown className="size-4" />
+							</Button>
+							<Button
+								type="button"
+								variant="ghost"
+								size="icon"
+								onClick={() =>
+									setDrafts((prev) =>
+										prev.filter((_, current) => current !== index),
+									)
+								}
+							>
+								<Trash2 className="size-4" />
+							</Button>
+						</div>
+						<div className="flex flex-wrap items-center gap-x-4 gap-y-2 text-xs">
+							<Select
+								wrapperClassName="w-28"
+								className="h-7 text-xs"
+								value={rule.stage ?? "afterPolish"}
+								onChange={(event) =>
+									updateRule(index, {
+										stage: event.target.value as ReplacementStage,
+									})
+								}
+							>
+								<option value="afterPolish">润色后</option>
+								<option value="beforePolish">润色前</option>
+							</Select>
+							<div className="flex items-center gap-1">
+								{KIND_OPTIONS.map((option) => (
+									<Button
+										key={option.kind}
+										type="button"
+										size="sm"
+										className="h-7 px-2 text-xs"
+										variant={
+											rule.scopes?.includes(option.kind)
+												? "secondary"
+												: "ghost"
+										}
+										onClick={() => toggleScope(index, option.kind)}
+									>
+										{option.label}
+									</Button>
+								))}
+							</div>
+							<div className="flex items-center gap-2">
+								<Switch
+									checked={rule.caseInsensitive ?? false}
+									onCheckedChange={(checked) =>
+										updateRule(index, { caseInsensitive: checked })
+									}
+								/>
+								<Label className="text-xs">忽略大小写</Label>
+							</div>
+						</div>
+					</li>
+				))}
+			</ul>
+			<div className="flex flex-wrap items-center gap-2">
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="gap-1"
+					onClick={() => setDrafts((prev) => [...prev, createRule()])}
+				>
+					<Plus className="size-4" />
+					添加规则
+				</Button>
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="gap-1"
+					disabled={importMutation.isPending}
+					onClick={() => fileInputRef.current?.click()}
+				>
+					<Upload className="size-4" />
+					导入
+				</Button>
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="gap-1"
+					disabled={exportMutation.isPending || saved.length === 0}
+					onClick={() => exportMutation.mutate()}
+				>
+					<Download className="size-4" />
+					导出
+				</Button>
+				<input
+					ref={fileInputRef}
+					type="file"
+					accept="application/json,.json"
+					className="hidden"
+					onChange={handleImport}
+				/>
+				<Button
+					type="button"
+					size="sm"
+					className="ml-auto"
+					disabled={!dirty || saveMutation.isPending}
+					onClick={() => saveMutation.mutate()}
+				>
+					保存规则
+				</Button>
+			</div>
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>测试</Label>
+				<div className="flex items-center gap-3">
+					<Select
+						wrapperClassName="w-28 shrink-0"
+						value={sampleKind}
+						onChange={(event) =>
+							setSampleKind(event.target.value as HistoryKind | "")
+						}
+					>
+						<option value="">全部类型</option>
+						{KIND_OPTIONS.map((option) => (
+							<option key={option.kind} value={option.kind}>
+								{option.label}
+							</option>
+						))}
+					</Select>
+					<Input
+						value={sample}
+						placeholder="输入一段文字，查看替换后的结果"
+						onChange={(event) => setSample(event.target.value)}
+					/>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={!sample.trim() || previewMutation.isPending}
+						onClick={() => previewMutation.mutate()}
+					>
+						预览
+					</Button>
+				</div>
+				{preview !== null && (
+					<div className="rounded-md bg-muted px-3 py-2 text-xs">
+						<p className="whitespace-pre-wrap break-all">{preview.text}</p>
+						<p className="mt-1 text-muted-foreground">
+							{preview.appliedRuleIds.length > 0
+								? `命中 ${preview.appliedRuleIds.length} 条规则`
+								: "没有规则命中"}
+						</p>
+					</div>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
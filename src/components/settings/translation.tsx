@@ -0,0 +1,101 @@
+import { useMutation } from "@tanstack/react-query";
+import { useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import {
+	commands,
+	type TranslationLanguage,
+	type TranslationSettings,
+} from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+const LANGUAGE_OPTIONS: { value: TranslationLanguage; label: string }[] = [
+	{ value: "chinese", label: "简体中文" },
+	{ value: "traditionalChinese", label: "繁体中文" },
+	{ value: "english", label: "英语" },
+	{ value: "japanese", label: "日语" },
+	{ value: "korean", label: "韩语" },
+	{ value: "french", label: "法语" },
+	{ value: "german", label: "德语" },
+	{ value: "spanish", label: "西班牙语" },
+	{ value: "russian", label: "俄语" },
+];
+
+export function TranslationSettingsPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const translation: Required<TranslationSettings> = {
+		sourceLanguage: "auto",
+		targetLanguage: "english",
+		dictationTranslates: false,
+		...settingsQuery.data?.translation,
+	};
+	const [error, setError] = useState<string | null>(null);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<TranslationSettings>) =>
+			commands.setTranslationSettings({ ...translation, ...patch }),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center gap-3">
+				<Label className="w-20 shrink-0">源语言</Label>
+				<Select
+					wrapperClassName="w-40"
+					value={translation.sourceLanguage}
+					onChange={(event) =>
+						saveMutation.mutate({
+							sourceLanguage: event.target.value as TranslationLanguage,
+						})
+					}
+				>
+					<option value="auto">自动识别</option>
+					{LANGUAGE_OPTIONS.map((option) => (
+						<option key={option.value} value={option.value}>
+							{option.label}
+						</option>
+					))}
+				</Select>
+			</div>
+			<div className="flex items-center gap-3">
+				<Label className="w-20 shrink-0">目标语言</Label>
+				<Select
+					wrapperClassName="w-40"
+					value={translation.targetLanguage}
+					onChange={(event) =>
+						saveMutation.mutate({
+							targetLanguage: event.target.value as TranslationLanguage,
+						})
+					}
+				>
+					{LANGUAGE_OPTIONS.map((option) => (
+						<option key={option.value} value={option.value}>
+							{option.label}
+						</option>
+					))}
+				</Select>
+			</div>
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">语音识别时翻译</div>
+					<p className="text-xs text-muted-foreground">
+						开启后语音识别快捷键也会输出译文，也可以在托盘菜单中切换。
+					</p>
+				</div>
+				<Switch
+					checked={translation.dictationTranslates}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ dictationTranslates: checked })
+					}
+				/>
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
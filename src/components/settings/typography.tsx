@@ -0,0 +1,176 @@
+import { useMutation } from "@tanstack/react-query";
+import { useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import {
+	type CjkLatinSpacing,
+	commands,
+	type PunctuationWidth,
+	type QuoteStyle,
+	type TypographySettings,
+} from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+const WIDTH_OPTIONS: { value: PunctuationWidth; label: string }[] = [
+	{ value: "keep", label: "保持原样" },
+	{ value: "fullWidth", label: "中文使用全角标点" },
+	{ value: "halfWidth", label: "统一为半角标点" },
+];
+
+const SPACING_OPTIONS: { value: CjkLatinSpacing; label: string }[] = [
+	{ value: "keep", label: "保持原样" },
+	{ value: "add", label: "添加空格" },
+	{ value: "remove", label: "去掉空格" },
+];
+
+const QUOTE_OPTIONS: { value: QuoteStyle; label: string }[] = [
+	{ value: "keep", label: "保持原样" },
+	{ value: "curly", label: "“弯引号”" },
+	{ value: "corner", label: "「直角引号」" },
+	{ value: "straight", label: '"直引号"' },
+];
+
+export function TypographyPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const typography: TypographySettings = settingsQuery.data?.typography ?? {};
+	const enabled = typography.enabled ?? false;
+
+	const [sample, setSample] = useState("");
+	const [preview, setPreview] = useState<string | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<TypographySettings>) =>
+			commands.setTypography({ ...typography, ...patch }),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const previewMutation = useMutation({
+		mutationFn: () => commands.previewTypography(typography, sample),
+		onSuccess: (data) => {
+			setError(null);
+			setPreview(data);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">排版规范化</div>
+					<p className="text-xs text-muted-foreground">
+						粘贴前统一标点宽度、中英文间距和引号样式，按固定规则处理，不依赖文本模型。
+					</p>
+				</div>
+				<Switch
+					checked={enabled}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ enabled: checked })
+					}
+				/>
+			</div>
+			<div className="grid grid-cols-2 gap-x-6 gap-y-3">
+				<div className="flex items-center gap-2">
+					<Label className="w-24 shrink-0">标点宽度</Label>
+					<Select
+						wrapperClassName="w-full"
+						value={typography.punctuationWidth ?? "keep"}
+						disabled={!enabled}
+						onChange={(event) =>
+							saveMutation.mutate({
+								punctuationWidth: event.target.value as PunctuationWidth,
+							})
+						}
+					>
+						{WIDTH_OPTIONS.map((option) => (
+							<option key={option.value} value={option.value}>
+								{option.label}
+							</option>
+						))}
+					</Select>
+				</div>
+				<div className="flex items-center gap-2">
+					<Label className="w-24 shrink-0">中英文间距</Label>
+					<Select
+						wrapperClassName="w-full"
+						value={typography.cjkLatinSpacing ?? "keep"}
+						disabled={!enabled}
+						onChange={(event) =>
+							saveMutation.mutate({
+								cjkLatinSpacing: event.target.value as CjkLatinSpacing,
+							})
+						}
+					>
+						{SPACING_OPTIONS.map((option) => (
+							<option key={option.value} value={option.value}>
+								{option.label}
+							</option>
+						))}
+					</Select>
+				</div>
+				<div className="flex items-center gap-2">
+					<Label className="w-24 shrink-0">引号样式</Label>
+					<Select
+						wrapperClassName="w-full"
+						value={typography.quoteStyle ?? "keep"}
+						disabled={!enabled}
+						onChange={(event) =>
+							saveMutation.mutate({
+								quoteStyle: event.target.value as QuoteStyle,
+							})
+						}
+					>
+						{QUOTE_OPTIONS.map((option) => (
+							<option key={option.value} value={option.value}>
+								{option.label}
+							</option>
+						))}
+					</Select>
+				</div>
+				<div className="flex items-center gap-2">
+					<Label className="w-24 shrink-0">去掉行末标点</Label>
+					<Switch
+						checked={typography.stripTrailingPunctuation ?? false}
+						disabled={!enabled}
+						onCheckedChange={(checked) =>
+							saveMutation.mutate({ stripTrailingPunctuation: checked })
+						}
+					/>
+				</div>
+			</div>
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>测试</Label>
+				<div className="flex items-center gap-3">
+					<Input
+						value={sample}
+						placeholder="例如：今天用GitHub提交了代码,明天继续."
+						onChange={(event) => setSample(event.target.value)}
+					/>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={!sample.trim() || previewMutation.isPending}
+						onClick={() => previewMutation.mutate()}
+					>
+						预览
+					</Button>
+				</div>
+				{preview !== null && (
+					<div className="rounded-md bg-muted px-3 py-2 text-xs">
+						<p className="whitespace-pre-wrap break-all">{preview}</p>
+					</div>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
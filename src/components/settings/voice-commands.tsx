@@ -0,0 +1,215 @@
+import { useMutation } from "@tanstack/react-query";
+import { useEffect, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Button } from "~/components/ui/button";
+import { Input } from "~/components/ui/input";
+import { Label } from "~/components/ui/label";
+import { Select } from "~/components/ui/select";
+import {
+	commands,
+	type VoiceCommandAction,
+	type VoiceCommandLanguage,
+	type VoiceCommandOutcome,
+	type VoiceCommandPhrase,
+	type VoiceCommandSettings,
+} from "~/lib/tauri";
+import { settingsStore } from "~/store";
+
+const LANGUAGE_OPTIONS: { value: VoiceCommandLanguage; label: string }[] = [
+	{ value: "chinese", label: "中文" },
+	{ value: "english", label: "英文" },
+];
+
+const ACTION_OPTIONS: { action: VoiceCommandAction; label: string }[] = [
+	{ action: "newLine", label: "换行" },
+	{ action: "newParagraph", label: "新段落" },
+	{ action: "period", label: "句号" },
+	{ action: "comma", label: "逗号" },
+	{ action: "questionMark", label: "问号" },
+	{ action: "exclamationMark", label: "感叹号" },
+	{ action: "deleteLastSentence", label: "删除上一句" },
+	{ action: "send", label: "发送（回车）" },
+];
+
+type Drafts = Record<VoiceCommandAction, string>;
+
+const formatDrafts = (
+	phrases: VoiceCommandPhrase[],
+	language: VoiceCommandLanguage,
+): Drafts =>
+	Object.fromEntries(
+		ACTION_OPTIONS.map(({ action }) => [
+			action,
+			phrases
+				.filter((item) => item.language === language && item.action === action)
+				.map((item) => item.phrase)
+				.join("、"),
+		]),
+	) as Drafts;
+
+/** 同一动作的多个说法用顿号或逗号分隔 */
+const parsePhrases = (value: string) =>
+	value
+		.split(/[、，,]/)
+		.map((item) => item.trim())
+		.filter(Boolean);
+
+export function VoiceCommandsPanel() {
+	const settingsQuery = settingsStore.useQuery();
+	const saved: VoiceCommandSettings = settingsQuery.data?.voiceCommands ?? {};
+	const enabled = saved.enabled ?? false;
+	const phrases = saved.phrases ?? [];
+
+	const [language, setLanguage] = useState<VoiceCommandLanguage>("chinese");
+	const [drafts, setDrafts] = useState<Drafts>(() =>
+		formatDrafts(phrases, language),
+	);
+	const [sample, setSample] = useState("");
+	const [preview, setPreview] = useState<VoiceCommandOutcome | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	const savedKey = JSON.stringify(phrases);
+	useEffect(() => {
+		setDrafts(formatDrafts(JSON.parse(savedKey), language));
+	}, [savedKey, language]);
+
+	const saveMutation = useMutation({
+		mutationFn: (patch: Partial<VoiceCommandSettings>) =>
+			commands.setVoiceCommands({ enabled, phrases, ...patch }),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const resetMutation = useMutation({
+		mutationFn: () => commands.resetVoiceCommands(),
+		onSuccess: async () => {
+			setError(null);
+			await settingsQuery.refetch();
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const previewMutation = useMutation({
+		mutationFn: () =>
+			commands.previewVoiceCommands({ enabled, phrases }, sample),
+		onSuccess: (data) => {
+			setError(null);
+			setPreview(data);
+		},
+		onError: (error) => setError(String(error)),
+	});
+
+	const commitAction = (action: VoiceCommandAction) => {
+		const current = formatDrafts(phrases, language)[action];
+		if (drafts[action] === current) {
+			return;
+		}
+		const others = phrases.filter(
+			(item) => item.language !== language || item.action !== action,
+		);
+		const next = parsePhrases(drafts[action]).map((phrase) => ({
+			language,
+			action,
+			phrase,
+		}));
+		saveMutation.mutate({ phrases: [...others, ...next] });
+	};
+
+	return (
+		<div className="flex flex-col gap-4">
+			<div className="flex items-center justify-between gap-4">
+				<div className="space-y-1">
+					<div className="text-sm font-medium">口述格式指令</div>
+					<p className="text-xs text-muted-foreground">
+						说出“换行”“句号”“删除上一句”等短语时按格式操作处理，不会写入文字，短语前后不需要停顿或标点；末尾说“发送”会在粘贴后按下回车。
+					</p>
+				</div>
+				<Switch
+					checked={enabled}
+					onCheckedChange={(checked) =>
+						saveMutation.mutate({ enabled: checked })
+					}
+				/>
+			</div>
+			<div className="flex items-center gap-3">
+				<Label className="shrink-0">语言</Label>
+				<Select
+					wrapperClassName="w-40"
+					value={language}
+					disabled={!enabled}
+					onChange={(event) =>
+						setLanguage(event.target.value as VoiceCommandLanguage)
+					}
+				>
+					{LANGUAGE_OPTIONS.map((option) => (
+						<option key={option.value} value={option.value}>
+							{option.label}
+						</option>
+					))}
+				</Select>
+				<Button
+					type="button"
+					size="sm"
+					variant="outline"
+					className="ml-auto"
+					disabled={!enabled || resetMutation.isPending}
+					onClick={() => resetMutation.mutate()}
+				>
+					恢复默认短语
+				</Button>
+			</div>
+			<div className="grid grid-cols-2 gap-x-6 gap-y-3">
+				{ACTION_OPTIONS.map((option) => (
+					<div key={option.action} className="flex items-center gap-2">
+						<Label className="w-24 shrink-0">{option.label}</Label>
+						<Input
+							value={drafts[option.action]}
+							disabled={!enabled}
+							placeholder="多个说法用顿号分隔"
+							onChange={(event) =>
+								setDrafts((prev) => ({
+									...prev,
+									[option.action]: event.target.value,
+								}))
+							}
+							onBlur={() => commitAction(option.action)}
+						/>
+					</div>
+				))}
+			</div>
+			<div className="flex flex-col gap-2 border-t pt-4">
+				<Label>测试</Label>
+				<div className="flex items-center gap-3">
+					<Input
+						value={sample}
+						placeholder="例如：第一行，换行，第二行，发送"
+						onChange={(event) => setSample(event.target.value)}
+					/>
+					<Button
+						type="button"
+						size="sm"
+						variant="outline"
+						disabled={!sample.trim() || previewMutation.isPending}
+						onClick={() => previewMutation.mutate()}
+					>
+						预览
+					</Button>
+				</div>
+				{preview !== null && (
+					<div className="rounded-md bg-muted px-3 py-2 text-xs">
+						<p className="whitespace-pre-wrap break-all">{preview.text}</p>
+						{preview.send && (
+							<p className="mt-1 text-muted-foreground">
+								粘贴后按下回车发送
+							</p>
+						)}
+					</div>
+				)}
+			</div>
+			{error && <p className="text-xs text-destructive">{error}</p>}
+		</div>
+	);
+}
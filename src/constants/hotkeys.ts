@@ -15,3 +15,19 @@ export const DEFAULT_DIARY_HOTKEY: Hotkey = {
 	alt: true,
 	shift: true,
 };
+
+export const DEFAULT_TRANSLATION_HOTKEY: Hotkey = {
+	code: "Space",
+	meta: false,
+	ctrl: true,
+	alt: true,
+	shift: false,
+};
+
+export const DEFAULT_EDIT_HOTKEY: Hotkey = {
+	code: "KeyE",
+	meta: false,
+	ctrl: true,
+	alt: true,
+	shift: false,
+};
@@ -13,8 +13,8 @@ export function useCancelDictating() {
 }
 export function useStartDictating() {
 	return useMutation({
-		mutationFn: async () => {
-			await commands.startDictating();
+		mutationFn: async (templateId: string | null = null) => {
+			await commands.startDictating(templateId);
 		},
 		onError: (error) => {
 			console.error("Failed to start dictating:", error);
@@ -23,11 +23,26 @@ async openPermissionSettings(permission: OSPermission) : Promise<void> {
 async setHotkey(action: HotkeyAction, hotkey: Hotkey | null) : Promise<null> {
     return await TAURI_INVOKE("set_hotkey", { action, hotkey });
 },
-async startDictating() : Promise<null> {
-    return await TAURI_INVOKE("start_dictating");
+/**
+ * 开始听写；`templateId` 指定本次使用的润色模式，为空时使用默认模式
+ */
+async startDictating(templateId: string | null) : Promise<null> {
+    return await TAURI_INVOKE("start_dictating", { templateId });
+},
+async startVoiceDiary(templateId: string | null) : Promise<null> {
+    return await TAURI_INVOKE("start_voice_diary", { templateId });
 },
-async startVoiceDiary() : Promise<null> {
-    return await TAURI_INVOKE("start_voice_diary");
+/**
+ * 开始翻译输入；`templateId` 为空时使用内置的翻译模式
+ */
+async startTranslating(templateId: string | null) : Promise<null> {
+    return await TAURI_INVOKE("start_translating", { templateId });
+},
+/**
+ * 开始语音编辑：读取前台应用中选中的文本，录下修改指令后用结果替换选区
+ */
+async startEditingSelection(templateId: string | null) : Promise<null> {
+    return await TAURI_INVOKE("start_editing_selection", { templateId });
 },
 async cancelDictating() : Promise<null> {
     return await TAURI_INVOKE("cancel_dictating");
@@ -65,9 +80,141 @@ async setAutostartEnabled(enabled: boolean) : Promise<null> {
 async setOnboardingCompleted(completed: boolean) : Promise<boolean> {
     return await TAURI_INVOKE("set_onboarding_completed", { completed });
 },
+/**
+ * 更新隐私信息脱敏设置
+ */
+async setPiiMasking(settings: PiiMaskingSettings) : Promise<PiiMaskingSettings> {
+    return await TAURI_INVOKE("set_pii_masking", { settings });
+},
+/**
+ * 预览脱敏效果，用于在设置页测试规则；忽略 `enabled` 开关
+ */
+async previewPiiMasking(settings: PiiMaskingSettings, text: string) : Promise<string> {
+    return await TAURI_INVOKE("preview_pii_masking", { settings, text });
+},
+/**
+ * 更新翻译输入的源语言、目标语言与语音识别快捷键的翻译开关
+ */
+async setTranslationSettings(translation: TranslationSettings) : Promise<TranslationSettings> {
+    return await TAURI_INVOKE("set_translation_settings", { translation });
+},
+/**
+ * 更新润色时附带的对话上下文设置
+ */
+async setConversationContext(context: ConversationContextSettings) : Promise<ConversationContextSettings> {
+    return await TAURI_INVOKE("set_conversation_context", { context });
+},
+/**
+ * 更新口述格式指令设置
+ */
+async setVoiceCommands(voiceCommands: VoiceCommandSettings) : Promise<VoiceCommandSettings> {
+    return await TAURI_INVOKE("set_voice_commands", { voiceCommands });
+},
+/**
+ * 恢复默认的指令短语表，保留开关状态
+ */
+async resetVoiceCommands() : Promise<VoiceCommandSettings> {
+    return await TAURI_INVOKE("reset_voice_commands");
+},
+/**
+ * 预览指令处理效果，用于在设置页测试短语表；忽略 `enabled` 开关
+ */
+async previewVoiceCommands(voiceCommands: VoiceCommandSettings, text: string) : Promise<VoiceCommandOutcome> {
+    return await TAURI_INVOKE("preview_voice_commands", { voiceCommands, text });
+},
+/**
+ * 保存替换规则，列表顺序即执行顺序
+ */
+async setReplacementRules(rules: ReplacementRule[]) : Promise<ReplacementRule[]> {
+    return await TAURI_INVOKE("set_replacement_rules", { rules });
+},
+/**
+ * 用示例文本测试规则，依次执行润色前与润色后的规则；`kind` 为空时忽略适用范围
+ */
+async previewReplacementRules(rules: ReplacementRule[], text: string, kind: HistoryKind | null) : Promise<ReplacementPreview> {
+    return await TAURI_INVOKE("preview_replacement_rules", { rules, text, kind });
+},
+/**
+ * 把替换规则导出为 JSON 文件保存到下载目录，返回文件路径
+ */
+async exportReplacementRules() : Promise<string> {
+    return await TAURI_INVOKE("export_replacement_rules");
+},
+/**
+ * 从 JSON 导入替换规则，`replaceExisting` 为真时覆盖现有规则，否则追加到末尾
+ */
+async importReplacementRules(json: string, replaceExisting: boolean) : Promise<ReplacementRule[]> {
+    return await TAURI_INVOKE("import_replacement_rules", { json, replaceExisting });
+},
+/**
+ * 更新排版规范化设置
+ */
+async setTypography(typography: TypographySettings) : Promise<TypographySettings> {
+    return await TAURI_INVOKE("set_typography", { typography });
+},
+/**
+ * 预览排版效果，用于在设置页测试；忽略 `enabled` 开关
+ */
+async previewTypography(typography: TypographySettings, text: string) : Promise<string> {
+    return await TAURI_INVOKE("preview_typography", { typography, text });
+},
+/**
+ * 更新简繁转换设置
+ */
+async setChineseConversion(chineseConversion: ChineseConversionSettings) : Promise<ChineseConversionSettings> {
+    return await TAURI_INVOKE("set_chinese_conversion", { chineseConversion });
+},
+/**
+ * 预览简繁转换效果，用于在设置页测试
+ */
+async previewChineseConversion(chineseConversion: ChineseConversionSettings, text: string) : Promise<string> {
+    return await TAURI_INVOKE("preview_chinese_conversion", { chineseConversion, text });
+},
 async testLlmApiKey(model: string | null, provider: string | null, apiKey: string | null) : Promise<null> {
     return await TAURI_INVOKE("test_llm_api_key", { model, provider, apiKey });
 },
+async listLocalLlmModels(backend: CustomLlmBackend, serverUrl: string) : Promise<string[]> {
+    return await TAURI_INVOKE("list_local_llm_models", { backend, serverUrl });
+},
+async getPromptsStore() : Promise<PromptsStore> {
+    return await TAURI_INVOKE("get_prompts_store");
+},
+async createPromptTemplate(template: PromptTemplateInput) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("create_prompt_template", { template });
+},
+async updatePromptTemplate(id: string, template: PromptTemplateInput) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("update_prompt_template", { id, template });
+},
+async deletePromptTemplate(id: string) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("delete_prompt_template", { id });
+},
+async setDefaultPromptTemplate(id: string) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("set_default_prompt_template", { id });
+},
+/**
+ * 为快捷键指定润色模式；`templateId` 为空时恢复使用默认模式
+ */
+async setHotkeyPromptTemplate(action: HotkeyAction, templateId: string | null) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("set_hotkey_prompt_template", { action, templateId });
+},
+/**
+ * 读取提示词；`templateId` 为空时读取默认模式
+ */
+async getPrompt(templateId: string | null) : Promise<PromptDetails> {
+    return await TAURI_INVOKE("get_prompt", { templateId });
+},
+async setPrompt(templateId: string | null, prompt: string) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("set_prompt", { templateId, prompt });
+},
+/**
+ * 恢复内置模板的默认提示词，之后会继续跟随版本更新
+ */
+async resetPrompt(templateId: string | null) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("reset_prompt", { templateId });
+},
+async setPromptGlossary(terms: string[]) : Promise<PromptsStore> {
+    return await TAURI_INVOKE("set_prompt_glossary", { terms });
+},
 async getSupportedModels() : Promise<SupportedModels> {
     return await TAURI_INVOKE("get_supported_models");
 },
@@ -80,6 +227,24 @@ async setActiveTextModel(modelId: string) : Promise<ModelsStore> {
 async updateTextModelCredentials(modelId: string, providerId: string, apiKey: string | null) : Promise<ModelsStore> {
     return await TAURI_INVOKE("update_text_model_credentials", { modelId, providerId, apiKey });
 },
+async addCustomLlmProvider(provider: CustomLlmProviderInput) : Promise<ModelsStore> {
+    return await TAURI_INVOKE("add_custom_llm_provider", { provider });
+},
+/**
+ * 更新自定义文本模型；`apiKey` 为空时保留原有密钥
+ */
+async updateCustomLlmProvider(id: string, provider: CustomLlmProviderInput) : Promise<ModelsStore> {
+    return await TAURI_INVOKE("update_custom_llm_provider", { id, provider });
+},
+async deleteCustomLlmProvider(id: string) : Promise<ModelsStore> {
+    return await TAURI_INVOKE("delete_custom_llm_provider", { id });
+},
+async setLlmFallbackModels(modelIds: string[]) : Promise<ModelsStore> {
+    return await TAURI_INVOKE("set_llm_fallback_models", { modelIds });
+},
+async setLlmRequestSettings(settings: LlmRequestSettings) : Promise<ModelsStore> {
+    return await TAURI_INVOKE("set_llm_request_settings", { settings });
+},
 async setActiveAsrModel(modelId: string) : Promise<ModelsStore> {
     return await TAURI_INVOKE("set_active_asr_model", { modelId });
 },
@@ -92,12 +257,52 @@ async getOfflineModelsStatus() : Promise<OfflineModelsStatus> {
 async downloadOfflineModels(modelId: string) : Promise<OfflineModelsStatus> {
     return await TAURI_INVOKE("download_offline_models", { modelId });
 },
+async cancelOfflineModelDownload(modelId: string) : Promise<null> {
+    return await TAURI_INVOKE("cancel_offline_model_download", { modelId });
+},
 async listHistoryEntries(filter: HistoryListFilter | null) : Promise<HistoryEntry[]> {
     return await TAURI_INVOKE("list_history_entries", { filter });
 },
 async addHistoryEntry(entry: NewHistoryEntry) : Promise<HistoryEntry> {
     return await TAURI_INVOKE("add_history_entry", { entry });
 },
+/**
+ * 为尚未生成标题的历史记录补全标题与标签，每次最多处理 50 条，返回成功的条数
+ */
+async backfillHistoryTitles() : Promise<number> {
+    return await TAURI_INVOKE("backfill_history_titles");
+},
+/**
+ * 生成指定日期所在天或周的日记摘要；该周期已有摘要时重新生成并覆盖
+ */
+async generateDiaryDigest(period: DigestPeriod, date: string) : Promise<HistoryEntry> {
+    return await TAURI_INVOKE("generate_diary_digest", { period, date });
+},
+/**
+ * 列出待办事项：未完成的在前，按时间先后排序
+ */
+async listTasks(filter: TaskListFilter | null) : Promise<TaskItem[]> {
+    return await TAURI_INVOKE("list_tasks", { filter });
+},
+/**
+ * 标记待办事项完成或未完成
+ */
+async setTaskDone(id: string, done: boolean) : Promise<TaskItem> {
+    return await TAURI_INVOKE("set_task_done", { id, done });
+},
+/**
+ * 重新提取指定记录中的待办，替换该记录下尚未完成的事项
+ */
+async extractTasks(entryId: string) : Promise<TaskItem[]> {
+    return await TAURI_INVOKE("extract_tasks", { entryId });
+},
+/**
+ * 把待办导出为 iCalendar 文件保存到下载目录，返回文件路径。
+ * 带日期的事项导出为日程，其余导出为待办
+ */
+async exportTasksIcs(includeDone: boolean) : Promise<string> {
+    return await TAURI_INVOKE("export_tasks_ics", { includeDone });
+},
 async deleteHistoryEntry(id: string) : Promise<null> {
     return await TAURI_INVOKE("delete_history_entry", { id });
 },
@@ -117,10 +322,18 @@ async loadHistoryAudio(path: string) : Promise<string> {
 
 export const events = __makeEvents__<{
 onEscapePress: OnEscapePress,
+onHistoryTitled: OnHistoryTitled,
+onOfflineModelDownload: OnOfflineModelDownload,
+onPolishPreview: OnPolishPreview,
+onTasksExtracted: OnTasksExtracted,
 onTranscribingStage: OnTranscribingStage,
 showNotification: ShowNotification
 }>({
 onEscapePress: "on-escape-press",
+onHistoryTitled: "on-history-titled",
+onOfflineModelDownload: "on-offline-model-download",
+onPolishPreview: "on-polish-preview",
+onTasksExtracted: "on-tasks-extracted",
 onTranscribingStage: "on-transcribing-stage",
 showNotification: "show-notification"
 })
@@ -136,33 +349,399 @@ export type AsrModelConfig = { id: string; title: string; offline?: boolean; siz
 export type AsrModelStore = { id: string; modelId?: string; provider: string; appId?: string | null; accessToken?: string | null; offline?: boolean; active?: boolean; totalRequests?: number; totalHours?: number }
 export type AsrProviderConfig = { id: string; name: string; model?: string | null }
 export type AudioState = "idle" | "recording" | "transcribing"
-export type HistoryEntry = { id: string; title: string | null; text: string; kind: HistoryKind; createdAt: string; durationSeconds: number; audioFilePath: string | null; llmModel: string | null; llmVariantId: string | null; asrModel: string | null; asrVariantId: string | null; totalWords: number; totalTokens: number; llmTotalTokens: number | null; sourceApp: string | null; llmPolishStatus: LlmPolishStatus; llmPolishError: string | null }
-export type HistoryKind = "dictation" | "diary"
+export type ChineseConversionSettings = { variant?: ChineseVariant; 
+/**
+ * 转为台湾繁体时同时替换常用词汇，如“软件”转为“軟體”
+ */
+taiwanPhrases?: boolean; 
+/**
+ * 历史记录也保存转换后的文本
+ */
+applyToHistory?: boolean }
+export type ChineseVariant = 
+/**
+ * 不转换
+ */
+"unchanged" | 
+/**
+ * 简体中文
+ */
+"simplified" | 
+/**
+ * 繁体中文（OpenCC 标准字形）
+ */
+"traditional" | 
+/**
+ * 繁体中文（台湾）
+ */
+"taiwan" | 
+/**
+ * 繁体中文（香港）
+ */
+"hongKong"
+export type CjkLatinSpacing = "keep" | 
+/**
+ * 汉字与英文字母、数字之间加空格
+ */
+"add" | 
+/**
+ * 去掉汉字与英文字母、数字之间的空格
+ */
+"remove"
+export type ConversationContextSettings = { enabled?: boolean; 
+/**
+ * 最多附带的记录条数
+ */
+maxEntries?: number; 
+/**
+ * 只附带该时间窗口内的记录，单位为分钟
+ */
+windowMinutes?: number; 
+/**
+ * 上下文的 token 预算，按字符数估算，超出时从最早的记录开始舍弃
+ */
+tokenBudget?: number }
+/**
+ * 自定义文本模型服务的后端类型
+ */
+export type CustomLlmBackend = "openAiCompatible" | "ollama" | "llamaCpp" | "anthropic" | "gemini"
+/**
+ * 用户自定义的文本模型服务，例如公司内部网关或本地 Ollama / llama.cpp
+ */
+export type CustomLlmProvider = { id: string; name: string; backend?: CustomLlmBackend; apiBaseUrl: string; model: string; apiKeyEnv?: string | null; extraHeaders?: { [key in string]: string } }
+export type CustomLlmProviderInput = { name: string; backend?: CustomLlmBackend; apiBaseUrl?: string; model: string; apiKey?: string | null; apiKeyEnv?: string | null; extraHeaders?: { [key in string]: string } }
+/**
+ * 日记摘要覆盖的时间范围
+ */
+export type DigestPeriod = "day" | "week"
+export type HistoryEntry = { id: string; title: string | null; text: string; kind: HistoryKind; createdAt: string; durationSeconds: number; audioFilePath: string | null; llmModel: string | null; llmVariantId: string | null; asrModel: string | null; asrVariantId: string | null; totalWords: number; totalTokens: number; llmTotalTokens: number | null; sourceApp: string | null; llmPolishStatus: LlmPolishStatus; llmPolishError: string | null; 
+/**
+ * 翻译前的识别原文，或语音编辑前选中的文本
+ */
+originalText: string | null; 
+/**
+ * 语音编辑时的口述指令
+ */
+instruction: string | null; 
+/**
+ * 自动生成的标签，与标题一起在保存后由后台任务写入
+ */
+tags: string[]; 
+/**
+ * 日记摘要引用的日记记录 ID
+ */
+sourceIds: string[]; digestPeriod: DigestPeriod | null; 
+/**
+ * 日记摘要覆盖的起始日期（本地时间，`YYYY-MM-DD`），周摘要为当周周一
+ */
+digestDate: string | null }
+export type HistoryKind = "dictation" | "diary" | 
+/**
+ * 翻译输入，`text` 为译文
+ */
+"translation" | 
+/**
+ * 语音编辑选中的文本，`text` 为编辑结果
+ */
+"edit" | 
+/**
+ * 由一天或一周的语音日记生成的摘要
+ */
+"digest"
 export type HistoryListFilter = { kind?: HistoryKind | null; limit?: number | null; offset?: number | null }
 export type HistoryStats = { totalEntries: number; totalWords: number; totalDurationSeconds: number; totalAppsUsed: number }
 export type Hotkey = { code: string; meta: boolean; ctrl: boolean; alt: boolean; shift: boolean }
-export type HotkeyAction = "startDictating" | "startVoiceDiary" | "other"
+export type HotkeyAction = "startDictating" | "startVoiceDiary" | "startTranslating" | "editSelection" | "other"
 export type HotkeysStore = { hotkeys: Partial<{ [key in HotkeyAction]: Hotkey }> }
 export type LlmModelConfig = { id: string; title: string; providers?: LlmProviderConfig[] }
 export type LlmModelStore = { id: string; textModelId?: string; provider: string; apiKey?: string | null; freeTotalRequests?: number; freeTotalTokenUsage?: number; totalRequests?: number; totalTokenUsage?: number; active?: boolean; usageDate?: string | null }
-export type LlmPolishStatus = "success" | "skipped" | "quotaExceeded" | "failed"
-export type LlmProviderConfig = { id: string; name: string; model?: string | null; apiKeyUrl?: string | null; apiBaseUrl?: string | null; apiKeyEnv?: string | null }
-export type ModelsStore = { llmModels?: LlmModelStore[]; activeLlmModel?: string | null; asrModels?: AsrModelStore[]; activeAsrModel?: string | null }
-export type NewHistoryEntry = { id?: string | null; text: string; kind: HistoryKind; title?: string | null; durationSeconds?: number; createdAt?: string | null; audioFilePath?: string | null; llmModel?: string | null; llmVariantId?: string | null; asrModel?: string | null; asrVariantId?: string | null; totalWords?: number | null; totalTokens?: number | null; llmTotalTokens?: number | null; sourceApp?: string | null; llmPolishStatus?: LlmPolishStatus; llmPolishError?: string | null }
+export type LlmPolishStatus = "success" | "skipped" | "quotaExceeded" | "failed" | "authFailed" | "rateLimited" | "timeout" | "networkError" | "badResponse" | "contentFiltered" | 
+/**
+ * 润色结果偏离原文，已回退为识别原文
+ */
+"diverged"
+export type LlmProviderConfig = { id: string; name: string; model?: string | null; apiKeyUrl?: string | null; apiBaseUrl?: string | null; apiKeyEnv?: string | null; extraHeaders?: { [key in string]: string }; 
+/**
+ * 运行在本机/局域网的推理服务，无需 API 密钥，也不计入体验额度
+ */
+local?: boolean; protocol?: LlmProtocol }
+/**
+ * 文本模型接口协议，决定鉴权方式、请求格式与响应解析
+ */
+export type LlmProtocol = 
+/**
+ * OpenAI chat completions 及其兼容接口
+ */
+"openAiCompatible" | 
+/**
+ * Anthropic Messages API
+ */
+"anthropicMessages" | 
+/**
+ * Google Gemini generateContent
+ */
+"geminiGenerateContent"
+/**
+ * 文本模型请求的超时与重试策略
+ */
+export type LlmRequestSettings = { timeoutSeconds?: number; 
+/**
+ * 遇到 429/5xx 时对同一模型的最大重试次数
+ */
+maxRetries?: number }
+export type ModelsStore = { llmModels?: LlmModelStore[]; activeLlmModel?: string | null; asrModels?: AsrModelStore[]; activeAsrModel?: string | null; customLlmProviders?: CustomLlmProvider[]; 
+/**
+ * 当前文本模型失败后依次尝试的备用文本模型 ID
+ */
+llmFallbackModels?: string[]; llmRequest?: LlmRequestSettings }
+export type NewHistoryEntry = { id?: string | null; text: string; kind: HistoryKind; title?: string | null; durationSeconds?: number; createdAt?: string | null; audioFilePath?: string | null; llmModel?: string | null; llmVariantId?: string | null; asrModel?: string | null; asrVariantId?: string | null; totalWords?: number | null; totalTokens?: number | null; llmTotalTokens?: number | null; sourceApp?: string | null; llmPolishStatus?: LlmPolishStatus; llmPolishError?: string | null; originalText?: string | null; instruction?: string | null; tags?: string[]; sourceIds?: string[]; digestPeriod?: DigestPeriod | null; digestDate?: string | null }
 export type NotificationType = "error" | "info"
 export type OSPermission = "microphone" | "accessibility"
 export type OSPermissionStatus = "notNeeded" | "empty" | "granted" | "denied"
 export type OSPermissionsCheck = { microphone: OSPermissionStatus; accessibility: OSPermissionStatus }
 export type OfflineAsrModelStatus = { id: string; title: string; ready: boolean; missingFiles: string[]; installDir: string }
+export type OfflineModelDownloadStage = "started" | "downloading" | "verifying" | "extracting" | "installed" | "failed" | "cancelled"
 export type OfflineModelsStatus = { ready: boolean; missingFiles: string[]; installDir: string; models: OfflineAsrModelStatus[] }
 export type OnEscapePress = null
+/**
+ * 后台任务写回标题与标签后通知前端刷新列表
+ */
+export type OnHistoryTitled = { id: string; title: string; tags: string[] }
+export type OnOfflineModelDownload = { modelId: string; stage: OfflineModelDownloadStage; 
+/**
+ * 字节数用浮点表示，前端按 number 处理，超过 4 GiB 也不会溢出
+ */
+receivedBytes: number; totalBytes: number | null; bytesPerSecond: number | null; etaSeconds: number | null; error: string | null }
+export type OnPolishPreview = { text: string }
+/**
+ * 后台提取到新的待办后通知前端刷新
+ */
+export type OnTasksExtracted = { entryId: string; count: number }
 export type OnTranscribingStage = { stage: TranscribingStage }
-export type SettingsStore = { theme?: AppTheme; llmSystemPrompt?: string | null; autostartEnabled?: boolean; onboardingCompleted?: boolean }
+export type PiiMaskingSettings = { 
+/**
+ * 是否在调用云端文本模型前脱敏，发往本机地址的请求不受影响
+ */
+enabled?: boolean; builtinRules?: PiiRule[]; customPatterns?: PiiPattern[] }
+/**
+ * 用户自定义的识别规则
+ */
+export type PiiPattern = { name: string; 
+/**
+ * Rust regex 语法的正则表达式
+ */
+pattern: string }
+/**
+ * 内置的识别规则
+ */
+export type PiiRule = 
+/**
+ * 中国大陆手机号，支持 +86 前缀与空格、短横线分隔
+ */
+"mobilePhone" | 
+/**
+ * 带区号的固定电话，如 010-12345678、(0755)1234567
+ */
+"landline" | 
+/**
+ * 18 位居民身份证号，校验末位校验码
+ */
+"idCard" | 
+/**
+ * 16 至 19 位银行卡号，校验 Luhn
+ */
+"bankCard" | "email"
+export type PromptDetails = { templateId: string; systemPrompt: string; 
+/**
+ * 内置模板的默认提示词，自定义模板为空
+ */
+defaultPrompt: string | null; customized: boolean }
+/**
+ * 一种润色模式：独立的系统提示词、温度与可选的文本模型
+ */
+export type PromptTemplate = { id: string; name: string; systemPrompt: string; temperature?: number | null; 
+/**
+ * 使用指定的文本模型 ID，为空时使用当前激活的文本模型
+ */
+modelOverride?: string | null; builtin?: boolean; 
+/**
+ * 内置模板的提示词被用户修改过；未修改的内置模板会随版本更新默认提示词
+ */
+customized?: boolean; 
+/**
+ * 翻译、总结等会大幅改写原文的模式，润色结果不做编辑距离与长度校验
+ */
+allowRewrite?: boolean }
+export type PromptTemplateInput = { name: string; systemPrompt: string; temperature?: number | null; modelOverride?: string | null; allowRewrite?: boolean }
+export type PromptsStore = { templates?: PromptTemplate[]; defaultTemplate?: string; 
+/**
+ * 各快捷键对应的润色模式，未设置时使用默认模式
+ */
+hotkeyTemplates?: Partial<{ [key in HotkeyAction]: string }>; 
+/**
+ * 专有名词表，通过 `{glossary}` 注入提示词
+ */
+glossary?: string[]; 
+/**
+ * 用户删除过的内置模板，新版本补充内置模板时不再加回
+ */
+removedBuiltins?: string[] }
+export type PunctuationWidth = "keep" | 
+/**
+ * 中文语境中的半角标点转为全角
+ */
+"fullWidth" | 
+/**
+ * 全角标点转为半角并在其后补空格
+ */
+"halfWidth"
+export type QuoteStyle = "keep" | 
+/**
+ * “弯引号”
+ */
+"curly" | 
+/**
+ * 「直角引号」
+ */
+"corner" | 
+/**
+ * "直引号"
+ */
+"straight"
+export type ReplacementMatch = 
+/**
+ * 按原文匹配；两端为英文字母或数字时只匹配完整单词
+ */
+"literal" | 
+/**
+ * 正则表达式，替换内容中可用 `$1` 引用分组
+ */
+"regex"
+/**
+ * 替换结果与实际生效的规则 ID
+ */
+export type ReplacementPreview = { text: string; appliedRuleIds: string[] }
+export type ReplacementRule = { id?: string; enabled?: boolean; matchType?: ReplacementMatch; pattern: string; replacement?: string; 
+/**
+ * 忽略英文大小写
+ */
+caseInsensitive?: boolean; 
+/**
+ * 适用的记录类型，为空表示全部
+ */
+scopes?: HistoryKind[]; stage?: ReplacementStage }
+export type ReplacementStage = 
+/**
+ * 发送给文本模型前执行，结果会交给模型继续润色
+ */
+"beforePolish" | 
+/**
+ * 润色完成、粘贴前执行，不受模型改写影响
+ */
+"afterPolish"
+export type SettingsStore = { theme?: AppTheme; 
+/**
+ * 已迁移到提示词模板，仅用于读取旧版本数据
+ */
+llmSystemPrompt?: string | null; autostartEnabled?: boolean; onboardingCompleted?: boolean; 
+/**
+ * 调用云端文本模型前的隐私信息脱敏
+ */
+piiMasking?: PiiMaskingSettings; translation?: TranslationSettings; 
+/**
+ * 润色时附带同一应用中最近的记录作为上下文
+ */
+conversationContext?: ConversationContextSettings; 
+/**
+ * 识别结果中的口述格式指令
+ */
+voiceCommands?: VoiceCommandSettings; 
+/**
+ * 文本替换规则，按顺序执行
+ */
+replacementRules?: ReplacementRule[]; 
+/**
+ * 粘贴前的中文排版规范化
+ */
+typography?: TypographySettings; 
+/**
+ * 输出文本的简繁转换
+ */
+chineseConversion?: ChineseConversionSettings }
 export type ShowNotification = { message: string; type: NotificationType }
 export type SupportedModels = { llmModels: LlmModelConfig[]; asrModels: AsrModelConfig[] }
+export type TaskItem = { id: string; 
+/**
+ * 提取来源的历史记录，记录删除时一并删除
+ */
+entryId: string | null; title: string; 
+/**
+ * 截止或发生时间（RFC 3339），为空表示没有日期的待办
+ */
+dueAt: string | null; 
+/**
+ * 只有日期、没有具体时间
+ */
+allDay: boolean; done: boolean; createdAt: string; completedAt: string | null }
+export type TaskListFilter = { 
+/**
+ * 是否包含已完成的事项
+ */
+includeDone?: boolean }
 export type TranscribingStage = "asr" | "polishing"
-export type TranscriptionResult = { text: string; durationMs: number | null; utterances: TranscriptionUtterance[]; llmPolishStatus?: LlmPolishStatus; llmPolishError?: string | null }
+export type TranscriptionResult = { text: string; durationMs: number | null; utterances: TranscriptionUtterance[]; llmPolishStatus?: LlmPolishStatus; llmPolishError?: string | null; 
+/**
+ * 翻译前的识别原文，或语音编辑前选中的文本
+ */
+originalText?: string | null; 
+/**
+ * 语音编辑时识别出的口述指令
+ */
+instruction?: string | null }
 export type TranscriptionUtterance = { text: string; startTime: number; endTime: number }
+export type TranslationLanguage = 
+/**
+ * 由模型自动判断，仅可作为源语言
+ */
+"auto" | "chinese" | "traditionalChinese" | "english" | "japanese" | "korean" | "french" | "german" | "spanish" | "russian"
+export type TranslationSettings = { sourceLanguage?: TranslationLanguage; targetLanguage?: TranslationLanguage; 
+/**
+ * 语音识别快捷键也按翻译输入处理，可在托盘菜单中切换
+ */
+dictationTranslates?: boolean }
+export type TypographySettings = { enabled?: boolean; punctuationWidth?: PunctuationWidth; cjkLatinSpacing?: CjkLatinSpacing; quoteStyle?: QuoteStyle; 
+/**
+ * 去掉每行末尾的句号、逗号等
+ */
+stripTrailingPunctuation?: boolean }
+export type VoiceCommandAction = "newLine" | "newParagraph" | "period" | "comma" | "questionMark" | "exclamationMark" | "deleteLastSentence" | 
+/**
+ * 只在整段话末尾生效：粘贴后模拟回车
+ */
+"send"
+export type VoiceCommandLanguage = "chinese" | "english"
+/**
+ * 指令处理后的文本，以及是否需要在粘贴后模拟回车
+ */
+export type VoiceCommandOutcome = { text: string; send: boolean; 
+/**
+ * 生效的指令数量
+ */
+applied: number }
+export type VoiceCommandPhrase = { 
+/**
+ * 决定插入全角还是半角标点
+ */
+language: VoiceCommandLanguage; action: VoiceCommandAction; phrase: string }
+export type VoiceCommandSettings = { 
+/**
+ * 默认关闭：开启前口述的“发送”“句号”等词都按普通内容处理，升级后不应改变
+ */
+enabled?: boolean; 
+/**
+ * 指令短语表，同一动作可以有多个说法
+ */
+phrases?: VoiceCommandPhrase[] }
 
 /** tauri-specta globals **/
 
@@ -14,6 +14,7 @@ import { Route as RecordingRouteImport } from './routes/recording'
 import { Route as OnboardingRouteImport } from './routes/onboarding'
 import { Route as NotificationRouteImport } from './routes/notification'
 import { Route as dashboardIndexRouteImport } from './routes/(dashboard)/index'
+import { Route as dashboardTasksRouteImport } from './routes/(dashboard)/tasks'
 import { Route as dashboardSettingsRouteImport } from './routes/(dashboard)/settings'
 import { Route as dashboardModelsRouteImport } from './routes/(dashboard)/models'
 
@@ -42,6 +43,11 @@ const dashboardIndexRoute = dashboardIndexRouteImport.update({
   path: '/',
   getParentRoute: () => rootRouteImport,
 } as any)
+const dashboardTasksRoute = dashboardTasksRouteImport.update({
+  id: '/(dashboard)/tasks',
+  path: '/tasks',
+  getParentRoute: () => rootRouteImport,
+} as any)
 const dashboardSettingsRoute = dashboardSettingsRouteImport.update({
   id: '/(dashboard)/settings',
   path: '/settings',
@@ -60,6 +66,7 @@ export interface FileRoutesByFullPath {
   '/transcribing': typeof TranscribingRoute
   '/models': typeof dashboardModelsRoute
   '/settings': typeof dashboardSettingsRoute
+  '/tasks': typeof dashboardTasksRoute
   '/': typeof dashboardIndexRoute
 }
 export interface FileRoutesByTo {
@@ -69,6 +76,7 @@ export interface FileRoutesByTo {
   '/transcribing': typeof TranscribingRoute
   '/models': typeof dashboardModelsRoute
   '/settings': typeof dashboardSettingsRoute
+  '/tasks': typeof dashboardTasksRoute
   '/': typeof dashboardIndexRoute
 }
 export interface FileRoutesById {
@@ -79,6 +87,7 @@ export interface FileRoutesById {
   '/transcribing': typeof TranscribingRoute
   '/(dashboard)/models': typeof dashboardModelsRoute
   '/(dashboard)/settings': typeof dashboardSettingsRoute
+  '/(dashboard)/tasks': typeof dashboardTasksRoute
   '/(dashboard)/': typeof dashboardIndexRoute
 }
 export interface FileRouteTypes {
@@ -90,6 +99,7 @@ export interface FileRouteTypes {
     | '/transcribing'
     | '/models'
     | '/settings'
+    | '/tasks'
     | '/'
   fileRoutesByTo: FileRoutesByTo
   to:
@@ -99,6 +109,7 @@ export interface FileRouteTypes {
     | '/transcribing'
     | '/models'
     | '/settings'
+    | '/tasks'
     | '/'
   id:
     | '__root__'
@@ -108,6 +119,7 @@ export interface FileRouteTypes {
     | '/transcribing'
     | '/(dashboard)/models'
     | '/(dashboard)/settings'
+    | '/(dashboard)/tasks'
     | '/(dashboard)/'
   fileRoutesById: FileRoutesById
 }
@@ -118,6 +130,7 @@ export interface RootRouteChildren {
   TranscribingRoute: typeof TranscribingRoute
   dashboardModelsRoute: typeof dashboardModelsRoute
   dashboardSettingsRoute: typeof dashboardSettingsRoute
+  dashboardTasksRoute: typeof dashboardTasksRoute
   dashboardIndexRoute: typeof dashboardIndexRoute
 }
 
@@ -158,6 +171,13 @@ declare module '@tanstack/react-router' {
       preLoaderRoute: typeof dashboardIndexRouteImport
       parentRoute: typeof rootRouteImport
     }
+    '/(dashboard)/tasks': {
+      id: '/(dashboard)/tasks'
+      path: '/tasks'
+      fullPath: '/tasks'
+      preLoaderRoute: typeof dashboardTasksRouteImport
+      parentRoute: typeof rootRouteImport
+    }
     '/(dashboard)/settings': {
       id: '/(dashboard)/settings'
       path: '/settings'
@@ -182,6 +202,7 @@ const rootRouteChildren: RootRouteChildren = {
   TranscribingRoute: TranscribingRoute,
   dashboardModelsRoute: dashboardModelsRoute,
   dashboardSettingsRoute: dashboardSettingsRoute,
+  dashboardTasksRoute: dashboardTasksRoute,
   dashboardIndexRoute: dashboardIndexRoute,
 }
 export const routeTree = rootRouteImport
@@ -1,12 +1,19 @@
 import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { createFileRoute, Link } from "@tanstack/react-router";
+import { listen } from "@tauri-apps/api/event";
 import {
+	BookOpenText,
 	CalendarDays,
 	Clock4,
+	Languages,
 	LayoutGrid,
+	ListTodo,
 	Mic,
 	Notebook,
+	PencilLine,
 	Play,
+	RefreshCw,
+	Sparkles,
 	StopCircle,
 	Trash2,
 } from "lucide-react";
@@ -28,12 +35,16 @@ import { ScrollArea } from "~/components/ui/scroll-area";
 import {
 	DEFAULT_DIARY_HOTKEY,
 	DEFAULT_DICTATION_HOTKEY,
+	DEFAULT_EDIT_HOTKEY,
+	DEFAULT_TRANSLATION_HOTKEY,
 } from "~/constants/hotkeys";
 import {
 	commands,
+	type DigestPeriod,
 	type HistoryEntry,
 	type HistoryListFilter,
 	type Hotkey,
+	type OnHistoryTitled,
 } from "~/lib/tauri";
 import { hotkeysStore } from "~/store";
 
@@ -53,6 +64,21 @@ const historyTabs = [
 		label: "日记",
 		icon: Notebook,
 	},
+	{
+		id: "translation",
+		label: "翻译",
+		icon: Languages,
+	},
+	{
+		id: "edit",
+		label: "编辑",
+		icon: PencilLine,
+	},
+	{
+		id: "digest",
+		label: "摘要",
+		icon: BookOpenText,
+	},
 ] as const;
 type HistoryTabId = (typeof historyTabs)[number]["id"];
 type HistoryKindFilter = Exclude<HistoryTabId, "all">;
@@ -70,6 +96,11 @@ function formatDuration(seconds: number) {
 	}
 	return `${minutes} 分 ${remaining} 秒`;
 }
+function formatLocalDate(date: Date) {
+	const month = String(date.getMonth() + 1).padStart(2, "0");
+	const day = String(date.getDate()).padStart(2, "0");
+	return `${date.getFullYear()}-${month}-${day}`;
+}
 function formatRelativeTime(isoString: string) {
 	const date = new Date(isoString);
 	if (Number.isNaN(date.getTime())) {
@@ -170,6 +201,75 @@ function RouteComponent() {
 	const deleteHistoryMutation = useMutation({
 		mutationFn: (id: string) => commands.deleteHistoryEntry(id),
 	});
+	const backfillTitlesMutation = useMutation({
+		mutationFn: () => commands.backfillHistoryTitles(),
+		onSuccess: async (count) => {
+			await queryClient.invalidateQueries({ queryKey: ["history"] });
+			await commands.showNotification(
+				count > 0 ? `已为 ${count} 条记录生成标题` : "所有记录都已有标题",
+				"info",
+				null,
+			);
+		},
+		onError: async (error) => {
+			await commands.showNotification(
+				`生成标题失败: ${String(error)}`,
+				"error",
+				null,
+			);
+		},
+	});
+	const extractTasksMutation = useMutation({
+		mutationFn: (entryId: string) => commands.extractTasks(entryId),
+		onSuccess: async (tasks) => {
+			await queryClient.invalidateQueries({ queryKey: ["tasks"] });
+			await commands.showNotification(
+				tasks.length > 0
+					? `已提取 ${tasks.length} 条待办`
+					: "没有找到待办事项",
+				"info",
+				null,
+			);
+		},
+		onError: async (error) => {
+			await commands.showNotification(
+				`提取待办失败: ${String(error)}`,
+				"error",
+				null,
+			);
+		},
+	});
+	const digestMutation = useMutation({
+		mutationFn: ({ period, date }: { period: DigestPeriod; date: string }) =>
+			commands.generateDiaryDigest(period, date),
+		onSuccess: async () => {
+			await queryClient.invalidateQueries({ queryKey: ["history"] });
+			await commands.showNotification("日记摘要已生成", "info", null);
+		},
+		onError: async (error) => {
+			await commands.showNotification(
+				`生成摘要失败: ${String(error)}`,
+				"error",
+				null,
+			);
+		},
+	});
+	useEffect(() => {
+		let unlisten: (() => void) | null = null;
+		const setupListener = async () => {
+			try {
+				unlisten = await listen<OnHistoryTitled>("on-history-titled", () => {
+					void queryClient.invalidateQueries({ queryKey: ["history"] });
+				});
+			} catch (error) {
+				console.error(error);
+			}
+		};
+		void setupListener();
+		return () => {
+			unlisten?.();
+		};
+	}, [queryClient]);
 	const historyList = historyQuery.data ?? [];
 	const stats = historyStatsQuery.data;
 	const modelsStore = modelsStoreQuery.data;
@@ -323,6 +423,11 @@ function RouteComponent() {
 		hotkeysQuery.data?.hotkeys?.startDictating ?? DEFAULT_DICTATION_HOTKEY;
 	const diaryHotkey =
 		hotkeysQuery.data?.hotkeys?.startVoiceDiary ?? DEFAULT_DIARY_HOTKEY;
+	const translationHotkey =
+		hotkeysQuery.data?.hotkeys?.startTranslating ??
+		DEFAULT_TRANSLATION_HOTKEY;
+	const editHotkey =
+		hotkeysQuery.data?.hotkeys?.editSelection ?? DEFAULT_EDIT_HOTKEY;
 	const renderHotkeyDisplay = useCallback((hotkey?: Hotkey | null) => {
 		const parts = hotkeyToParts(hotkey);
 		if (parts.length === 0) {
@@ -384,6 +489,20 @@ function RouteComponent() {
 					hotkey: diaryHotkey,
 				});
 			}
+			if (activeTab === "all" || activeTab === "translation") {
+				hints.push({
+					id: "translation",
+					text: "使用快捷键将语音翻译后输入",
+					hotkey: translationHotkey,
+				});
+			}
+			if (activeTab === "all" || activeTab === "edit") {
+				hints.push({
+					id: "edit",
+					text: "选中文字后使用快捷键，用语音说出修改要求",
+					hotkey: editHotkey,
+				});
+			}
 
 			return (
 				<div className="rounded-2xl border border-dashed border-border/60 bg-background/40 p-8 text-center">
@@ -411,8 +530,23 @@ function RouteComponent() {
 			const hasAudio = Boolean(record.audioFilePath);
 			return (
 				<Card key={record.id} className="group gap-4 py-4 transition">
-					<CardContent className="px-4 text-base leading-relaxed text-foreground line-clamp-3 whitespace-pre-line">
-						{record.title ?? record.text}
+					<CardContent className="px-4 text-base leading-relaxed text-foreground">
+						{record.title && <p className="font-medium">{record.title}</p>}
+						<p
+							className={`whitespace-pre-line ${record.title ? "line-clamp-2 text-sm text-muted-foreground" : "line-clamp-3"}`}
+						>
+							{record.text}
+						</p>
+						{record.instruction && (
+							<p className="mt-1 text-sm text-muted-foreground line-clamp-1">
+								指令：{record.instruction}
+							</p>
+						)}
+						{record.originalText && (
+							<p className="mt-1 text-sm text-muted-foreground line-clamp-2">
+								{record.originalText}
+							</p>
+						)}
 					</CardContent>
 					<CardFooter className="flex flex-wrap items-center justify-between gap-2 px-4">
 						<div className="flex flex-wrap gap-2 text-xs text-muted-foreground">
@@ -423,15 +557,65 @@ function RouteComponent() {
 								<CalendarDays className="size-3" />
 								{formatRelativeTime(record.createdAt)}
 							</Badge>
-							<Badge
-								variant="secondary"
-								className="gap-1 bg-muted text-foreground"
-							>
-								<Clock4 className="size-3" />
-								{formatDuration(record.durationSeconds)}
-							</Badge>
+							{record.kind === "digest" ? (
+								<Badge
+									variant="secondary"
+									className="gap-1 bg-muted text-foreground"
+								>
+									<Notebook className="size-3" />
+									基于 {record.sourceIds.length} 条日记
+								</Badge>
+							) : (
+								<Badge
+									variant="secondary"
+									className="gap-1 bg-muted text-foreground"
+								>
+									<Clock4 className="size-3" />
+									{formatDuration(record.durationSeconds)}
+								</Badge>
+							)}
+							{record.tags.map((tag) => (
+								<Badge key={tag} variant="outline">
+									#{tag}
+								</Badge>
+							))}
 						</div>
 						<div className="hidden items-center gap-4 text-xs text-muted-foreground group-hover:flex">
+							{record.digestPeriod && record.digestDate && (
+								<Button
+									variant="text"
+									size="xs"
+									className="flex items-center text-xs gap-1 hover:text-foreground"
+									disabled={digestMutation.isPending || isDeleting}
+									onClick={(event) => {
+										event.preventDefault();
+										event.stopPropagation();
+										digestMutation.mutate({
+											period: record.digestPeriod as DigestPeriod,
+											date: record.digestDate as string,
+										});
+									}}
+								>
+									<RefreshCw className="size-3" />
+									重新生成
+								</Button>
+							)}
+							{record.kind === "diary" && (
+								<Button
+									variant="text"
+									size="xs"
+									className="flex items-center text-xs gap-1 hover:text-foreground"
+									disabled={extractTasksMutation.isPending || isDeleting}
+									onClick={(event) => {
+										event.preventDefault();
+										event.stopPropagation();
+										extractTasksMutation.mutate(record.id);
+									}}
+								>
+									<ListTodo className="size-3" />
+									提取待办
+								</Button>
+							)}
 							<Button
 								variant="text"
 								size="xs"
@@ -541,14 +725,64 @@ function RouteComponent() {
 									查看存储在本设备上的转录历史记录
 								</p>
 							</div>
-							<TabsList>
-								{historyTabs.map((tab) => (
-									<TabsTrigger key={tab.id} value={tab.id} className="gap-1.5">
-										<tab.icon className="size-3" />
-										{tab.label}
-									</TabsTrigger>
-								))}
-							</TabsList>
+							<div className="flex flex-wrap items-center gap-3">
+								{(activeTab === "diary" || activeTab === "digest") && (
+									<>
+										<Button
+											variant="text"
+											size="xs"
+											className="gap-1 text-xs text-muted-foreground hover:text-foreground"
+											disabled={digestMutation.isPending}
+											onClick={() =>
+												digestMutation.mutate({
+													period: "day",
+													date: formatLocalDate(new Date()),
+												})
+											}
+										>
+											<BookOpenText className="size-3" />
+											今日摘要
+										</Button>
+										<Button
+											variant="text"
+											size="xs"
+											className="gap-1 text-xs text-muted-foreground hover:text-foreground"
+											disabled={digestMutation.isPending}
+											onClick={() =>
+												digestMutation.mutate({
+													period: "week",
+													date: formatLocalDate(new Date()),
+												})
+											}
+										>
+											<CalendarDays className="size-3" />
+											本周摘要
+										</Button>
+									</>
+								)}
+								<Button
+									variant="text"
+									size="xs"
+									className="gap-1 text-xs text-muted-foreground hover:text-foreground"
+									disabled={backfillTitlesMutation.isPending}
+									onClick={() => backfillTitlesMutation.mutate()}
+								>
+									<Sparkles className="size-3" />
+									{backfillTitlesMutation.isPending ? "生成中..." : "补全标题"}
+								</Button>
+								<TabsList>
+									{historyTabs.map((tab) => (
+										<TabsTrigger
+											key={tab.id}
+											value={tab.id}
+											className="gap-1.5"
+										>
+											<tab.icon className="size-3" />
+											{tab.label}
+										</TabsTrigger>
+									))}
+								</TabsList>
+							</div>
 						</div>
 						<ScrollArea className="h-full w-full pr-4">
 							<TabsContents className="mb-15">
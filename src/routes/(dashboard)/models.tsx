@@ -6,7 +6,9 @@ import {
 	Crosshair,
 	Download,
 	History as HistoryIcon,
+	Plus,
 	SquareArrowOutUpRight,
+	Trash2,
 	WholeWord,
 } from "lucide-react";
 import {
@@ -17,6 +19,8 @@ import {
 	useState,
 } from "react";
 import { RadioGroup } from "~/components/animate-ui/components/radix/radio-group";
+import { CustomLlmProviderForm } from "~/components/custom-llm-provider-form";
+import { LlmFallbackSettings } from "~/components/llm-fallback-settings";
 import { ASRIcon } from "~/components/icons/asr";
 import { DeepSeekIcon } from "~/components/icons/deepseek";
 import { QwenIcon } from "~/components/icons/qwen";
@@ -36,7 +40,12 @@ import { Label } from "~/components/ui/label";
 import { Progress } from "~/components/ui/progress";
 import { Select } from "~/components/ui/select";
 import { PARAFORMER_MODEL_ID } from "~/constants/models";
-import { commands, type LlmModelStore } from "~/lib/tauri";
+import {
+	commands,
+	type CustomLlmProviderInput,
+	type LlmModelStore,
+	type OnOfflineModelDownload,
+} from "~/lib/tauri";
 
 export const Route = createFileRoute("/(dashboard)/models")({
 	component: RouteComponent,
@@ -67,10 +76,6 @@ const asrModelMeta: Record<string, { icon: ReactNode; wrapper: string }> = {
 	},
 };
 
-const FRIENDLY_ERROR_MESSAGES: Record<string, string> = {
-	deepseek: "调用 DeepSeek API 失败，API 密钥错误",
-	qwen: "调用通义千问 API 失败，API 密钥错误",
-};
 type ModelTab = "llm" | "asr";
 
 type TextModelFormValue = {
@@ -89,12 +94,6 @@ type StatusState = Record<string, StatusMessage | null>;
 
 type DownloadProgressState = Record<string, number>;
 
-type OfflineDownloadProgressEvent = {
-	modelId: string;
-	receivedBytes: number;
-	totalBytes: number | null;
-};
-
 const formatAsrDuration = (hours: number) => {
 	if (!hours) {
 		return "0 秒";
@@ -158,6 +157,18 @@ function RouteComponent() {
 	const [savingModel, setSavingModel] = useState<string | null>(null);
 	const [downloadProgress, setDownloadProgress] =
 		useState<DownloadProgressState>({});
+	const [showCustomForm, setShowCustomForm] = useState(false);
+	const [customFormError, setCustomFormError] = useState<string | null>(null);
+
+	const customProviderIds = useMemo(
+		() =>
+			new Set(
+				(modelsStoreData?.customLlmProviders ?? []).map(
+					(provider) => provider.id,
+				),
+			),
+		[modelsStoreData?.customLlmProviders],
+	);
 
 	const getLlmEntries = useCallback(
 		(modelId: string) =>
@@ -244,10 +255,14 @@ function RouteComponent() {
 		let unlisten: (() => void) | null = null;
 		const setupListener = async () => {
 			try {
-				unlisten = await listen<OfflineDownloadProgressEvent>(
-					"offline-model-download-progress",
+				unlisten = await listen<OnOfflineModelDownload>(
+					"on-offline-model-download",
 					(event) => {
-						const { modelId, receivedBytes, totalBytes } = event.payload;
+						const { modelId, stage, receivedBytes, totalBytes } =
+							event.payload;
+						if (stage !== "downloading") {
+							return;
+						}
 						setDownloadProgress((prev) => {
 							const next = { ...prev };
 							const percent =
@@ -288,6 +303,28 @@ function RouteComponent() {
 		},
 	});
 
+	const addCustomProviderMutation = useMutation({
+		mutationFn: (provider: CustomLlmProviderInput) =>
+			commands.addCustomLlmProvider(provider),
+		onSuccess: (data) => {
+			queryClient.setQueryData(["models-store"], data);
+			void queryClient.invalidateQueries({ queryKey: ["supported-models"] });
+			setShowCustomForm(false);
+			setCustomFormError(null);
+		},
+		onError: (error) => {
+			setCustomFormError(String(error));
+		},
+	});
+
+	const deleteCustomProviderMutation = useMutation({
+		mutationFn: (id: string) => commands.deleteCustomLlmProvider(id),
+		onSuccess: (data) => {
+			queryClient.setQueryData(["models-store"], data);
+			void queryClient.invalidateQueries({ queryKey: ["supported-models"] });
+		},
+	});
+
 	const handleModelChange = (value: string) => {
 		if (!value) {
 			return;
@@ -371,8 +408,7 @@ function RouteComponent() {
 				...prev,
 				[modelId]: {
 					variant: "error",
-					text:
-						FRIENDLY_ERROR_MESSAGES[modelId] ?? "调用 API 失败，API 密钥错误",
+					text: String(error),
 				},
 			}));
 		} finally {
@@ -494,18 +530,34 @@ function RouteComponent() {
 															</p>
 														</div>
 													</div>
-													<button
-														type="button"
-														className="text-xs text-muted-foreground transition hover:text-primary flex items-center gap-0.5"
-														onClick={(event) => {
-															event.preventDefault();
-															event.stopPropagation();
-															handleToggleTextForm(model.id);
-														}}
-													>
-														设置 API 密钥
-														<ChevronDown className="size-3" />
-													</button>
+													<div className="flex items-center gap-3">
+														{customProviderIds.has(model.id) && (
+															<button
+																type="button"
+																className="text-xs text-muted-foreground transition hover:text-destructive flex items-center gap-0.5"
+																onClick={(event) => {
+																	event.preventDefault();
+																	event.stopPropagation();
+																	deleteCustomProviderMutation.mutate(model.id);
+																}}
+															>
+																<Trash2 className="size-3" />
+																删除
+															</button>
+														)}
+														<button
+															type="button"
+															className="text-xs text-muted-foreground transition hover:text-primary flex items-center gap-0.5"
+															onClick={(event) => {
+																event.preventDefault();
+																event.stopPropagation();
+																handleToggleTextForm(model.id);
+															}}
+														>
+															设置 API 密钥
+															<ChevronDown className="size-3" />
+														</button>
+													</div>
 												</div>
 											}
 										>
@@ -580,7 +632,11 @@ function RouteComponent() {
 															size="sm"
 															disabled={
 																savingModel === model.id ||
-																!formValue.apiKey.trim()
+																(!formValue.apiKey.trim() &&
+																	!providers.find(
+																		(provider) =>
+																			provider.id === formValue.provider,
+																	)?.local)
 															}
 															onClick={() => void handleTestAndSave(model.id)}
 														>
@@ -596,6 +652,39 @@ function RouteComponent() {
 								})}
 							</RadioGroup>
 						)}
+						<div className="mt-4">
+							{showCustomForm ? (
+								<CustomLlmProviderForm
+									saving={addCustomProviderMutation.isPending}
+									error={customFormError}
+									onSubmit={(provider) =>
+										addCustomProviderMutation.mutate(provider)
+									}
+									onCancel={() => {
+										setShowCustomForm(false);
+										setCustomFormError(null);
+									}}
+								/>
+							) : (
+								<Button
+									type="button"
+									size="sm"
+									variant="outline"
+									onClick={() => setShowCustomForm(true)}
+								>
+									<Plus className="size-4" />
+									添加 OpenAI 兼容模型
+								</Button>
+							)}
+						</div>
+						{!textModelsEmpty && (
+							<div className="mt-4">
+								<LlmFallbackSettings
+									store={modelsStoreData}
+									llmModels={llmModels}
+								/>
+							</div>
+						)}
 					</TabsContent>
 					<TabsContent value="asr" className="space-y-4">
 						{asrModelsEmpty ? (
@@ -1,13 +1,36 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { createFileRoute } from "@tanstack/react-router";
-import { Keyboard, Notebook, Palette, Power } from "lucide-react";
+import {
+	Keyboard,
+	Languages,
+	Notebook,
+	Palette,
+	PencilLine,
+	Power,
+	Sparkles,
+	WandSparkles,
+} from "lucide-react";
 import { useEffect, useState } from "react";
 import { Switch } from "~/components/animate/switch";
 import { Dashboard } from "~/components/layouts/dashboard";
+import { ChineseConversionPanel } from "~/components/settings/chinese-conversion";
+import { ConversationContextPanel } from "~/components/settings/conversation-context";
 import { HotkeySetting } from "~/components/settings/hotkey";
+import { PiiMasking } from "~/components/settings/pii-masking";
+import { PromptTemplates } from "~/components/settings/prompt-templates";
+import { ReplacementRulesPanel } from "~/components/settings/replacement-rules";
 import { SettingRow } from "~/components/settings/row";
 import { ThemeToggle } from "~/components/settings/theme-toggle";
+import { TranslationSettingsPanel } from "~/components/settings/translation";
+import { TypographyPanel } from "~/components/settings/typography";
+import { VoiceCommandsPanel } from "~/components/settings/voice-commands";
 import { Card } from "~/components/ui/card";
-import { DEFAULT_DIARY_HOTKEY } from "~/constants/hotkeys";
+import { Select } from "~/components/ui/select";
+import {
+	DEFAULT_DIARY_HOTKEY,
+	DEFAULT_EDIT_HOTKEY,
+	DEFAULT_TRANSLATION_HOTKEY,
+} from "~/constants/hotkeys";
 import type { Hotkey, HotkeyAction } from "~/lib/tauri";
 import { commands } from "~/lib/tauri";
 import { hotkeysStore } from "~/store";
@@ -17,7 +40,49 @@ export const Route = createFileRoute("/(dashboard)/settings")({
 });
 
 function RouteComponent() {
+	const queryClient = useQueryClient();
 	const hotkeysQuery = hotkeysStore.useQuery();
+	const promptsQuery = useQuery({
+		queryKey: ["prompts-store"],
+		queryFn: () => commands.getPromptsStore(),
+	});
+	const templates = promptsQuery.data?.templates ?? [];
+
+	const setDefaultTemplateMutation = useMutation({
+		mutationFn: (id: string) => commands.setDefaultPromptTemplate(id),
+		onSuccess: (data) => queryClient.setQueryData(["prompts-store"], data),
+	});
+
+	const setHotkeyTemplateMutation = useMutation({
+		mutationFn: ({
+			action,
+			templateId,
+		}: {
+			action: HotkeyAction;
+			templateId: string | null;
+		}) => commands.setHotkeyPromptTemplate(action, templateId),
+		onSuccess: (data) => queryClient.setQueryData(["prompts-store"], data),
+	});
+
+	const hotkeyTemplateSelect = (action: HotkeyAction) => (
+		<Select
+			wrapperClassName="w-40"
+			value={promptsQuery.data?.hotkeyTemplates?.[action] ?? ""}
+			onChange={(event) =>
+				setHotkeyTemplateMutation.mutate({
+					action,
+					templateId: event.target.value || null,
+				})
+			}
+		>
+			<option value="">默认模式</option>
+			{templates.map((template) => (
+				<option key={template.id} value={template.id}>
+					{template.name}
+				</option>
+			))}
+		</Select>
+	);
 
 	// 开机启动
 	const [autostartEnabled, setAutostartEnabled] = useState(false);
@@ -128,6 +193,102 @@ function RouteComponent() {
 				/>
 			),
 		},
+		{
+			id: "translation",
+			title: "翻译输入",
+			description: "说出的内容翻译为目标语言后输入。",
+			icon: <Languages className="size-4" />,
+			action: (
+				<HotkeySetting
+					currentHotkey={hotkeysQuery.data?.hotkeys?.startTranslating}
+					defaultHotkey={DEFAULT_TRANSLATION_HOTKEY}
+					onUpdate={async (hotkey) => {
+						const nextHotkeys: Partial<Record<HotkeyAction, Hotkey>> = {
+							...(hotkeysQuery.data?.hotkeys ?? {}),
+						};
+
+						if (hotkey) {
+							nextHotkeys.startTranslating = hotkey;
+						} else {
+							delete nextHotkeys.startTranslating;
+						}
+
+						await hotkeysQuery.set({
+							hotkeys: nextHotkeys,
+						});
+
+						await commands.setHotkey("startTranslating", hotkey);
+					}}
+				/>
+			),
+		},
+		{
+			id: "edit-selection",
+			title: "语音编辑",
+			description: "先选中文字，再说出修改要求，结果会替换选中的内容。",
+			icon: <PencilLine className="size-4" />,
+			action: (
+				<HotkeySetting
+					currentHotkey={hotkeysQuery.data?.hotkeys?.editSelection}
+					defaultHotkey={DEFAULT_EDIT_HOTKEY}
+					onUpdate={async (hotkey) => {
+						const nextHotkeys: Partial<Record<HotkeyAction, Hotkey>> = {
+							...(hotkeysQuery.data?.hotkeys ?? {}),
+						};
+
+						if (hotkey) {
+							nextHotkeys.editSelection = hotkey;
+						} else {
+							delete nextHotkeys.editSelection;
+						}
+
+						await hotkeysQuery.set({
+							hotkeys: nextHotkeys,
+						});
+
+						await commands.setHotkey("editSelection", hotkey);
+					}}
+				/>
+			),
+		},
+	];
+
+	const promptRows = [
+		{
+			id: "default-template",
+			title: "默认润色模式",
+			description: "未单独指定时，语音识别结果使用此模式润色。",
+			icon: <Sparkles className="size-4" />,
+			action: (
+				<Select
+					wrapperClassName="w-40"
+					value={promptsQuery.data?.defaultTemplate ?? "polish"}
+					onChange={(event) =>
+						setDefaultTemplateMutation.mutate(event.target.value)
+					}
+				>
+					{templates.map((template) => (
+						<option key={template.id} value={template.id}>
+							{template.name}
+						</option>
+					))}
+				</Select>
+			),
+		},
+		{
+			id: "dictation-template",
+			title: "语音识别快捷键",
+			description: "通过语音识别快捷键录音时使用的润色模式。",
+			icon: <WandSparkles className="size-4" />,
+			action: hotkeyTemplateSelect("startDictating"),
+		},
+		{
+			id: "diary-template",
+			title: "语音日记快捷键",
+			description: "通过语音日记快捷键录音时使用的润色模式。",
+			icon: <WandSparkles className="size-4" />,
+			action: hotkeyTemplateSelect("startVoiceDiary"),
+		},
 	];
 
 	return (
@@ -173,6 +334,64 @@ function RouteComponent() {
 							</ul>
 						</Card>
 					</section>
+
+					<section className="space-y-2">
+						<h2 className="text-base font-medium">润色模式</h2>
+						<Card className="px-4 py-0 gap-0">
+							<ul className="divide-y divide-border py-4">
+								{promptRows.map((row) => (
+									<li key={row.id} className="py-4 first:pt-0 last:pb-0">
+										<SettingRow
+											title={row.title}
+											description={row.description}
+											action={row.action}
+											icon={row.icon}
+										/>
+									</li>
+								))}
+							</ul>
+						</Card>
+						<Card className="px-4 py-4 gap-0">
+							<PromptTemplates />
+						</Card>
+						<Card className="px-4 py-4 gap-0">
+							<ConversationContextPanel />
+						</Card>
+						<Card className="px-4 py-4 gap-0">
+							<VoiceCommandsPanel />
+						</Card>
+					</section>
+
+					<section className="space-y-2">
+						<h2 className="text-base font-medium">翻译</h2>
+						<Card className="px-4 py-4 gap-0">
+							<TranslationSettingsPanel />
+						</Card>
+					</section>
+
+					<section className="space-y-2">
+						<h2 className="text-base font-medium">文本替换</h2>
+						<Card className="px-4 py-4 gap-0">
+							<ReplacementRulesPanel />
+						</Card>
+					</section>
+
+					<section className="space-y-2">
+						<h2 className="text-base font-medium">输出格式</h2>
+						<Card className="px-4 py-4 gap-0">
+							<TypographyPanel />
+						</Card>
+						<Card className="px-4 py-4 gap-0">
+							<ChineseConversionPanel />
+						</Card>
+					</section>
+
+					<section className="space-y-2">
+						<h2 className="text-base font-medium">隐私</h2>
+						<Card className="px-4 py-4 gap-0">
+							<PiiMasking />
+						</Card>
+					</section>
 				</div>
 			</div>
 		</Dashboard>
@@ -0,0 +1,199 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { createFileRoute } from "@tanstack/react-router";
+import { listen } from "@tauri-apps/api/event";
+import { CalendarClock, Circle, CircleCheck, Download } from "lucide-react";
+import { useEffect, useState } from "react";
+import { Switch } from "~/components/animate/switch";
+import { Dashboard } from "~/components/layouts/dashboard";
+import { Button } from "~/components/ui/button";
+import { Label } from "~/components/ui/label";
+import { ScrollArea } from "~/components/ui/scroll-area";
+import { commands, type OnTasksExtracted, type TaskItem } from "~/lib/tauri";
+
+export const Route = createFileRoute("/(dashboard)/tasks")({
+	component: RouteComponent,
+});
+
+const formatDue = (task: TaskItem) => {
+	if (!task.dueAt) {
+		return null;
+	}
+	const due = new Date(task.dueAt);
+	const date = due.toLocaleDateString("zh-CN", {
+		month: "numeric",
+		day: "numeric",
+		weekday: "short",
+	});
+	if (task.allDay) {
+		return date;
+	}
+	const time = due.toLocaleTimeString("zh-CN", {
+		hour: "2-digit",
+		minute: "2-digit",
+	});
+	return `${date} ${time}`;
+};
+
+const isOverdue = (task: TaskItem) => {
+	if (!task.dueAt || task.done) {
+		return false;
+	}
+	const due = new Date(task.dueAt);
+	if (task.allDay) {
+		due.setDate(due.getDate() + 1);
+	}
+	return due.getTime() < Date.now();
+};
+
+function RouteComponent() {
+	const queryClient = useQueryClient();
+	const [includeDone, setIncludeDone] = useState(false);
+	const tasksQuery = useQuery({
+		queryKey: ["tasks", includeDone],
+		queryFn: () => commands.listTasks({ includeDone }),
+	});
+	const doneMutation = useMutation({
+		mutationFn: ({ id, done }: { id: string; done: boolean }) =>
+			commands.setTaskDone(id, done),
+		onSuccess: async () => {
+			await queryClient.invalidateQueries({ queryKey: ["tasks"] });
+		},
+		onError: async (error) => {
+			await commands.showNotification(
+				`更新待办失败: ${String(error)}`,
+				"error",
+				null,
+			);
+		},
+	});
+	const exportMutation = useMutation({
+		mutationFn: () => commands.exportTasksIcs(includeDone),
+		onSuccess: async (path) => {
+			await commands.showNotification(`已导出到 ${path}`, "info", null);
+		},
+		onError: async (error) => {
+			await commands.showNotification(
+				`导出失败: ${String(error)}`,
+				"error",
+				null,
+			);
+		},
+	});
+
+	useEffect(() => {
+		let unlisten: (() => void) | null = null;
+		const setupListener = async () => {
+			try {
+				unlisten = await listen<OnTasksExtracted>("on-tasks-extracted", () => {
+					void queryClient.invalidateQueries({ queryKey: ["tasks"] });
+				});
+			} catch (error) {
+				console.error(error);
+			}
+		};
+		void setupListener();
+		return () => {
+			unlisten?.();
+		};
+	}, [queryClient]);
+
+	const tasks = tasksQuery.data ?? [];
+
+	const renderTasks = () => {
+		if (tasksQuery.isLoading) {
+			return (
+				<div className="rounded-2xl border border-dashed border-border/60 bg-background/40 p-8 text-center">
+					<p className="text-sm font-medium">待办加载中...</p>
+				</div>
+			);
+		}
+		if (tasks.length === 0) {
+			return (
+				<div className="rounded-2xl border border-dashed border-border/60 bg-background/40 p-8 text-center">
+					<p className="text-sm font-medium">暂无待办</p>
+					<p className="mt-3 text-xs text-muted-foreground">
+						在语音日记中说出“明天下午三点开会”“记得交报告”等安排，会自动整理到这里。
+					</p>
+				</div>
+			);
+		}
+		return tasks.map((task) => {
+			const due = formatDue(task);
+			return (
+				<div
+					key={task.id}
+					className="flex items-center gap-3 rounded-xl border border-border/60 bg-background/60 px-4 py-3"
+				>
+					<Button
+						variant="text"
+						size="xs"
+						className="text-muted-foreground hover:text-foreground"
+						disabled={doneMutation.isPending}
+						onClick={() =>
+							doneMutation.mutate({ id: task.id, done: !task.done })
+						}
+					>
+						{task.done ? (
+							<CircleCheck className="size-4 text-primary" />
+						) : (
+							<Circle className="size-4" />
+						)}
+					</Button>
+					<p
+						className={`flex-1 text-sm ${task.done ? "text-muted-foreground line-through" : ""}`}
+					>
+						{task.title}
+					</p>
+					{due && (
+						<span
+							className={`flex items-center gap-1 text-xs ${isOverdue(task) ? "text-destructive" : "text-muted-foreground"}`}
+						>
+							<CalendarClock className="size-3" />
+							{due}
+						</span>
+					)}
+				</div>
+			);
+		});
+	};
+
+	return (
+		<Dashboard>
+			<div className="flex h-full flex-col gap-6 overflow-hidden py-6">
+				<div className="flex flex-wrap items-end justify-between gap-4">
+					<div className="space-y-1">
+						<h1 className="text-2xl font-semibold">待办</h1>
+						<p className="text-sm text-muted-foreground">
+							从语音日记中整理出的待办事项与日程安排。
+						</p>
+					</div>
+					<div className="flex flex-wrap items-center gap-4">
+						<div className="flex items-center gap-2">
+							<Switch
+								id="tasks-include-done"
+								checked={includeDone}
+								onCheckedChange={setIncludeDone}
+							/>
+							<Label htmlFor="tasks-include-done" className="text-xs">
+								显示已完成
+							</Label>
+						</div>
+						<Button
+							variant="outline"
+							size="sm"
+							className="gap-1"
+							disabled={exportMutation.isPending || tasks.length === 0}
+							onClick={() => exportMutation.mutate()}
+						>
+							<Download className="size-4" />
+							导出日历
+						</Button>
+					</div>
+				</div>
+				<ScrollArea className="h-full w-full pr-4">
+					<div className="mb-15 flex flex-col gap-2">{renderTasks()}</div>
+				</ScrollArea>
+			</div>
+		</Dashboard>
+	);
+}
@@ -25,16 +25,15 @@ import {
 	useOpenPermissionSettings,
 	useRequestPermission,
 } from "~/hooks/use-permissions";
-import { commands, type Hotkey, type OSPermission } from "~/lib/tauri";
+import {
+	commands,
+	type Hotkey,
+	type OnOfflineModelDownload,
+	type OSPermission,
+} from "~/lib/tauri";
 import { cn } from "~/lib/utils";
 import { hotkeysStore } from "~/store";
 
-type OfflineDownloadProgressEvent = {
-	modelId: string;
-	receivedBytes: number;
-	totalBytes: number | null;
-};
-
 const PERMISSIONS: Array<{
 	key: OSPermission;
 	title: string;
@@ -128,10 +127,13 @@ function RouteComponent() {
 
 	useEffect(() => {
 		let unlisten: (() => void) | null = null;
-		listen<OfflineDownloadProgressEvent>(
-			"offline-model-download-progress",
+		listen<OnOfflineModelDownload>(
+			"on-offline-model-download",
 			(event) => {
-				if (event.payload.modelId !== PARAFORMER_MODEL_ID) {
+				if (
+					event.payload.modelId !== PARAFORMER_MODEL_ID ||
+					event.payload.stage !== "downloading"
+				) {
 					return;
 				}
 				setDownloadState({
@@ -2,6 +2,9 @@ import { createFileRoute } from "@tanstack/react-router";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { useEffect, useState } from "react";
 import { MovingLabel } from "~/components/moving-border";
+import type { OnPolishPreview } from "~/lib/tauri";
+
+const PREVIEW_TAIL_LENGTH = 6;
 
 export const Route = createFileRoute("/transcribing")({
 	component: RouteComponent,
@@ -9,27 +12,44 @@ export const Route = createFileRoute("/transcribing")({
 
 function RouteComponent() {
 	const [stage, setStage] = useState<"asr" | "polishing">("asr");
+	const [preview, setPreview] = useState("");
 
 	useEffect(() => {
 		let unlisten: UnlistenFn | undefined;
+		let unlistenPreview: UnlistenFn | undefined;
 
 		listen<{ stage?: string }>("on-transcribing-stage", (event) => {
 			const nextStage = event.payload?.stage;
 			if (nextStage === "asr" || nextStage === "polishing") {
 				setStage(nextStage);
+				setPreview("");
 			}
 		}).then((fn) => {
 			unlisten = fn;
 		});
 
+		listen<OnPolishPreview>("on-polish-preview", (event) => {
+			setPreview(event.payload.text);
+		}).then((fn) => {
+			unlistenPreview = fn;
+		});
+
 		return () => {
 			if (unlisten) {
 				unlisten();
 			}
+			if (unlistenPreview) {
+				unlistenPreview();
+			}
 		};
 	}, []);
 
-	const label = stage === "polishing" ? "AI 润色中" : "转录中";
+	// 窗口很窄，只展示最新生成的几个字
+	const previewTail = preview
+		.replace(/\s+/g, " ")
+		.trim()
+		.slice(-PREVIEW_TAIL_LENGTH);
+	const label = stage === "polishing" ? previewTail || "AI 润色中" : "转录中";
 	const dots = Array.from({ length: 4 }, (_, i) => i);
 
 	return (